@@ -6,6 +6,7 @@ mod contract_code;
 mod implicit_account;
 mod stake_proposal;
 mod sub_account;
+mod sub_accounts;
 
 /// инструмент выбора to add action
 #[derive(Debug, Default, Clone, clap::Clap)]
@@ -70,6 +71,8 @@ pub enum CliAction {
     StakeProposal(self::stake_proposal::operation_mode::CliOperationMode),
     /// Add a new sub-account
     SubAccount(self::sub_account::operation_mode::CliOperationMode),
+    /// Add many sub-accounts listed in a CSV file
+    SubAccounts(self::sub_accounts::operation_mode::CliOperationMode),
     /// Add a new access key for an account
     AccessKey(self::access_key::operation_mode::CliOperationMode),
 }
@@ -87,6 +90,8 @@ pub enum Action {
     StakeProposal(self::stake_proposal::operation_mode::OperationMode),
     #[strum_discriminants(strum(message = "Add a new sub-account"))]
     SubAccount(self::sub_account::operation_mode::OperationMode),
+    #[strum_discriminants(strum(message = "Add many sub-accounts from a CSV file"))]
+    SubAccounts(self::sub_accounts::operation_mode::OperationMode),
 }
 
 impl CliAction {
@@ -117,6 +122,11 @@ impl CliAction {
                 command.push_front("sub-account".to_owned());
                 command
             }
+            Self::SubAccounts(subcommand) => {
+                let mut command = subcommand.to_cli_args();
+                command.push_front("sub-accounts".to_owned());
+                command
+            }
         }
     }
 }
@@ -131,6 +141,7 @@ impl From<Action> for CliAction {
             }
             Action::StakeProposal(operation_mode) => Self::StakeProposal(operation_mode.into()),
             Action::SubAccount(operation_mode) => Self::SubAccount(operation_mode.into()),
+            Action::SubAccounts(operation_mode) => Self::SubAccounts(operation_mode.into()),
         }
     }
 }
@@ -155,6 +166,9 @@ impl Action {
             CliAction::SubAccount(cli_operation_mode) => Ok(Action::SubAccount(
                 self::sub_account::operation_mode::OperationMode::from(cli_operation_mode).unwrap(),
             )),
+            CliAction::SubAccounts(cli_operation_mode) => Ok(Action::SubAccounts(
+                self::sub_accounts::operation_mode::OperationMode::from(cli_operation_mode)?,
+            )),
         }
     }
 }
@@ -179,6 +193,7 @@ impl Action {
             ActionDiscriminants::ImplicitAccount => CliAction::ImplicitAccount(Default::default()),
             ActionDiscriminants::StakeProposal => CliAction::StakeProposal(Default::default()),
             ActionDiscriminants::SubAccount => CliAction::SubAccount(Default::default()),
+            ActionDiscriminants::SubAccounts => CliAction::SubAccounts(Default::default()),
         };
         Ok(Self::from(cli_action)?)
     }
@@ -209,6 +224,7 @@ impl Action {
                     .process(prepopulated_unsigned_transaction)
                     .await
             }
+            Action::SubAccounts(operation_mode) => operation_mode.process().await,
         }
     }
 }
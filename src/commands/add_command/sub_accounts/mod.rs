@@ -0,0 +1,651 @@
+use std::io::Write;
+use std::str::FromStr;
+
+use dialoguer::{Confirm, Input};
+
+pub mod operation_mode;
+
+/// Create many sub-accounts listed in a CSV file
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliSubAccounts {
+    /// CSV file with account_id,deposit[,public_key] lines, e.g. "bob.alice.testnet,1 NEAR"
+    /// (a header line is skipped); a key is generated for the rows without a public key
+    csv: Option<std::path::PathBuf>,
+    /// The account that creates the sub-accounts and pays the deposits
+    #[clap(long)]
+    owner_account_id: Option<near_primitives::types::AccountId>,
+    /// Access key of the owner in the keychain [default: the first full access key]
+    #[clap(long)]
+    signer_public_key: Option<near_crypto::PublicKey>,
+    /// Save the generated keys to this JSON Lines file instead of the keychain
+    #[clap(long)]
+    keys_output: Option<std::path::PathBuf>,
+    /// Report of the created accounts [default: <csv>.report.csv]
+    #[clap(long)]
+    report: Option<std::path::PathBuf>,
+    /// Create the accounts without asking for a confirmation
+    #[clap(long)]
+    yes: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubAccounts {
+    owner_account_id: near_primitives::types::AccountId,
+    signer_public_key: Option<near_crypto::PublicKey>,
+    csv: std::path::PathBuf,
+    keys_output: Option<std::path::PathBuf>,
+    report: std::path::PathBuf,
+    yes: bool,
+    new_accounts: Vec<NewAccount>,
+    existing_accounts: Vec<NewAccount>,
+}
+
+const REPORT_HEADER: &str = "line,account_id,status,transaction_hash,public_key,error";
+
+#[derive(Debug, Clone)]
+struct NewAccount {
+    line: usize,
+    account_id: near_primitives::types::AccountId,
+    deposit: crate::common::NearBalance,
+    public_key: Option<near_crypto::PublicKey>,
+}
+
+impl CliSubAccounts {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        if self.yes {
+            args.push_front("--yes".to_owned());
+        }
+        if let Some(report) = &self.report {
+            args.push_front(report.display().to_string());
+            args.push_front("--report".to_owned());
+        }
+        if let Some(keys_output) = &self.keys_output {
+            args.push_front(keys_output.display().to_string());
+            args.push_front("--keys-output".to_owned());
+        }
+        if let Some(signer_public_key) = &self.signer_public_key {
+            args.push_front(signer_public_key.to_string());
+            args.push_front("--signer-public-key".to_owned());
+        }
+        if let Some(owner_account_id) = &self.owner_account_id {
+            args.push_front(owner_account_id.to_string());
+            args.push_front("--owner-account-id".to_owned());
+        }
+        if let Some(csv) = &self.csv {
+            args.push_front(csv.display().to_string());
+        }
+        args
+    }
+}
+
+impl From<SubAccounts> for CliSubAccounts {
+    fn from(sub_accounts: SubAccounts) -> Self {
+        Self {
+            csv: Some(sub_accounts.csv),
+            owner_account_id: Some(sub_accounts.owner_account_id),
+            signer_public_key: sub_accounts.signer_public_key,
+            keys_output: sub_accounts.keys_output,
+            report: Some(sub_accounts.report),
+            yes: sub_accounts.yes,
+        }
+    }
+}
+
+impl SubAccounts {
+    pub fn from(
+        item: CliSubAccounts,
+        connection_config: crate::common::ConnectionConfig,
+    ) -> color_eyre::eyre::Result<Self> {
+        let csv = match item.csv {
+            Some(csv) => csv,
+            None => Input::<String>::new()
+                .with_prompt(
+                    "Enter the path to the CSV file with account_id,deposit[,public_key] lines",
+                )
+                .interact_text()
+                .unwrap()
+                .into(),
+        };
+        let owner_account_id: near_primitives::types::AccountId = match item.owner_account_id {
+            Some(owner_account_id) => owner_account_id,
+            None => Input::new()
+                .with_prompt("What is the account ID of the owner of the sub-accounts?")
+                .interact_text()
+                .unwrap(),
+        };
+        let report = item.report.unwrap_or_else(|| {
+            let mut report = csv.clone().into_os_string();
+            report.push(".report.csv");
+            report.into()
+        });
+
+        let accounts = read_new_accounts(&csv, &owner_account_id)?;
+        let accounts_count = accounts.len();
+        let mut new_accounts = vec![];
+        let mut existing_accounts = vec![];
+        for (index, account) in accounts.into_iter().enumerate() {
+            println!(
+                "[{}/{}] Checking <{}> ...",
+                index + 1,
+                accounts_count,
+                account.account_id
+            );
+            match crate::common::check_account_id(
+                connection_config.clone(),
+                account.account_id.clone(),
+            )? {
+                Some(_) => existing_accounts.push(account),
+                None => new_accounts.push(account),
+            }
+        }
+        let total_deposit = new_accounts
+            .iter()
+            .map(|account| account.deposit.to_yoctonear())
+            .sum::<u128>();
+        println!(
+            "\n{} sub-accounts of <{}> on {}: {} to create, {} already exist and are skipped",
+            new_accounts.len() + existing_accounts.len(),
+            owner_account_id,
+            connection_config.rpc_url(),
+            new_accounts.len(),
+            existing_accounts.len()
+        );
+        println!(
+            "Total initial deposit: {}",
+            crate::common::NearBalance::from_yoctonear(total_deposit)
+        );
+        if item.yes {
+            crate::common::check_signing_policy_without_prompts(&connection_config)?;
+        }
+        if !new_accounts.is_empty()
+            && !item.yes
+            && !Confirm::new()
+                .with_prompt("Do you want to create these accounts?")
+                .default(false)
+                .interact()?
+        {
+            return Err(color_eyre::Report::msg(
+                "Creating the sub-accounts is canceled",
+            ));
+        }
+        Ok(Self {
+            owner_account_id,
+            signer_public_key: item.signer_public_key,
+            csv,
+            keys_output: item.keys_output,
+            report,
+            yes: item.yes,
+            new_accounts,
+            existing_accounts,
+        })
+    }
+}
+
+fn read_new_accounts(
+    csv: &std::path::Path,
+    owner_account_id: &near_primitives::types::AccountId,
+) -> color_eyre::eyre::Result<Vec<NewAccount>> {
+    let owner_suffix = format!(".{}", owner_account_id);
+    let mut accounts: Vec<NewAccount> = vec![];
+    for csv_line in crate::common::read_csv_lines(csv, "deposit")? {
+        let line = csv_line.line;
+        let columns = csv_line
+            .columns
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        let (account_id, deposit, public_key) = match columns.as_slice() {
+            [account_id, deposit] => (account_id, deposit, None),
+            [account_id, deposit, ""] => (account_id, deposit, None),
+            [account_id, deposit, public_key] => (account_id, deposit, Some(public_key)),
+            _ => {
+                return Err(color_eyre::Report::msg(format!(
+                    "Line {} of {} has to be account_id,deposit[,public_key]",
+                    line,
+                    csv.display()
+                )))
+            }
+        };
+        let account_id: near_primitives::types::AccountId = account_id.parse().map_err(|err| {
+            color_eyre::Report::msg(format!(
+                "Line {}: invalid account ID <{}>: {}",
+                line, account_id, err
+            ))
+        })?;
+        let sub_account_name = account_id
+            .to_string()
+            .strip_suffix(owner_suffix.as_str())
+            .unwrap_or_default()
+            .to_owned();
+        if sub_account_name.is_empty() || sub_account_name.contains('.') {
+            return Err(color_eyre::Report::msg(format!(
+                "Line {}: <{}> is not a direct sub-account of <{}>",
+                line, account_id, owner_account_id
+            )));
+        }
+        if accounts
+            .iter()
+            .any(|account| account.account_id == account_id)
+        {
+            return Err(color_eyre::Report::msg(format!(
+                "Line {}: <{}> is listed more than once",
+                line, account_id
+            )));
+        }
+        accounts.push(NewAccount {
+            line,
+            account_id,
+            deposit: crate::common::NearBalance::from_str(deposit)
+                .map_err(|err| color_eyre::Report::msg(format!("Line {}: {}", line, err)))?,
+            public_key: match public_key {
+                Some(public_key) => {
+                    Some(near_crypto::PublicKey::from_str(public_key).map_err(|err| {
+                        color_eyre::Report::msg(format!(
+                            "Line {}: invalid public key <{}>: {}",
+                            line, public_key, err
+                        ))
+                    })?)
+                }
+                None => None,
+            },
+        });
+    }
+    if accounts.is_empty() {
+        return Err(color_eyre::Report::msg(format!(
+            "There are no accounts in {}",
+            csv.display()
+        )));
+    }
+    Ok(accounts)
+}
+
+/// Returns the last key saved for the account in a keys output file, if any.
+fn read_saved_public_key(
+    keys_output: &std::path::Path,
+    account_id: &near_primitives::types::AccountId,
+) -> Option<near_crypto::PublicKey> {
+    std::fs::read_to_string(keys_output)
+        .ok()?
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|saved_key| {
+            saved_key["account_id"].as_str() == Some(account_id.to_string().as_str())
+        })
+        .filter_map(|saved_key| saved_key["public_key"].as_str()?.parse().ok())
+        .last()
+}
+
+impl SubAccounts {
+    /// Generates a key for the new account and saves it to the keys output file or the keychain
+    /// before the account is created, so the key is never lost. A key saved for the account by a
+    /// previous run is reused instead.
+    async fn generate_public_key(
+        &self,
+        connection_config: &crate::common::ConnectionConfig,
+        account_id: &near_primitives::types::AccountId,
+    ) -> color_eyre::eyre::Result<near_crypto::PublicKey> {
+        let saved_public_key = match &self.keys_output {
+            Some(keys_output) => read_saved_public_key(keys_output, account_id),
+            None => {
+                crate::common::read_keychain_access_keys(connection_config.dir_name(), account_id)
+                    .into_iter()
+                    .next()
+                    .map(|(_, keychain_access_key)| keychain_access_key.public_key)
+            }
+        };
+        if let Some(public_key) = saved_public_key {
+            println!(
+                "Using the key {} saved for <{}> by a previous run",
+                public_key, account_id
+            );
+            return Ok(public_key);
+        }
+        let key_pair_properties =
+            crate::common::generate_keypair(crate::common::KeyType::Ed25519, "").await?;
+        match &self.keys_output {
+            Some(keys_output) => {
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(keys_output)
+                    .map_err(|err| {
+                        color_eyre::Report::msg(format!(
+                            "Failed to open {}: {}",
+                            keys_output.display(),
+                            err
+                        ))
+                    })?;
+                writeln!(
+                    file,
+                    "{}",
+                    serde_json::json!({
+                        "master_seed_phrase": key_pair_properties.master_seed_phrase,
+                        "seed_phrase_hd_path": key_pair_properties.seed_phrase_hd_path.to_string(),
+                        "account_id": account_id,
+                        "public_key": key_pair_properties.public_key_str,
+                        "private_key": key_pair_properties.secret_keypair_str,
+                    })
+                )?;
+                file.sync_data()?;
+            }
+            None => {
+                crate::common::save_access_key_to_keychain(
+                    Some(connection_config.clone()),
+                    key_pair_properties.clone(),
+                    &account_id.to_string(),
+                )
+                .await?
+            }
+        }
+        Ok(near_crypto::PublicKey::from_str(
+            &key_pair_properties.public_key_str,
+        )?)
+    }
+
+    pub async fn process(
+        self,
+        connection_config: crate::common::ConnectionConfig,
+    ) -> crate::CliResult {
+        let is_new_report = std::fs::metadata(&self.report)
+            .map(|metadata| metadata.len() == 0)
+            .unwrap_or(true);
+        let mut report = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.report)
+            .map_err(|err| {
+                color_eyre::Report::msg(format!(
+                    "Failed to open the report {}: {}",
+                    self.report.display(),
+                    err
+                ))
+            })?;
+        if is_new_report {
+            writeln!(report, "{}", REPORT_HEADER)?;
+        }
+        for account in self.existing_accounts.iter() {
+            write_report_row(&mut report, account, "exists", "", None, "")?;
+        }
+        let mut created = 0;
+        let mut failed = 0;
+        if !self.new_accounts.is_empty() {
+            let signer_public_key = match &self.signer_public_key {
+                Some(signer_public_key) => signer_public_key.clone(),
+                None => {
+                    crate::common::find_full_access_keychain_key(
+                        &connection_config,
+                        &self.owner_account_id,
+                    )
+                    .await?
+                }
+            };
+            let signer = crate::common::KeychainSigner {
+                dir_name: connection_config.dir_name().to_owned(),
+                account_id: self.owner_account_id.clone(),
+                public_key: signer_public_key.clone(),
+            };
+            for (index, account) in self.new_accounts.iter().enumerate() {
+                println!(
+                    "\n[{}/{}] Creating <{}> with {} ...",
+                    index + 1,
+                    self.new_accounts.len(),
+                    account.account_id,
+                    account.deposit
+                );
+                let result = match &account.public_key {
+                    Some(public_key) => Ok(public_key.clone()),
+                    None => {
+                        self.generate_public_key(&connection_config, &account.account_id)
+                            .await
+                    }
+                };
+                let (status, transaction_hash, public_key, error) = match result {
+                    Ok(public_key) => {
+                        let result = crate::commands::construct_transaction_command::sign_transaction::sign_and_submit(
+                            near_primitives::transaction::Transaction {
+                                signer_id: self.owner_account_id.clone(),
+                                public_key: signer_public_key.clone(),
+                                nonce: 0,
+                                receiver_id: account.account_id.clone(),
+                                block_hash: Default::default(),
+                                actions: vec![
+                                    near_primitives::transaction::Action::CreateAccount(
+                                        near_primitives::transaction::CreateAccountAction {},
+                                    ),
+                                    near_primitives::transaction::Action::Transfer(
+                                        near_primitives::transaction::TransferAction {
+                                            deposit: account.deposit.to_yoctonear(),
+                                        },
+                                    ),
+                                    near_primitives::transaction::Action::AddKey(
+                                        near_primitives::transaction::AddKeyAction {
+                                            public_key: public_key.clone(),
+                                            access_key: near_primitives::account::AccessKey {
+                                                nonce: 0,
+                                                permission: near_primitives::account::AccessKeyPermission::FullAccess,
+                                            },
+                                        },
+                                    ),
+                                ],
+                            },
+                            signer_public_key.clone(),
+                            Some(connection_config.clone()),
+                            &signer,
+                            crate::commands::construct_transaction_command::sign_transaction::SubmitOptions {
+                                submit: Some(crate::commands::construct_transaction_command::sign_transaction::Submit::Send),
+                                ..Default::default()
+                            },
+                        )
+                        .await;
+                        match result {
+                            Ok(Some(transaction_info)) => {
+                                let transaction_hash =
+                                    transaction_info.transaction_outcome.id.to_string();
+                                match transaction_info.status {
+                                    near_primitives::views::FinalExecutionStatus::SuccessValue(
+                                        _,
+                                    ) => (
+                                        "created",
+                                        transaction_hash,
+                                        Some(public_key),
+                                        String::new(),
+                                    ),
+                                    near_primitives::views::FinalExecutionStatus::Failure(
+                                        tx_execution_error,
+                                    ) => (
+                                        "failure",
+                                        transaction_hash,
+                                        Some(public_key),
+                                        format!("{:?}", tx_execution_error),
+                                    ),
+                                    near_primitives::views::FinalExecutionStatus::NotStarted
+                                    | near_primitives::views::FinalExecutionStatus::Started => (
+                                        "pending",
+                                        transaction_hash,
+                                        Some(public_key),
+                                        String::new(),
+                                    ),
+                                }
+                            }
+                            Ok(None) => (
+                                "error",
+                                String::new(),
+                                Some(public_key),
+                                "The transaction was not accepted by the node".to_owned(),
+                            ),
+                            Err(err) => ("error", String::new(), Some(public_key), err.to_string()),
+                        }
+                    }
+                    Err(err) => ("error", String::new(), None, err.to_string()),
+                };
+                println!("<{}>: {}", account.account_id, status);
+                match status {
+                    "created" => created += 1,
+                    "failure" | "error" => failed += 1,
+                    _ => {}
+                }
+                write_report_row(
+                    &mut report,
+                    account,
+                    status,
+                    &transaction_hash,
+                    public_key.as_ref(),
+                    &error,
+                )?;
+            }
+        }
+        println!(
+            "\nCreated: {}, skipped as existing: {}, failed: {}. The report is saved in a file {}",
+            created,
+            self.existing_accounts.len(),
+            failed,
+            self.report.display()
+        );
+        Ok(())
+    }
+}
+
+fn write_report_row(
+    report: &mut std::fs::File,
+    account: &NewAccount,
+    status: &str,
+    transaction_hash: &str,
+    public_key: Option<&near_crypto::PublicKey>,
+    error: &str,
+) -> crate::CliResult {
+    writeln!(
+        report,
+        "{},{},{},{},{},{}",
+        account.line,
+        account.account_id,
+        status,
+        transaction_hash,
+        public_key
+            .map(|public_key| public_key.to_string())
+            .unwrap_or_default(),
+        error.replace(|c| c == ',' || c == '\n', " ")
+    )?;
+    report.sync_data()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_error(name: &str, data: &str) -> String {
+        let csv = crate::common::write_temp_csv(name, data);
+        let error = read_new_accounts(&csv, &"alice.testnet".parse().unwrap())
+            .unwrap_err()
+            .to_string();
+        std::fs::remove_file(csv).unwrap();
+        error
+    }
+
+    #[test]
+    fn read_new_accounts_skips_the_header_comments_and_blank_lines() {
+        let public_key =
+            near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, "bob").public_key();
+        let csv = crate::common::write_temp_csv(
+            "accounts.csv",
+            &format!(
+                "account_id,deposit,public_key\n# team\nbob.alice.testnet, 1 NEAR, {}\n\n\
+                carol.alice.testnet,0.5 NEAR,\n",
+                public_key
+            ),
+        );
+        let accounts = read_new_accounts(&csv, &"alice.testnet".parse().unwrap()).unwrap();
+        assert_eq!(
+            accounts
+                .iter()
+                .map(|account| (
+                    account.line,
+                    account.account_id.to_string(),
+                    account.deposit.to_yoctonear(),
+                    account.public_key.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    3,
+                    "bob.alice.testnet".to_owned(),
+                    10u128.pow(24),
+                    Some(public_key)
+                ),
+                (
+                    5,
+                    "carol.alice.testnet".to_owned(),
+                    5 * 10u128.pow(23),
+                    None
+                ),
+            ]
+        );
+        std::fs::remove_file(csv).unwrap();
+    }
+
+    #[test]
+    fn read_new_accounts_rejects_invalid_lines() {
+        assert!(read_error("malformed.csv", "bob.alice.testnet\n").starts_with("Line 1 of "));
+        assert_eq!(
+            read_error("not-sub-account.csv", "bob.carol.alice.testnet,1 NEAR\n"),
+            "Line 1: <bob.carol.alice.testnet> is not a direct sub-account of <alice.testnet>"
+        );
+        assert_eq!(
+            read_error("other-owner.csv", "bob.testnet,1 NEAR\n"),
+            "Line 1: <bob.testnet> is not a direct sub-account of <alice.testnet>"
+        );
+        assert_eq!(
+            read_error(
+                "duplicate.csv",
+                "bob.alice.testnet,1 NEAR\nbob.alice.testnet,2 NEAR\n"
+            ),
+            "Line 2: <bob.alice.testnet> is listed more than once"
+        );
+        assert!(
+            read_error("invalid-deposit.csv", "bob.alice.testnet,one NEAR\n")
+                .starts_with("Line 1: ")
+        );
+        assert!(read_error(
+            "invalid-public-key.csv",
+            "bob.alice.testnet,1 NEAR,ed25519:xyz\n"
+        )
+        .starts_with("Line 1: invalid public key <ed25519:xyz>"));
+        assert!(read_error("empty.csv", "account_id,deposit\n")
+            .starts_with("There are no accounts in "));
+    }
+
+    #[test]
+    fn read_saved_public_key_returns_the_last_key_of_the_account() {
+        let bob_key =
+            near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, "bob").public_key();
+        let carol_key =
+            near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, "carol").public_key();
+        let keys_output = crate::common::write_temp_csv(
+            "keys.jsonl",
+            &format!(
+                "{}\n{}\nnot a key\n",
+                serde_json::json!({"account_id": "bob.alice.testnet", "public_key": bob_key.to_string()}),
+                serde_json::json!({"account_id": "carol.alice.testnet", "public_key": carol_key.to_string()}),
+            ),
+        );
+        assert_eq!(
+            read_saved_public_key(&keys_output, &"bob.alice.testnet".parse().unwrap()),
+            Some(bob_key)
+        );
+        assert_eq!(
+            read_saved_public_key(&keys_output, &"dave.alice.testnet".parse().unwrap()),
+            None
+        );
+        std::fs::remove_file(&keys_output).unwrap();
+        assert_eq!(
+            read_saved_public_key(&keys_output, &"bob.alice.testnet".parse().unwrap()),
+            None
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use std::str::FromStr;
+
+use dialoguer::Input;
+
+/// предустановленный RPC-сервер
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliServer {
+    #[clap(subcommand)]
+    pub create_from: Option<CliCreateFrom>,
+}
+
+/// данные для custom server
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliCustomServer {
+    #[clap(long)]
+    pub url: Option<crate::common::AvailableRpcServerUrl>,
+    #[clap(subcommand)]
+    create_from: Option<CliCreateFrom>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Server {
+    pub connection_config: crate::common::ConnectionConfig,
+    pub create_from: CreateFrom,
+}
+
+impl CliCustomServer {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = self
+            .create_from
+            .as_ref()
+            .map(|subcommand| subcommand.to_cli_args())
+            .unwrap_or_default();
+        if let Some(url) = &self.url {
+            args.push_front(url.to_string());
+            args.push_front("--url".to_string());
+        }
+        args
+    }
+}
+
+impl From<Server> for CliCustomServer {
+    fn from(server: Server) -> Self {
+        Self {
+            url: Some(
+                crate::common::AvailableRpcServerUrl::from_str(
+                    server.connection_config.rpc_url().as_str(),
+                )
+                .unwrap(),
+            ),
+            create_from: Some(server.create_from.into()),
+        }
+    }
+}
+
+impl CliServer {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        self.create_from
+            .as_ref()
+            .map(|subcommand| subcommand.to_cli_args())
+            .unwrap_or_default()
+    }
+}
+
+impl From<Server> for CliServer {
+    fn from(server: Server) -> Self {
+        Self {
+            create_from: Some(server.create_from.into()),
+        }
+    }
+}
+
+impl CliServer {
+    pub fn into_server(
+        self,
+        connection_config: crate::common::ConnectionConfig,
+    ) -> color_eyre::eyre::Result<Server> {
+        let create_from = match self.create_from {
+            Some(cli_create_from) => CreateFrom::from(cli_create_from, connection_config.clone())?,
+            None => CreateFrom::choose_create_from(connection_config.clone())?,
+        };
+        Ok(Server {
+            connection_config,
+            create_from,
+        })
+    }
+}
+
+impl CliCustomServer {
+    pub fn into_server(self) -> color_eyre::eyre::Result<Server> {
+        let url: crate::common::AvailableRpcServerUrl = match self.url {
+            Some(url) => url,
+            None => Input::new()
+                .with_prompt("What is the RPC endpoint?")
+                .interact_text()
+                .unwrap(),
+        };
+        let connection_config = crate::common::ConnectionConfig::Custom { url: url.inner };
+        let create_from = match self.create_from {
+            Some(cli_create_from) => CreateFrom::from(cli_create_from, connection_config.clone())?,
+            None => CreateFrom::choose_create_from(connection_config.clone())?,
+        };
+        Ok(Server {
+            connection_config,
+            create_from,
+        })
+    }
+}
+
+impl Server {
+    pub async fn process(self) -> crate::CliResult {
+        self.create_from.process(self.connection_config).await
+    }
+}
+
+#[derive(Debug, Clone, clap::Clap)]
+pub enum CliCreateFrom {
+    /// Specify the CSV file with the sub-accounts
+    Csv(crate::commands::add_command::sub_accounts::CliSubAccounts),
+}
+
+#[derive(Debug, Clone)]
+pub enum CreateFrom {
+    Csv(crate::commands::add_command::sub_accounts::SubAccounts),
+}
+
+impl CliCreateFrom {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        match self {
+            Self::Csv(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("csv".to_owned());
+                args
+            }
+        }
+    }
+}
+
+impl From<CreateFrom> for CliCreateFrom {
+    fn from(create_from: CreateFrom) -> Self {
+        match create_from {
+            CreateFrom::Csv(sub_accounts) => Self::Csv(sub_accounts.into()),
+        }
+    }
+}
+
+impl CreateFrom {
+    pub fn from(
+        item: CliCreateFrom,
+        connection_config: crate::common::ConnectionConfig,
+    ) -> color_eyre::eyre::Result<Self> {
+        match item {
+            CliCreateFrom::Csv(cli_sub_accounts) => Ok(Self::Csv(
+                crate::commands::add_command::sub_accounts::SubAccounts::from(
+                    cli_sub_accounts,
+                    connection_config,
+                )?,
+            )),
+        }
+    }
+}
+
+impl CreateFrom {
+    pub fn choose_create_from(
+        connection_config: crate::common::ConnectionConfig,
+    ) -> color_eyre::eyre::Result<Self> {
+        Self::from(CliCreateFrom::Csv(Default::default()), connection_config)
+    }
+
+    pub async fn process(
+        self,
+        connection_config: crate::common::ConnectionConfig,
+    ) -> crate::CliResult {
+        match self {
+            CreateFrom::Csv(sub_accounts) => sub_accounts.process(connection_config).await,
+        }
+    }
+}
@@ -0,0 +1,514 @@
+use dialoguer::Confirm;
+use near_primitives::borsh::BorshDeserialize;
+
+/// Keep the keys of the keychain in memory and sign transactions for other near-cli processes over a Unix socket
+#[derive(Debug, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliAgent {
+    /// Path of the Unix socket [default: $NEAR_CLI_AGENT_SOCK or ~/.near-credentials/agent.sock]
+    #[clap(long)]
+    pub socket: Option<std::path::PathBuf>,
+    /// How long (in seconds) the loaded keys are kept in memory before they are read from the keychain again
+    #[clap(long, default_value = "3600")]
+    pub ttl: u64,
+    /// When to ask for a confirmation before signing a transaction: always, never, risky
+    #[clap(long, default_value = "always")]
+    pub confirm: ConfirmationRule,
+}
+
+impl Default for CliAgent {
+    fn default() -> Self {
+        Self {
+            socket: None,
+            ttl: 3600,
+            confirm: ConfirmationRule::Always,
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    strum_macros::IntoStaticStr,
+    strum_macros::EnumString,
+    strum_macros::EnumVariantNames,
+    smart_default::SmartDefault,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum ConfirmationRule {
+    #[default]
+    Always,
+    Never,
+    /// Everything except function calls without a deposit
+    Risky,
+}
+
+impl std::fmt::Display for ConfirmationRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfirmationRule::Always => write!(f, "always"),
+            ConfirmationRule::Never => write!(f, "never"),
+            ConfirmationRule::Risky => write!(f, "risky"),
+        }
+    }
+}
+
+impl ConfirmationRule {
+    fn requires_confirmation(
+        &self,
+        transaction: &near_primitives::transaction::Transaction,
+    ) -> bool {
+        match self {
+            ConfirmationRule::Always => true,
+            ConfirmationRule::Never => false,
+            ConfirmationRule::Risky => !transaction.actions.iter().all(|action| {
+                matches!(
+                    action,
+                    near_primitives::transaction::Action::FunctionCall(function_call_action)
+                        if function_call_action.deposit == 0
+                )
+            }),
+        }
+    }
+}
+
+impl CliAgent {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        args.push_front(self.confirm.to_string());
+        args.push_front("--confirm".to_owned());
+        args.push_front(self.ttl.to_string());
+        args.push_front("--ttl".to_owned());
+        if let Some(socket) = &self.socket {
+            args.push_front(socket.display().to_string());
+            args.push_front("--socket".to_owned());
+        }
+        args
+    }
+
+    #[cfg(not(unix))]
+    pub async fn process(self) -> crate::CliResult {
+        Err(color_eyre::Report::msg(
+            "The agent is only supported on Unix platforms",
+        ))
+    }
+
+    #[cfg(unix)]
+    pub async fn process(self) -> crate::CliResult {
+        use std::os::unix::fs::PermissionsExt;
+
+        let socket_path = self
+            .socket
+            .clone()
+            .unwrap_or_else(crate::common::agent_socket_path);
+        if std::os::unix::net::UnixStream::connect(&socket_path).is_ok() {
+            return Err(color_eyre::Report::msg(format!(
+                "Another agent is already listening on {}",
+                socket_path.display()
+            )));
+        }
+        // A socket file left behind by an agent that was not shut down cleanly.
+        let _ = std::fs::remove_file(&socket_path);
+        if let Some(socket_dir) = socket_path.parent() {
+            std::fs::create_dir_all(socket_dir)?;
+        }
+        let listener = std::os::unix::net::UnixListener::bind(&socket_path).map_err(|err| {
+            color_eyre::Report::msg(format!(
+                "Failed to listen on {}: {}",
+                socket_path.display(),
+                err
+            ))
+        })?;
+        std::fs::set_permissions(&socket_path, std::fs::Permissions::from_mode(0o600))?;
+        println!(
+            "The agent is listening on {} (keys are kept for {} seconds, confirmation: {}).",
+            socket_path.display(),
+            self.ttl,
+            self.confirm
+        );
+        if self.socket.is_some() {
+            println!(
+                "Run `export {}={}` to make near-cli use it.",
+                crate::consts::AGENT_SOCKET_ENV_VAR,
+                socket_path.display()
+            );
+        }
+
+        let agent = std::sync::Arc::new(Agent {
+            confirm: self.confirm,
+            key_cache: std::sync::Mutex::new(KeyCache {
+                ttl: std::time::Duration::from_secs(self.ttl),
+                entries: std::collections::HashMap::new(),
+            }),
+            confirmation_lock: std::sync::Mutex::new(()),
+        });
+        {
+            let agent = agent.clone();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(KEY_CACHE_EVICTION_INTERVAL);
+                agent.key_cache.lock().unwrap().evict_expired();
+            });
+        }
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(err) => {
+                    println!("Failed to accept a connection: {}", err);
+                    continue;
+                }
+            };
+            let agent = agent.clone();
+            std::thread::spawn(move || agent.handle_connection(stream));
+        }
+        Ok(())
+    }
+}
+
+/// How often the keys whose TTL is over are dropped from memory.
+const KEY_CACHE_EVICTION_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// The state shared by the connections, each of which is handled on its own thread.
+struct Agent {
+    confirm: ConfirmationRule,
+    key_cache: std::sync::Mutex<KeyCache>,
+    /// Confirmation prompts of concurrent requests are asked one at a time.
+    confirmation_lock: std::sync::Mutex<()>,
+}
+
+impl Agent {
+    #[cfg(unix)]
+    fn handle_connection(&self, mut stream: std::os::unix::net::UnixStream) {
+        let response = match serde_json::Deserializer::from_reader(&stream)
+            .into_iter::<crate::common::AgentRequest>()
+            .next()
+        {
+            Some(Ok(request)) => self.handle_request(request),
+            Some(Err(err)) => Err(color_eyre::Report::msg(format!("Invalid request: {}", err))),
+            None => return,
+        };
+        let response = response.unwrap_or_else(|err| crate::common::AgentResponse {
+            error: Some(err.to_string()),
+            ..Default::default()
+        });
+        if let Err(err) = serde_json::to_writer(&mut stream, &response) {
+            println!("Failed to send the response: {}", err);
+        }
+    }
+
+    fn handle_request(
+        &self,
+        request: crate::common::AgentRequest,
+    ) -> color_eyre::eyre::Result<crate::common::AgentResponse> {
+        match request {
+            crate::common::AgentRequest::ListKeys {
+                dir_name,
+                account_id,
+            } => {
+                check_dir_name(&dir_name)?;
+                Ok(crate::common::AgentResponse {
+                    public_keys: self
+                        .key_cache
+                        .lock()
+                        .unwrap()
+                        .access_keys(&dir_name, &account_id)
+                        .iter()
+                        .map(|access_key| access_key.public_key.clone())
+                        .collect(),
+                    ..Default::default()
+                })
+            }
+            crate::common::AgentRequest::Sign {
+                dir_name,
+                transaction,
+            } => {
+                check_dir_name(&dir_name)?;
+                let unsigned_transaction = near_primitives::serialize::from_base64(&transaction)
+                    .ok()
+                    .and_then(|bytes| {
+                        near_primitives::transaction::Transaction::try_from_slice(&bytes).ok()
+                    })
+                    .ok_or_else(|| {
+                        color_eyre::Report::msg("The transaction cannot be deserialized")
+                    })?;
+                let mut summary = vec!["\nSigning request:".to_owned()];
+                summary.extend(
+                    crate::common::transaction_summary(&unsigned_transaction)
+                        .into_iter()
+                        .map(|line| format!("  {}", line)),
+                );
+                self.sign(SigningRequest {
+                    requires_confirmation: self
+                        .confirm
+                        .requires_confirmation(&unsigned_transaction),
+                    hash: unsigned_transaction.get_hash_and_size().0,
+                    dir_name,
+                    account_id: unsigned_transaction.signer_id,
+                    public_key: unsigned_transaction.public_key,
+                    summary,
+                    subject: "transaction",
+                })
+            }
+            crate::common::AgentRequest::SignMessage {
+                dir_name,
+                account_id,
+                public_key,
+                payload,
+            } => {
+                check_dir_name(&dir_name)?;
+                let summary = vec![
+                    format!("\nMessage signing request from <{}>:", account_id),
+                    format!("  recipient: {}", payload.recipient),
+                    format!("  message: {}", payload.message),
+                ];
+                self.sign(SigningRequest {
+                    dir_name,
+                    account_id,
+                    public_key,
+                    hash: payload.hash(),
+                    summary,
+                    // A signed message can log the account in to the recipient, so it is only
+                    // signed without asking when the confirmation is turned off.
+                    requires_confirmation: self.confirm != ConfirmationRule::Never,
+                    subject: "message",
+                })
+            }
+        }
+    }
+
+    fn sign(
+        &self,
+        request: SigningRequest,
+    ) -> color_eyre::eyre::Result<crate::common::AgentResponse> {
+        let SigningRequest {
+            dir_name,
+            account_id,
+            public_key,
+            hash,
+            summary,
+            requires_confirmation,
+            subject,
+        } = request;
+        if !self
+            .key_cache
+            .lock()
+            .unwrap()
+            .access_keys(&dir_name, &account_id)
+            .iter()
+            .any(|access_key| access_key.public_key == public_key)
+        {
+            return Err(color_eyre::Report::msg(format!(
+                "The agent has no private key for {} of <{}>",
+                public_key, account_id
+            )));
+        }
+        {
+            let _confirmation_lock = self.confirmation_lock.lock().unwrap();
+            for line in summary.iter() {
+                println!("{}", line);
+            }
+            if requires_confirmation
+                && !Confirm::new()
+                    .with_prompt(format!("Do you want to sign this {}?", subject))
+                    .interact()
+                    .unwrap_or(false)
+            {
+                println!("The signing is rejected.");
+                return Err(color_eyre::Report::msg(
+                    "The signing is rejected by the user",
+                ));
+            }
+        }
+        // The cache is not locked while the user is asked, so the key is looked up again.
+        let signature = self
+            .key_cache
+            .lock()
+            .unwrap()
+            .access_keys(&dir_name, &account_id)
+            .iter()
+            .find(|access_key| access_key.public_key == public_key)
+            .ok_or_else(|| {
+                color_eyre::Report::msg(format!(
+                    "The agent has no private key for {} of <{}>",
+                    public_key, account_id
+                ))
+            })?
+            .sign(hash.as_ref())?;
+        println!("The {} is signed.", subject);
+        Ok(crate::common::AgentResponse {
+            signature: Some(signature),
+            ..Default::default()
+        })
+    }
+}
+
+/// A transaction or a message to sign, with the lines shown to the user before signing it.
+struct SigningRequest {
+    dir_name: String,
+    account_id: near_primitives::types::AccountId,
+    public_key: near_crypto::PublicKey,
+    hash: near_primitives::hash::CryptoHash,
+    summary: Vec<String>,
+    requires_confirmation: bool,
+    subject: &'static str,
+}
+
+/// Only the keychain directories of near-cli are served, so a client cannot make the agent
+/// read files elsewhere in the home directory.
+fn check_dir_name(dir_name: &str) -> crate::CliResult {
+    let known_dir_names = [
+        crate::consts::DIR_NAME_KEY_CHAIN,
+        crate::consts::DIR_NAME_TESTNET,
+        crate::consts::DIR_NAME_MAINNET,
+        crate::consts::DIR_NAME_BETANET,
+        crate::consts::DIR_NAME_CUSTOM,
+    ];
+    if known_dir_names.contains(&dir_name) {
+        Ok(())
+    } else {
+        Err(color_eyre::Report::msg(format!(
+            "<{}> is not a keychain directory of near-cli",
+            dir_name
+        )))
+    }
+}
+
+/// A private key held by the agent. Its textual form is the only copy kept in memory, and
+/// it is overwritten with zeros when the key is dropped.
+struct CachedAccessKey {
+    public_key: near_crypto::PublicKey,
+    private_key: Vec<u8>,
+}
+
+impl CachedAccessKey {
+    fn new(keychain_access_key: crate::common::KeychainAccessKey) -> Self {
+        Self {
+            public_key: keychain_access_key.public_key,
+            private_key: keychain_access_key.private_key.to_string().into_bytes(),
+        }
+    }
+
+    fn sign(&self, data: &[u8]) -> color_eyre::eyre::Result<near_crypto::Signature> {
+        let private_key: near_crypto::SecretKey =
+            std::str::from_utf8(&self.private_key)?.parse()?;
+        Ok(private_key.sign(data))
+    }
+}
+
+impl Drop for CachedAccessKey {
+    fn drop(&mut self) {
+        for byte in self.private_key.iter_mut() {
+            // Volatile writes are not optimized away even though the buffer is freed next.
+            unsafe { std::ptr::write_volatile(byte, 0) };
+        }
+        std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+/// The access keys read from the keychain, per keychain directory and account. The keychain
+/// is only read for accounts without a live entry; entries are dropped once their TTL is over.
+struct KeyCache {
+    ttl: std::time::Duration,
+    entries: std::collections::HashMap<
+        (String, near_primitives::types::AccountId),
+        (std::time::Instant, Vec<CachedAccessKey>),
+    >,
+}
+
+impl KeyCache {
+    fn evict_expired(&mut self) {
+        let ttl = self.ttl;
+        self.entries
+            .retain(|_, (loaded_at, _)| loaded_at.elapsed() < ttl);
+    }
+
+    fn access_keys(
+        &mut self,
+        dir_name: &str,
+        account_id: &near_primitives::types::AccountId,
+    ) -> &[CachedAccessKey] {
+        self.evict_expired();
+        let entry_key = (dir_name.to_owned(), account_id.clone());
+        if !self.entries.contains_key(&entry_key) {
+            let access_keys: Vec<CachedAccessKey> =
+                crate::common::read_keychain_access_keys(dir_name, account_id)
+                    .into_iter()
+                    .map(|(_, access_key)| CachedAccessKey::new(access_key))
+                    .collect();
+            // Accounts without keys are not cached, so keys added later are picked up.
+            if access_keys.is_empty() {
+                return &[];
+            }
+            self.entries
+                .insert(entry_key.clone(), (std::time::Instant::now(), access_keys));
+        }
+        &self.entries[&entry_key].1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached_access_key(seed: &str) -> CachedAccessKey {
+        let private_key = near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, seed);
+        CachedAccessKey::new(crate::common::KeychainAccessKey {
+            account_id: "alice.testnet".parse().unwrap(),
+            public_key: private_key.public_key(),
+            private_key,
+        })
+    }
+
+    #[test]
+    fn only_keychain_directories_are_served() {
+        assert!(check_dir_name(crate::consts::DIR_NAME_TESTNET).is_ok());
+        assert!(check_dir_name(crate::consts::DIR_NAME_KEY_CHAIN).is_ok());
+        assert!(check_dir_name(".ssh/").is_err());
+        assert!(check_dir_name(".near-credentials/testnet/../../.ssh").is_err());
+        assert!(check_dir_name("/etc").is_err());
+    }
+
+    #[test]
+    fn cached_access_key_signs_like_the_private_key() {
+        let private_key = near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, "agent");
+        let access_key = cached_access_key("agent");
+        assert_eq!(access_key.public_key, private_key.public_key());
+        assert_eq!(
+            access_key.sign(b"payload").unwrap(),
+            private_key.sign(b"payload")
+        );
+    }
+
+    #[test]
+    fn expired_keys_are_dropped_without_a_request() {
+        let account_id: near_primitives::types::AccountId = "alice.testnet".parse().unwrap();
+        let mut key_cache = KeyCache {
+            ttl: std::time::Duration::from_secs(3600),
+            entries: std::collections::HashMap::new(),
+        };
+        key_cache.entries.insert(
+            (
+                crate::consts::DIR_NAME_TESTNET.to_owned(),
+                account_id.clone(),
+            ),
+            (std::time::Instant::now(), vec![cached_access_key("agent")]),
+        );
+        key_cache.evict_expired();
+        assert_eq!(key_cache.entries.len(), 1);
+        assert_eq!(
+            key_cache
+                .access_keys(crate::consts::DIR_NAME_TESTNET, &account_id)
+                .len(),
+            1
+        );
+
+        key_cache.ttl = std::time::Duration::from_secs(0);
+        key_cache.evict_expired();
+        assert!(key_cache.entries.is_empty());
+    }
+}
@@ -3,6 +3,7 @@ use near_primitives::borsh::BorshSerialize;
 use strum::{EnumDiscriminants, EnumIter, EnumMessage, IntoEnumIterator};
 
 mod sign_manually;
+pub mod sign_with_external;
 pub mod sign_with_keychain;
 pub mod sign_with_ledger;
 pub mod sign_with_private_key;
@@ -15,6 +16,8 @@ pub enum CliSignTransaction {
     SignWithKeychain(self::sign_with_keychain::CliSignKeychain),
     /// Connect your Ledger device and sign transaction with it
     SignWithLedger(self::sign_with_ledger::CliSignLedger),
+    /// Sign the transaction with an external program
+    SignWithExternal(self::sign_with_external::CliSignExternal),
     /// Provide arguments to sign a manually transaction
     SignManually(self::sign_manually::CliSignManually),
 }
@@ -32,6 +35,10 @@ pub enum SignTransaction {
         message = "Yes, I want to sign the transaction with Ledger device"
     ))]
     SignWithLedger(self::sign_with_ledger::SignLedger),
+    #[strum_discriminants(strum(
+        message = "Yes, I want to sign the transaction with an external program"
+    ))]
+    SignWithExternal(self::sign_with_external::SignExternal),
     #[strum_discriminants(strum(
         message = "No, I want to construct the transaction and sign it somewhere else"
     ))]
@@ -56,6 +63,11 @@ impl CliSignTransaction {
                 args.push_front("sign-with-ledger".to_owned());
                 args
             }
+            CliSignTransaction::SignWithExternal(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("sign-with-external".to_owned());
+                args
+            }
             CliSignTransaction::SignManually(subcommand) => {
                 let mut args = subcommand.to_cli_args();
                 args.push_front("sign-manually".to_owned());
@@ -65,6 +77,44 @@ impl CliSignTransaction {
     }
 }
 
+impl CliSignTransaction {
+    /// Fills in the signer public key, the nonce and the block hash used in offline mode
+    /// unless they were given on the command line.
+    pub fn prefill_offline_values(
+        &mut self,
+        signer_public_key: &near_crypto::PublicKey,
+        nonce: u64,
+        block_hash: near_primitives::hash::CryptoHash,
+    ) {
+        let (cli_signer_public_key, cli_nonce, cli_block_hash) = match self {
+            CliSignTransaction::SignPrivateKey(subcommand) => {
+                (None, &mut subcommand.nonce, &mut subcommand.block_hash)
+            }
+            CliSignTransaction::SignWithKeychain(subcommand) => {
+                (None, &mut subcommand.nonce, &mut subcommand.block_hash)
+            }
+            CliSignTransaction::SignWithLedger(subcommand) => {
+                (None, &mut subcommand.nonce, &mut subcommand.block_hash)
+            }
+            CliSignTransaction::SignWithExternal(subcommand) => (
+                Some(&mut subcommand.signer_public_key),
+                &mut subcommand.nonce,
+                &mut subcommand.block_hash,
+            ),
+            CliSignTransaction::SignManually(subcommand) => (
+                Some(&mut subcommand.signer_public_key),
+                &mut subcommand.nonce,
+                &mut subcommand.block_hash,
+            ),
+        };
+        if let Some(cli_signer_public_key) = cli_signer_public_key {
+            cli_signer_public_key.get_or_insert_with(|| signer_public_key.clone());
+        }
+        cli_nonce.get_or_insert(nonce);
+        cli_block_hash.get_or_insert(block_hash);
+    }
+}
+
 impl From<SignTransaction> for CliSignTransaction {
     fn from(sign_transaction: SignTransaction) -> Self {
         match sign_transaction {
@@ -77,6 +127,9 @@ impl From<SignTransaction> for CliSignTransaction {
             SignTransaction::SignWithLedger(sign_with_ledger) => Self::SignWithLedger(
                 self::sign_with_ledger::CliSignLedger::from(sign_with_ledger),
             ),
+            SignTransaction::SignWithExternal(sign_with_external) => Self::SignWithExternal(
+                self::sign_with_external::CliSignExternal::from(sign_with_external),
+            ),
             SignTransaction::SignManually(sign_manually) => {
                 Self::SignManually(self::sign_manually::CliSignManually::from(sign_manually))
             }
@@ -111,6 +164,11 @@ impl SignTransaction {
                     self::sign_with_ledger::SignLedger::from(cli_ledger, connection_config)?;
                 Ok(SignTransaction::SignWithLedger(ledger))
             }
+            CliSignTransaction::SignWithExternal(cli_external) => {
+                let external =
+                    self::sign_with_external::SignExternal::from(cli_external, connection_config);
+                Ok(SignTransaction::SignWithExternal(external))
+            }
             CliSignTransaction::SignManually(cli_manually) => {
                 let manually =
                     self::sign_manually::SignManually::from(cli_manually, connection_config);
@@ -147,6 +205,9 @@ impl SignTransaction {
             SignTransactionDiscriminants::SignWithLedger => {
                 CliSignTransaction::SignWithLedger(Default::default())
             }
+            SignTransactionDiscriminants::SignWithExternal => {
+                CliSignTransaction::SignWithExternal(Default::default())
+            }
             SignTransactionDiscriminants::SignManually => {
                 CliSignTransaction::SignManually(Default::default())
             }
@@ -174,6 +235,11 @@ impl SignTransaction {
                     .process(prepopulated_unsigned_transaction, network_connection_config)
                     .await
             }
+            SignTransaction::SignWithExternal(external) => {
+                external
+                    .process(prepopulated_unsigned_transaction, network_connection_config)
+                    .await
+            }
             SignTransaction::SignManually(args_manually) => {
                 args_manually
                     .process(prepopulated_unsigned_transaction, network_connection_config)
@@ -183,6 +249,194 @@ impl SignTransaction {
     }
 }
 
+/// How [`sign_and_submit`] completes and submits the transaction.
+#[derive(Debug, Clone, Default)]
+pub struct SubmitOptions {
+    /// The nonce and the block hash used in offline mode (they are fetched in online mode)
+    pub nonce: Option<u64>,
+    pub block_hash: Option<near_primitives::hash::CryptoHash>,
+    /// Asked for after signing when it is not given
+    pub submit: Option<Submit>,
+    /// Show the cost and the risks of the transaction for confirmation before signing it
+    pub review: bool,
+    /// Send the transaction even when the pre-flight checks predict that it fails
+    pub ignore_preflight_errors: bool,
+    /// The network whose signing policy applies in offline mode (testnet, mainnet or betanet)
+    pub policy_network: Option<String>,
+}
+
+/// Fills in the nonce and the block hash of the transaction, signs it with the signer and
+/// submits the signed transaction. The signing policy of the network profile is enforced first
+/// (offline, the profile of `options.policy_network`), and with `options.review` the cost and the risks of the transaction are shown for
+/// confirmation. In online mode the nonce is reserved in the local nonce store, and a
+/// transaction rejected for its nonce is re-signed and sent once more.
+pub async fn sign_and_submit(
+    prepopulated_unsigned_transaction: near_primitives::transaction::Transaction,
+    signer_public_key: near_crypto::PublicKey,
+    connection_config: Option<crate::common::ConnectionConfig>,
+    signer: &dyn crate::common::TransactionSigner,
+    options: SubmitOptions,
+) -> color_eyre::eyre::Result<Option<near_primitives::views::FinalExecutionOutcomeView>> {
+    let policy_connection_config = match (&connection_config, &options.policy_network) {
+        (Some(network_connection_config), _) => network_connection_config.clone(),
+        (None, Some(policy_network)) => {
+            crate::common::ConnectionConfig::from_network_name(policy_network)?
+        }
+        (None, None) => {
+            return Err(color_eyre::Report::msg(
+                "The network of a transaction signed offline has to be given (--policy-network) to apply its signing policy",
+            ))
+        }
+    };
+    crate::common::enforce_signing_policy(
+        &prepopulated_unsigned_transaction,
+        &policy_connection_config,
+    )?;
+    if options.review {
+        crate::common::review_transaction(
+            &prepopulated_unsigned_transaction,
+            connection_config.as_ref(),
+        )
+        .await?;
+    }
+    let unsigned_transaction = match &connection_config {
+        None => near_primitives::transaction::Transaction {
+            public_key: signer_public_key,
+            nonce: options.nonce.unwrap_or_default(),
+            block_hash: options.block_hash.unwrap_or_default(),
+            ..prepopulated_unsigned_transaction
+        },
+        Some(network_connection_config) => {
+            let (nonce, block_hash) = reserve_nonce(
+                network_connection_config,
+                &prepopulated_unsigned_transaction.signer_id,
+                &signer_public_key,
+            )
+            .await?;
+            near_primitives::transaction::Transaction {
+                public_key: signer_public_key,
+                block_hash,
+                nonce,
+                ..prepopulated_unsigned_transaction
+            }
+        }
+    };
+    println!("\nUnsigned transaction:\n");
+    crate::common::print_transaction(unsigned_transaction.clone());
+    let signed_transaction = sign(unsigned_transaction.clone(), signer).await?;
+    let serialize_to_base64 = near_primitives::serialize::to_base64(
+        signed_transaction
+            .try_to_vec()
+            .expect("Transaction is not expected to fail on serialization"),
+    );
+    println!("Your transaction was signed successfully.");
+    let submit = match options.submit {
+        Some(submit) => submit,
+        None => Submit::choose_submit(connection_config.clone()),
+    };
+    match connection_config {
+        None => submit.process_offline(serialize_to_base64),
+        Some(network_connection_config) => {
+            match submit
+                .clone()
+                .process_online(
+                    network_connection_config.clone(),
+                    signed_transaction,
+                    serialize_to_base64,
+                    options.ignore_preflight_errors,
+                )
+                .await
+            {
+                Err(err)
+                    if err
+                        .downcast_ref::<crate::common::InvalidNonceError>()
+                        .is_some() =>
+                {
+                    println!("{}\nRe-signing the transaction with a fresh nonce ...", err);
+                    let (nonce, block_hash) = reserve_nonce(
+                        &network_connection_config,
+                        &unsigned_transaction.signer_id,
+                        &unsigned_transaction.public_key,
+                    )
+                    .await?;
+                    let signed_transaction = sign(
+                        near_primitives::transaction::Transaction {
+                            nonce,
+                            block_hash,
+                            ..unsigned_transaction
+                        },
+                        signer,
+                    )
+                    .await?;
+                    let serialize_to_base64 = near_primitives::serialize::to_base64(
+                        signed_transaction
+                            .try_to_vec()
+                            .expect("Transaction is not expected to fail on serialization"),
+                    );
+                    submit
+                        .process_online(
+                            network_connection_config,
+                            signed_transaction,
+                            serialize_to_base64,
+                            options.ignore_preflight_errors,
+                        )
+                        .await
+                }
+                result => result,
+            }
+        }
+    }
+}
+
+async fn sign(
+    unsigned_transaction: near_primitives::transaction::Transaction,
+    signer: &dyn crate::common::TransactionSigner,
+) -> color_eyre::eyre::Result<near_primitives::transaction::SignedTransaction> {
+    let signature = signer.sign(&unsigned_transaction).await?;
+    Ok(near_primitives::transaction::SignedTransaction::new(
+        signature,
+        unsigned_transaction,
+    ))
+}
+
+/// Fetches the access key and the latest block hash and reserves the next nonce of the key in
+/// the local nonce store.
+pub async fn reserve_nonce(
+    network_connection_config: &crate::common::ConnectionConfig,
+    signer_id: &near_primitives::types::AccountId,
+    signer_public_key: &near_crypto::PublicKey,
+) -> color_eyre::eyre::Result<(u64, near_primitives::hash::CryptoHash)> {
+    let online_signer_access_key_response =
+        near_jsonrpc_client::new_client(network_connection_config.rpc_url().as_str())
+            .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
+                block_reference: near_primitives::types::Finality::Final.into(),
+                request: near_primitives::views::QueryRequest::ViewAccessKey {
+                    account_id: signer_id.clone(),
+                    public_key: signer_public_key.clone(),
+                },
+            })
+            .await
+            .map_err(|err| {
+                color_eyre::Report::msg(format!(
+                    "Failed to fetch public key information for nonce: {:?}",
+                    err
+                ))
+            })?;
+    let current_nonce = if let near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKey(
+        online_signer_access_key,
+    ) = online_signer_access_key_response.kind
+    {
+        online_signer_access_key.nonce
+    } else {
+        return Err(color_eyre::Report::msg("Error current_nonce"));
+    };
+    let nonce =
+        crate::common::NonceStore::new(network_connection_config, signer_id, signer_public_key)
+            .reserve(current_nonce)
+            .await?;
+    Ok((nonce, online_signer_access_key_response.block_hash))
+}
+
 fn input_signer_public_key() -> near_crypto::PublicKey {
     Input::new()
         .with_prompt("To create an unsigned transaction enter sender's public key")
@@ -231,6 +485,10 @@ pub enum Submit {
     Send,
     #[strum_discriminants(strum(message = "Do you want show the transaction on display?"))]
     Display,
+    #[strum_discriminants(strum(
+        message = "Do you want show the transaction on display as a QR code?"
+    ))]
+    DisplayQrCode,
 }
 
 impl Submit {
@@ -246,21 +504,23 @@ impl Submit {
                 args.push_front("display".to_owned());
                 args
             }
+            Self::DisplayQrCode => {
+                let mut args = std::collections::VecDeque::new();
+                args.push_front("display-qr-code".to_owned());
+                args
+            }
         }
     }
 
     pub fn choose_submit(connection_config: Option<crate::common::ConnectionConfig>) -> Self {
         println!();
-        let variants = SubmitDiscriminants::iter().collect::<Vec<_>>();
-
-        let submits = if let Some(_) = connection_config {
-            variants
-                .iter()
-                .map(|p| p.get_message().unwrap().to_owned())
-                .collect::<Vec<_>>()
-        } else {
-            vec!["Do you want show the transaction on display?".to_string()]
-        };
+        let variants = SubmitDiscriminants::iter()
+            .filter(|p| connection_config.is_some() || !matches!(p, SubmitDiscriminants::Send))
+            .collect::<Vec<_>>();
+        let submits = variants
+            .iter()
+            .map(|p| p.get_message().unwrap().to_owned())
+            .collect::<Vec<_>>();
         let select_submit = Select::with_theme(&ColorfulTheme::default())
             .with_prompt("Select an action that you want to add to the action:")
             .items(&submits)
@@ -270,6 +530,7 @@ impl Submit {
         match variants[select_submit] {
             SubmitDiscriminants::Send => Submit::Send,
             SubmitDiscriminants::Display => Submit::Display,
+            SubmitDiscriminants::DisplayQrCode => Submit::DisplayQrCode,
         }
     }
 
@@ -278,6 +539,9 @@ impl Submit {
         serialize_to_base64: String,
     ) -> color_eyre::eyre::Result<Option<near_primitives::views::FinalExecutionOutcomeView>> {
         println!("Serialize_to_base64:\n{}", &serialize_to_base64);
+        if let Submit::DisplayQrCode = self {
+            crate::common::print_qr_code(&serialize_to_base64)?;
+        }
         Ok(None)
     }
 
@@ -286,12 +550,19 @@ impl Submit {
         network_connection_config: crate::common::ConnectionConfig,
         signed_transaction: near_primitives::transaction::SignedTransaction,
         serialize_to_base64: String,
+        ignore_preflight_errors: bool,
     ) -> color_eyre::eyre::Result<Option<near_primitives::views::FinalExecutionOutcomeView>> {
         match self {
             Submit::Send => {
-                println!("Transaction sent ...");
                 let json_rcp_client =
                     near_jsonrpc_client::new_client(network_connection_config.rpc_url().as_str());
+                crate::common::check_transaction_before_sending(
+                    &json_rcp_client,
+                    &signed_transaction.transaction,
+                    ignore_preflight_errors,
+                )
+                .await?;
+                println!("Transaction sent ...");
                 let transaction_info = loop {
                     let transaction_info_result = json_rcp_client
                         .broadcast_tx_commit(near_primitives::serialize::to_base64(
@@ -305,6 +576,16 @@ impl Submit {
                             break response;
                         }
                         Err(err) => {
+                            if let Some(data) = err
+                                .data
+                                .as_ref()
+                                .map(|data| data.to_string())
+                                .filter(|data| data.contains("InvalidNonce"))
+                            {
+                                return Err(color_eyre::Report::new(
+                                    crate::common::InvalidNonceError { message: data },
+                                ));
+                            }
                             if let Some(serde_json::Value::String(data)) = &err.data {
                                 if data.contains("Timeout") {
                                     println!("Timeout error transaction.\nPlease wait. The next try to send this transaction is happening right now ...");
@@ -323,6 +604,11 @@ impl Submit {
                 println!("\nSerialize_to_base64:\n{}", &serialize_to_base64);
                 Ok(None)
             }
+            Submit::DisplayQrCode => {
+                println!("\nSerialize_to_base64:\n{}", &serialize_to_base64);
+                crate::common::print_qr_code(&serialize_to_base64)?;
+                Ok(None)
+            }
         }
     }
 }
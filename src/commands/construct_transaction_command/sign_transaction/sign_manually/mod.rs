@@ -9,11 +9,11 @@ use near_primitives::borsh::BorshSerialize;
 )]
 pub struct CliSignManually {
     #[clap(long)]
-    signer_public_key: Option<near_crypto::PublicKey>,
+    pub(super) signer_public_key: Option<near_crypto::PublicKey>,
     #[clap(long)]
-    nonce: Option<u64>,
+    pub(super) nonce: Option<u64>,
     #[clap(long)]
-    block_hash: Option<near_primitives::hash::CryptoHash>,
+    pub(super) block_hash: Option<near_primitives::hash::CryptoHash>,
 }
 
 #[derive(Debug, Clone)]
@@ -96,6 +96,14 @@ impl SignManually {
         prepopulated_unsigned_transaction: near_primitives::transaction::Transaction,
         network_connection_config: Option<crate::common::ConnectionConfig>,
     ) -> color_eyre::eyre::Result<Option<near_primitives::views::FinalExecutionOutcomeView>> {
+        // Offline, the transaction is only prepared here: the signing policy is enforced by the
+        // command that signs it
+        if let Some(network_connection_config) = &network_connection_config {
+            crate::common::enforce_signing_policy(
+                &prepopulated_unsigned_transaction,
+                network_connection_config,
+            )?;
+        }
         let public_key: near_crypto::PublicKey = self.signer_public_key.clone();
 
         let unsigned_transaction = match network_connection_config {
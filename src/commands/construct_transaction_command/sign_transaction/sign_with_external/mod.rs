@@ -0,0 +1,413 @@
+use std::io::Write;
+
+use dialoguer::Input;
+use near_primitives::borsh::BorshSerialize;
+
+/// Version of the JSON messages exchanged with the external signer.
+const EXTERNAL_SIGNER_PROTOCOL_VERSION: u32 = 1;
+
+/// Sign constructed transaction with an external program.
+///
+/// The program is started once per transaction and receives a JSON request on stdin:
+/// `{"version": 1, "transaction_hash": "<base58>", "transaction": "<base64>", "signer_id": ...,
+/// "public_key": ..., "receiver_id": ..., "nonce": ..., "block_hash": ..., "summary": [...]}`.
+/// It must print `{"signature": "ed25519:..."}` to stdout, or `{"error": "..."}` to refuse signing.
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliSignExternal {
+    /// The signer program with its arguments, e.g. "hsm-signer --slot 1"
+    program: Option<String>,
+    #[clap(long)]
+    pub(super) signer_public_key: Option<near_crypto::PublicKey>,
+    #[clap(long)]
+    pub(super) nonce: Option<u64>,
+    #[clap(long)]
+    pub(super) block_hash: Option<near_primitives::hash::CryptoHash>,
+    /// Sign the transaction without reviewing its cost and risks first (for scripts and CI)
+    #[clap(long)]
+    skip_review: bool,
+    /// Send the transaction even when the pre-flight checks predict that it fails
+    #[clap(long)]
+    ignore_preflight_errors: bool,
+    /// The network whose signing policy applies when signing offline (testnet, mainnet or betanet)
+    #[clap(long)]
+    policy_network: Option<String>,
+    #[clap(subcommand)]
+    submit: Option<super::Submit>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SignExternal {
+    pub program: String,
+    pub signer_public_key: near_crypto::PublicKey,
+    nonce: Option<u64>,
+    block_hash: Option<near_primitives::hash::CryptoHash>,
+    pub submit: Option<super::Submit>,
+    pub skip_review: bool,
+    pub ignore_preflight_errors: bool,
+    pub policy_network: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ExternalSignerResponse {
+    signature: Option<near_crypto::Signature>,
+    error: Option<String>,
+}
+
+impl CliSignExternal {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = self
+            .submit
+            .as_ref()
+            .map(|subcommand| subcommand.to_cli_args())
+            .unwrap_or_default();
+        if let Some(policy_network) = &self.policy_network {
+            args.push_front(policy_network.to_owned());
+            args.push_front("--policy-network".to_owned());
+        }
+        if self.ignore_preflight_errors {
+            args.push_front("--ignore-preflight-errors".to_owned());
+        }
+        if self.skip_review {
+            args.push_front("--skip-review".to_owned());
+        }
+        if let Some(nonce) = &self.nonce {
+            args.push_front(nonce.to_string());
+            args.push_front("--nonce".to_owned())
+        }
+        if let Some(block_hash) = &self.block_hash {
+            args.push_front(block_hash.to_string());
+            args.push_front("--block-hash".to_owned())
+        }
+        if let Some(signer_public_key) = &self.signer_public_key {
+            args.push_front(signer_public_key.to_string());
+            args.push_front("--signer-public-key".to_owned())
+        }
+        if let Some(program) = &self.program {
+            args.push_front(program.to_owned());
+        }
+        args
+    }
+}
+
+impl From<SignExternal> for CliSignExternal {
+    fn from(sign_external: SignExternal) -> Self {
+        Self {
+            program: Some(sign_external.program),
+            signer_public_key: Some(sign_external.signer_public_key),
+            nonce: sign_external.nonce,
+            block_hash: sign_external.block_hash,
+            submit: sign_external.submit.into(),
+            skip_review: sign_external.skip_review,
+            ignore_preflight_errors: sign_external.ignore_preflight_errors,
+            policy_network: sign_external.policy_network,
+        }
+    }
+}
+
+impl SignExternal {
+    pub fn from(
+        item: CliSignExternal,
+        connection_config: Option<crate::common::ConnectionConfig>,
+    ) -> Self {
+        let program = match item.program {
+            Some(program) => program,
+            None => SignExternal::input_program(),
+        };
+        let signer_public_key = match item.signer_public_key {
+            Some(signer_public_key) => signer_public_key,
+            None => super::input_signer_public_key(),
+        };
+        let submit: Option<super::Submit> = item.submit;
+        match connection_config {
+            Some(_) => Self {
+                program,
+                signer_public_key,
+                nonce: None,
+                block_hash: None,
+                submit,
+                skip_review: item.skip_review,
+                ignore_preflight_errors: item.ignore_preflight_errors,
+                policy_network: item.policy_network,
+            },
+            None => {
+                let nonce: u64 = match item.nonce {
+                    Some(cli_nonce) => cli_nonce,
+                    None => super::input_access_key_nonce(&signer_public_key.to_string()),
+                };
+                let block_hash = match item.block_hash {
+                    Some(cli_block_hash) => cli_block_hash,
+                    None => super::input_block_hash(),
+                };
+                let policy_network = match item.policy_network {
+                    Some(policy_network) => policy_network,
+                    None => crate::common::input_signing_policy_network(),
+                };
+                Self {
+                    program,
+                    signer_public_key,
+                    nonce: Some(nonce),
+                    block_hash: Some(block_hash),
+                    submit,
+                    skip_review: item.skip_review,
+                    ignore_preflight_errors: item.ignore_preflight_errors,
+                    policy_network: Some(policy_network),
+                }
+            }
+        }
+    }
+}
+
+impl SignExternal {
+    fn input_program() -> String {
+        Input::new()
+            .with_prompt("Enter the external signer program")
+            .interact_text()
+            .unwrap()
+    }
+
+    /// Passes the transaction to the external program and returns the verified signature.
+    pub fn sign(
+        program: &str,
+        unsigned_transaction: &near_primitives::transaction::Transaction,
+    ) -> color_eyre::eyre::Result<near_crypto::Signature> {
+        let program_args = shell_words::split(program).map_err(|err| {
+            color_eyre::Report::msg(format!("Invalid external signer program: {}", err))
+        })?;
+        let (program_name, program_args) = program_args
+            .split_first()
+            .ok_or_else(|| color_eyre::Report::msg("The external signer program is empty"))?;
+        let transaction_hash = unsigned_transaction.get_hash_and_size().0;
+        let request = serde_json::json!({
+            "version": EXTERNAL_SIGNER_PROTOCOL_VERSION,
+            "transaction_hash": transaction_hash.to_string(),
+            "transaction": near_primitives::serialize::to_base64(
+                unsigned_transaction
+                    .try_to_vec()
+                    .expect("Transaction is not expected to fail on serialization"),
+            ),
+            "signer_id": unsigned_transaction.signer_id,
+            "public_key": unsigned_transaction.public_key.to_string(),
+            "receiver_id": unsigned_transaction.receiver_id,
+            "nonce": unsigned_transaction.nonce,
+            "block_hash": unsigned_transaction.block_hash.to_string(),
+            "summary": crate::common::transaction_summary(unsigned_transaction),
+        });
+        let mut child = std::process::Command::new(program_name)
+            .args(program_args)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::inherit())
+            .spawn()
+            .map_err(|err| {
+                color_eyre::Report::msg(format!(
+                    "Failed to start the external signer <{}>: {}",
+                    program_name, err
+                ))
+            })?;
+        child
+            .stdin
+            .take()
+            .expect("stdin of the external signer is piped")
+            .write_all(request.to_string().as_bytes())?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(color_eyre::Report::msg(format!(
+                "The external signer <{}> failed with {}",
+                program_name, output.status
+            )));
+        }
+        Self::signature_from_response(program_name, &output.stdout, unsigned_transaction)
+    }
+
+    /// Parses the response of the external signer and checks that the signature was made
+    /// by the key in the `public_key` field of the transaction.
+    fn signature_from_response(
+        program_name: &str,
+        response: &[u8],
+        unsigned_transaction: &near_primitives::transaction::Transaction,
+    ) -> color_eyre::eyre::Result<near_crypto::Signature> {
+        let response: ExternalSignerResponse = serde_json::from_slice(response).map_err(|err| {
+            color_eyre::Report::msg(format!(
+                "The external signer <{}> returned an invalid response: {}",
+                program_name, err
+            ))
+        })?;
+        let signature = match (response.signature, response.error) {
+            (_, Some(error)) => {
+                return Err(color_eyre::Report::msg(format!(
+                    "The external signer <{}> refused to sign the transaction: {}",
+                    program_name, error
+                )))
+            }
+            (Some(signature), None) => signature,
+            (None, None) => {
+                return Err(color_eyre::Report::msg(format!(
+                    "The external signer <{}> returned no signature",
+                    program_name
+                )))
+            }
+        };
+        let transaction_hash = unsigned_transaction.get_hash_and_size().0;
+        if !signature.verify(transaction_hash.as_ref(), &unsigned_transaction.public_key) {
+            return Err(color_eyre::Report::msg(format!(
+                "The external signer <{}> returned a signature that does not match the public key {}",
+                program_name, unsigned_transaction.public_key
+            )));
+        }
+        Ok(signature)
+    }
+
+    pub async fn process(
+        self,
+        prepopulated_unsigned_transaction: near_primitives::transaction::Transaction,
+        connection_config: Option<crate::common::ConnectionConfig>,
+    ) -> color_eyre::eyre::Result<Option<near_primitives::views::FinalExecutionOutcomeView>> {
+        super::sign_and_submit(
+            prepopulated_unsigned_transaction,
+            self.signer_public_key.clone(),
+            connection_config,
+            &self,
+            super::SubmitOptions {
+                nonce: self.nonce,
+                block_hash: self.block_hash,
+                submit: self.submit.clone(),
+                review: !self.skip_review,
+                ignore_preflight_errors: self.ignore_preflight_errors,
+                policy_network: self.policy_network,
+            },
+        )
+        .await
+    }
+}
+
+impl crate::common::TransactionSigner for SignExternal {
+    fn public_key<'a>(&'a self) -> crate::common::SignerFuture<'a, near_crypto::PublicKey> {
+        Box::pin(async move { Ok(self.signer_public_key.clone()) })
+    }
+
+    fn sign<'a>(
+        &'a self,
+        unsigned_transaction: &'a near_primitives::transaction::Transaction,
+    ) -> crate::common::SignerFuture<'a, near_crypto::Signature> {
+        Box::pin(async move {
+            println!("Waiting for the external signer <{}> ...", self.program);
+            Self::sign(&self.program, unsigned_transaction)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed_by(
+        secret_key: &near_crypto::SecretKey,
+    ) -> (
+        near_primitives::transaction::Transaction,
+        near_crypto::Signature,
+    ) {
+        let unsigned_transaction = near_primitives::transaction::Transaction {
+            signer_id: "alice.testnet".parse().unwrap(),
+            public_key: secret_key.public_key(),
+            nonce: 7,
+            receiver_id: "contract.testnet".parse().unwrap(),
+            block_hash: Default::default(),
+            actions: vec![near_primitives::transaction::Action::Transfer(
+                near_primitives::transaction::TransferAction { deposit: 1 },
+            )],
+        };
+        let signature = secret_key.sign(unsigned_transaction.get_hash_and_size().0.as_ref());
+        (unsigned_transaction, signature)
+    }
+
+    #[test]
+    fn signature_of_the_transaction_key_is_accepted() {
+        let secret_key = near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, "hsm");
+        let (unsigned_transaction, signature) = signed_by(&secret_key);
+        let response = serde_json::json!({ "signature": signature.to_string() }).to_string();
+        assert_eq!(
+            SignExternal::signature_from_response(
+                "hsm-signer",
+                response.as_bytes(),
+                &unsigned_transaction
+            )
+            .unwrap(),
+            signature
+        );
+    }
+
+    #[test]
+    fn signature_of_another_key_is_rejected() {
+        let (unsigned_transaction, _) = signed_by(&near_crypto::SecretKey::from_seed(
+            near_crypto::KeyType::ED25519,
+            "hsm",
+        ));
+        let (_, foreign_signature) = signed_by(&near_crypto::SecretKey::from_seed(
+            near_crypto::KeyType::ED25519,
+            "other",
+        ));
+        let response =
+            serde_json::json!({ "signature": foreign_signature.to_string() }).to_string();
+        let err = SignExternal::signature_from_response(
+            "hsm-signer",
+            response.as_bytes(),
+            &unsigned_transaction,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("does not match the public key"));
+    }
+
+    #[test]
+    fn refusals_and_malformed_responses_are_errors() {
+        let (unsigned_transaction, signature) = signed_by(&near_crypto::SecretKey::from_seed(
+            near_crypto::KeyType::ED25519,
+            "hsm",
+        ));
+        let refusal = serde_json::json!({
+            "signature": signature.to_string(),
+            "error": "denied by the operator",
+        })
+        .to_string();
+        let err = SignExternal::signature_from_response(
+            "hsm-signer",
+            refusal.as_bytes(),
+            &unsigned_transaction,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("denied by the operator"));
+
+        let err = SignExternal::signature_from_response("hsm-signer", b"{}", &unsigned_transaction)
+            .unwrap_err();
+        assert!(err.to_string().contains("returned no signature"));
+
+        let err = SignExternal::signature_from_response(
+            "hsm-signer",
+            b"signature: ed25519:...",
+            &unsigned_transaction,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid response"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn external_program_receives_the_request_on_stdin() {
+        let (unsigned_transaction, _) = signed_by(&near_crypto::SecretKey::from_seed(
+            near_crypto::KeyType::ED25519,
+            "hsm",
+        ));
+        let program = r#"sh -c 'grep -q "\"nonce\":7" && echo "{\"error\": \"seen\"}"'"#;
+        let err = SignExternal::sign(program, &unsigned_transaction).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("refused to sign the transaction: seen"));
+
+        let err = SignExternal::sign("", &unsigned_transaction).unwrap_err();
+        assert!(err.to_string().contains("is empty"));
+    }
+}
@@ -11,9 +11,18 @@ use serde::Deserialize;
 )]
 pub struct CliSignKeychain {
     #[clap(long)]
-    nonce: Option<u64>,
+    pub(super) nonce: Option<u64>,
     #[clap(long)]
-    block_hash: Option<near_primitives::hash::CryptoHash>,
+    pub(super) block_hash: Option<near_primitives::hash::CryptoHash>,
+    /// Sign the transaction without reviewing its cost and risks first (for scripts and CI)
+    #[clap(long)]
+    skip_review: bool,
+    /// Send the transaction even when the pre-flight checks predict that it fails
+    #[clap(long)]
+    ignore_preflight_errors: bool,
+    /// The network whose signing policy applies when signing offline (testnet, mainnet or betanet)
+    #[clap(long)]
+    policy_network: Option<String>,
     #[clap(subcommand)]
     submit: Option<super::Submit>,
 }
@@ -23,6 +32,9 @@ pub struct SignKeychain {
     nonce: Option<u64>,
     block_hash: Option<near_primitives::hash::CryptoHash>,
     pub submit: Option<super::Submit>,
+    pub skip_review: bool,
+    pub ignore_preflight_errors: bool,
+    pub policy_network: Option<String>,
 }
 
 impl CliSignKeychain {
@@ -32,6 +44,16 @@ impl CliSignKeychain {
             .as_ref()
             .map(|subcommand| subcommand.to_cli_args())
             .unwrap_or_default();
+        if let Some(policy_network) = &self.policy_network {
+            args.push_front(policy_network.to_owned());
+            args.push_front("--policy-network".to_owned());
+        }
+        if self.ignore_preflight_errors {
+            args.push_front("--ignore-preflight-errors".to_owned());
+        }
+        if self.skip_review {
+            args.push_front("--skip-review".to_owned());
+        }
         if let Some(nonce) = &self.nonce {
             args.push_front(nonce.to_string());
             args.push_front("--nonce".to_owned())
@@ -50,6 +72,9 @@ impl From<SignKeychain> for CliSignKeychain {
             nonce: sign_keychain.nonce,
             block_hash: sign_keychain.block_hash,
             submit: sign_keychain.submit,
+            skip_review: sign_keychain.skip_review,
+            ignore_preflight_errors: sign_keychain.ignore_preflight_errors,
+            policy_network: sign_keychain.policy_network,
         }
     }
 }
@@ -66,6 +91,9 @@ impl SignKeychain {
                 nonce: None,
                 block_hash: None,
                 submit,
+                skip_review: item.skip_review,
+                ignore_preflight_errors: item.ignore_preflight_errors,
+                policy_network: item.policy_network,
             }),
             None => {
                 let home_dir = dirs::home_dir().expect("Impossible to get your home dir!");
@@ -92,10 +120,17 @@ impl SignKeychain {
                     Some(cli_block_hash) => cli_block_hash,
                     None => super::input_block_hash(),
                 };
+                let policy_network = match item.policy_network {
+                    Some(policy_network) => policy_network,
+                    None => crate::common::input_signing_policy_network(),
+                };
                 Ok(SignKeychain {
                     nonce: Some(nonce),
                     block_hash: Some(block_hash),
                     submit,
+                    skip_review: item.skip_review,
+                    ignore_preflight_errors: item.ignore_preflight_errors,
+                    policy_network: Some(policy_network),
                 })
             }
         }
@@ -114,112 +149,176 @@ impl SignKeychain {
         near_jsonrpc_client::new_client(&selected_server_url)
     }
 
+    /// Selects the least privileged access key that can sign the transaction among the keys
+    /// available in the keychain (each key comes with the place it is stored at).
+    async fn select_access_key(
+        &self,
+        prepopulated_unsigned_transaction: &near_primitives::transaction::Transaction,
+        network_connection_config: &crate::common::ConnectionConfig,
+        keychain_public_keys: &[(String, near_crypto::PublicKey)],
+    ) -> color_eyre::eyre::Result<near_crypto::PublicKey> {
+        if keychain_public_keys.is_empty() {
+            return Err(color_eyre::Report::msg("There are no access keys found in the keychain for the signer account. Log in before signing transactions with keychain."));
+        }
+        let rpc_client = self.rpc_client(network_connection_config.rpc_url().as_str());
+        let query_view_method_response = rpc_client
+            .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
+                block_reference: near_primitives::types::Finality::Final.into(),
+                request: near_primitives::views::QueryRequest::ViewAccessKeyList {
+                    account_id: prepopulated_unsigned_transaction.signer_id.clone(),
+                },
+            })
+            .await
+            .map_err(|err| {
+                color_eyre::Report::msg(format!(
+                    "Failed to fetch query for view key list: {:?}",
+                    err
+                ))
+            })?;
+        let access_key_view =
+            if let near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKeyList(result) =
+                query_view_method_response.kind
+            {
+                result
+            } else {
+                return Err(color_eyre::Report::msg("Error call result"));
+            };
+        let gas_price = rpc_client
+            .gas_price(None)
+            .await
+            .map_err(|err| {
+                color_eyre::Report::msg(format!("Failed to fetch the gas price: {:?}", err))
+            })?
+            .gas_price;
+
+        let mut rejected_access_keys: Vec<String> = vec![];
+        let mut selected_access_key: Option<(
+            crate::common::AccessKeyPrivilege,
+            near_crypto::PublicKey,
+        )> = None;
+        for access_key in access_key_view.keys {
+            let keychain_public_key = keychain_public_keys
+                .iter()
+                .find(|(_, public_key)| *public_key == access_key.public_key);
+            let (location, public_key) = match keychain_public_key {
+                Some(keychain_public_key) => keychain_public_key,
+                None => {
+                    rejected_access_keys.push(format!(
+                        "{}: the private key is not found in the keychain",
+                        access_key.public_key
+                    ));
+                    continue;
+                }
+            };
+            match crate::common::check_access_key_permission(
+                &access_key.access_key.permission,
+                prepopulated_unsigned_transaction,
+                gas_price,
+            ) {
+                Ok(privilege) => {
+                    let is_less_privileged = match &selected_access_key {
+                        Some((selected_privilege, _)) => privilege < *selected_privilege,
+                        None => true,
+                    };
+                    if is_less_privileged {
+                        selected_access_key = Some((privilege, public_key.clone()));
+                    }
+                }
+                Err(reason) => rejected_access_keys.push(format!(
+                    "{} ({}): {}",
+                    access_key.public_key, location, reason
+                )),
+            }
+        }
+        match selected_access_key {
+            Some((privilege, public_key)) => {
+                println!(
+                    "Access key {} was selected from the keychain as the least privileged key that can sign this transaction ({}).",
+                    public_key, privilege
+                );
+                Ok(public_key)
+            }
+            None => Err(color_eyre::Report::msg(format!(
+                "None of the access keys in the keychain can sign this transaction:\n{}",
+                rejected_access_keys
+                    .iter()
+                    .map(|reason| format!("  - {}", reason))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ))),
+        }
+    }
+
     pub async fn process(
         self,
         prepopulated_unsigned_transaction: near_primitives::transaction::Transaction,
         connection_config: Option<crate::common::ConnectionConfig>,
     ) -> color_eyre::eyre::Result<Option<near_primitives::views::FinalExecutionOutcomeView>> {
-        let home_dir = dirs::home_dir().expect("Impossible to get your home dir!");
-        let file_name = format!("{}.json", prepopulated_unsigned_transaction.signer_id);
-        let mut path = std::path::PathBuf::from(&home_dir);
-
-        let data_path: std::path::PathBuf = match &connection_config {
-            None => {
-                let dir_name = crate::consts::DIR_NAME_KEY_CHAIN;
-                path.push(dir_name);
-                path.push(file_name);
-                path
+        let dir_name = match &connection_config {
+            None => crate::consts::DIR_NAME_KEY_CHAIN,
+            Some(network_connection_config) => network_connection_config.dir_name(),
+        }
+        .to_owned();
+        let mut keychain_public_keys = crate::common::read_keychain_access_keys(
+            &dir_name,
+            &prepopulated_unsigned_transaction.signer_id,
+        )
+        .into_iter()
+        .map(|(path, user)| (path.display().to_string(), user.public_key))
+        .collect::<Vec<_>>();
+        if let Some(agent_response) =
+            crate::common::agent_request(&crate::common::AgentRequest::ListKeys {
+                dir_name: dir_name.clone(),
+                account_id: prepopulated_unsigned_transaction.signer_id.clone(),
+            })?
+        {
+            for public_key in agent_response.public_keys {
+                if !keychain_public_keys
+                    .iter()
+                    .any(|(_, keychain_public_key)| *keychain_public_key == public_key)
+                {
+                    keychain_public_keys.push(("held by the agent".to_owned(), public_key));
+                }
             }
+        }
+        let signer_public_key = match &connection_config {
+            // The account file is listed first, its key was used to prompt for the nonce.
+            None => keychain_public_keys
+                .first()
+                .map(|(_, public_key)| public_key.clone())
+                .ok_or_else(|| {
+                    color_eyre::Report::msg(
+                        "There are no access keys found in the keychain for the signer account.",
+                    )
+                })?,
             Some(network_connection_config) => {
-                let dir_name = network_connection_config.dir_name();
-                path.push(dir_name);
-                path.push(file_name);
-
-                if path.exists() {
-                    path
-                } else {
-                    let query_view_method_response = self
-                        .rpc_client(network_connection_config.rpc_url().as_str())
-                        .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
-                            block_reference: near_primitives::types::Finality::Final.into(),
-                            request: near_primitives::views::QueryRequest::ViewAccessKeyList {
-                                account_id: prepopulated_unsigned_transaction.signer_id.clone(),
-                            },
-                        })
-                        .await
-                        .map_err(|err| {
-                            color_eyre::Report::msg(format!(
-                                "Failed to fetch query for view key list: {:?}",
-                                err
-                            ))
-                        })?;
-                    let access_key_view =
-                        if let near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKeyList(
-                            result,
-                        ) = query_view_method_response.kind
-                        {
-                            result
-                        } else {
-                            return Err(color_eyre::Report::msg(format!("Error call result")));
-                        };
-                    let mut path = std::path::PathBuf::from(&home_dir);
-                    path.push(dir_name);
-                    path.push(&prepopulated_unsigned_transaction.signer_id.to_string());
-                    let mut data_path = std::path::PathBuf::new();
-                    'outer: for access_key in access_key_view.keys {
-                        let account_public_key = access_key.public_key.to_string();
-                        let is_full_access_key: bool = match &access_key.access_key.permission {
-                            near_primitives::views::AccessKeyPermissionView::FullAccess => true,
-                            near_primitives::views::AccessKeyPermissionView::FunctionCall {
-                                allowance: _,
-                                receiver_id: _,
-                                method_names: _,
-                            } => false,
-                        };
-                        let dir = path
-                            .read_dir()
-                            .map_err(|err| {
-                                color_eyre::Report::msg(format!("There are no access keys found in the keychain for the signer account. Log in before signing transactions with keychain. {}", err))
-                            })?;
-                        for entry in dir {
-                            if let Ok(entry) = entry {
-                                if entry
-                                    .path()
-                                    .file_stem()
-                                    .unwrap()
-                                    .to_str()
-                                    .unwrap()
-                                    .contains(account_public_key.rsplit(':').next().unwrap())
-                                    && is_full_access_key
-                                {
-                                    data_path.push(entry.path());
-                                    break 'outer;
-                                }
-                            } else {
-                                return Err(color_eyre::Report::msg(format!(
-                                    "There are no access keys found in the keychain for the signer account. Log in before signing transactions with keychain."
-                                )));
-                            };
-                        }
-                    }
-                    data_path
-                }
+                self.select_access_key(
+                    &prepopulated_unsigned_transaction,
+                    network_connection_config,
+                    &keychain_public_keys,
+                )
+                .await?
             }
         };
-        let data = std::fs::read_to_string(data_path).map_err(|err| {
-            color_eyre::Report::msg(format!("Access key file not found! Error: {}", err))
-        })?;
-        let account_json: User = serde_json::from_str(&data)
-            .map_err(|err| color_eyre::Report::msg(format!("Error reading data: {}", err)))?;
-        let sign_with_private_key = super::sign_with_private_key::SignPrivateKey {
-            signer_public_key: account_json.public_key,
-            signer_private_key: account_json.private_key,
-            nonce: self.nonce.clone(),
-            block_hash: self.block_hash.clone(),
-            submit: self.submit.clone(),
+        let keychain_signer = crate::common::KeychainSigner {
+            dir_name,
+            account_id: prepopulated_unsigned_transaction.signer_id.clone(),
+            public_key: signer_public_key.clone(),
         };
-        sign_with_private_key
-            .process(prepopulated_unsigned_transaction, connection_config)
-            .await
+        super::sign_and_submit(
+            prepopulated_unsigned_transaction,
+            signer_public_key,
+            connection_config,
+            &keychain_signer,
+            super::SubmitOptions {
+                nonce: self.nonce,
+                block_hash: self.block_hash,
+                submit: self.submit,
+                review: !self.skip_review,
+                ignore_preflight_errors: self.ignore_preflight_errors,
+                policy_network: self.policy_network,
+            },
+        )
+        .await
     }
 }
@@ -1,5 +1,6 @@
 use dialoguer::Input;
-use near_primitives::borsh::BorshSerialize;
+
+use crate::common::TransactionSigner;
 
 /// Sign constructed transaction with Ledger
 #[derive(Debug, Default, Clone, clap::Clap)]
@@ -12,9 +13,18 @@ pub struct CliSignLedger {
     #[clap(long)]
     seed_phrase_hd_path: Option<slip10::BIP32Path>,
     #[clap(long)]
-    nonce: Option<u64>,
+    pub(super) nonce: Option<u64>,
     #[clap(long)]
-    block_hash: Option<near_primitives::hash::CryptoHash>,
+    pub(super) block_hash: Option<near_primitives::hash::CryptoHash>,
+    /// Sign the transaction without reviewing its cost and risks first (for scripts and CI)
+    #[clap(long)]
+    skip_review: bool,
+    /// Send the transaction even when the pre-flight checks predict that it fails
+    #[clap(long)]
+    ignore_preflight_errors: bool,
+    /// The network whose signing policy applies when signing offline (testnet, mainnet or betanet)
+    #[clap(long)]
+    policy_network: Option<String>,
     #[clap(subcommand)]
     submit: Option<super::Submit>,
 }
@@ -26,6 +36,9 @@ pub struct SignLedger {
     nonce: Option<u64>,
     block_hash: Option<near_primitives::hash::CryptoHash>,
     pub submit: Option<super::Submit>,
+    pub skip_review: bool,
+    pub ignore_preflight_errors: bool,
+    pub policy_network: Option<String>,
 }
 
 impl CliSignLedger {
@@ -35,6 +48,16 @@ impl CliSignLedger {
             .as_ref()
             .map(|subcommand| subcommand.to_cli_args())
             .unwrap_or_default();
+        if let Some(policy_network) = &self.policy_network {
+            args.push_front(policy_network.to_owned());
+            args.push_front("--policy-network".to_owned());
+        }
+        if self.ignore_preflight_errors {
+            args.push_front("--ignore-preflight-errors".to_owned());
+        }
+        if self.skip_review {
+            args.push_front("--skip-review".to_owned());
+        }
         if let Some(nonce) = &self.nonce {
             args.push_front(nonce.to_string());
             args.push_front("--nonce".to_owned())
@@ -54,6 +77,9 @@ impl From<SignLedger> for CliSignLedger {
             nonce: sign_ledger.nonce,
             block_hash: sign_ledger.block_hash,
             submit: sign_ledger.submit.into(),
+            skip_review: sign_ledger.skip_review,
+            ignore_preflight_errors: sign_ledger.ignore_preflight_errors,
+            policy_network: sign_ledger.policy_network,
         }
     }
 }
@@ -67,21 +93,8 @@ impl SignLedger {
             Some(hd_path) => hd_path,
             None => SignLedger::input_seed_phrase_hd_path(),
         };
-        println!(
-            "Please allow getting the PublicKey on Ledger device (HD Path: {})",
-            seed_phrase_hd_path
-        );
-        let public_key = actix::System::new()
-            .block_on(async { near_ledger::get_public_key(seed_phrase_hd_path.clone()).await })
-            .map_err(|near_ledger_error| {
-                color_eyre::Report::msg(format!(
-                    "An error occurred while trying to get PublicKey from Ledger device: {:?}",
-                    near_ledger_error
-                ))
-            })?;
-        let signer_public_key = near_crypto::PublicKey::ED25519(
-            near_crypto::ED25519PublicKey::from(public_key.to_bytes()),
-        );
+        let ledger_signer = crate::common::LedgerSigner::new(seed_phrase_hd_path.clone())?;
+        let signer_public_key = actix::System::new().block_on(ledger_signer.public_key())?;
         let submit: Option<super::Submit> = item.submit;
         match connection_config {
             Some(_) => Ok(Self {
@@ -90,6 +103,9 @@ impl SignLedger {
                 nonce: None,
                 block_hash: None,
                 submit,
+                skip_review: item.skip_review,
+                ignore_preflight_errors: item.ignore_preflight_errors,
+                policy_network: item.policy_network,
             }),
             None => {
                 let nonce: u64 = match item.nonce {
@@ -100,12 +116,19 @@ impl SignLedger {
                     Some(cli_block_hash) => cli_block_hash,
                     None => super::input_block_hash(),
                 };
+                let policy_network = match item.policy_network {
+                    Some(policy_network) => policy_network,
+                    None => crate::common::input_signing_policy_network(),
+                };
                 Ok(Self {
                     seed_phrase_hd_path,
                     signer_public_key,
                     nonce: Some(nonce),
                     block_hash: Some(block_hash),
                     submit,
+                    skip_review: item.skip_review,
+                    ignore_preflight_errors: item.ignore_preflight_errors,
+                    policy_network: Some(policy_network),
                 })
             }
         }
@@ -113,10 +136,6 @@ impl SignLedger {
 }
 
 impl SignLedger {
-    fn rpc_client(self, selected_server_url: &str) -> near_jsonrpc_client::JsonRpcClient {
-        near_jsonrpc_client::new_client(&selected_server_url)
-    }
-
     pub fn input_seed_phrase_hd_path() -> slip10::BIP32Path {
         Input::new()
             .with_prompt("Enter seed phrase HD Path (if you not sure leave blank for default)")
@@ -130,155 +149,85 @@ impl SignLedger {
         prepopulated_unsigned_transaction: near_primitives::transaction::Transaction,
         connection_config: Option<crate::common::ConnectionConfig>,
     ) -> color_eyre::eyre::Result<Option<near_primitives::views::FinalExecutionOutcomeView>> {
-        let seed_phrase_hd_path = self.seed_phrase_hd_path.clone();
-        let public_key = self.signer_public_key.clone();
-        let nonce = self.nonce.unwrap_or_default().clone();
-        let block_hash = self.block_hash.unwrap_or_default().clone();
-        let submit: Option<super::Submit> = self.submit.clone();
-        match connection_config.clone() {
-            None => {
-                let unsigned_transaction = near_primitives::transaction::Transaction {
-                    public_key,
-                    nonce,
-                    block_hash,
-                    ..prepopulated_unsigned_transaction
-                };
-                println!("\nUnsigned transaction:\n");
-                crate::common::print_transaction(unsigned_transaction.clone());
-                println!(
-                    "Confirm transaction signing on your Ledger device (HD Path: {})",
-                    seed_phrase_hd_path,
-                );
-                let signature = match near_ledger::sign_transaction(
-                    unsigned_transaction
-                        .try_to_vec()
-                        .expect("Transaction is not expected to fail on serialization"),
-                    seed_phrase_hd_path,
-                )
-                .await
-                {
-                    Ok(signature) => near_crypto::Signature::from_parts(
-                        near_crypto::KeyType::ED25519,
-                        &signature,
-                    )
-                    .expect("Signature is not expected to fail on deserialization"),
-                    Err(near_ledger_error) => {
-                        return Err(color_eyre::Report::msg(format!(
-                            "Error occurred while signing the transaction: {:?}",
-                            near_ledger_error
-                        )));
-                    }
-                };
+        let ledger_signer = crate::common::LedgerSigner::new(self.seed_phrase_hd_path.clone())?;
+        super::sign_and_submit(
+            prepopulated_unsigned_transaction,
+            self.signer_public_key,
+            connection_config,
+            &ledger_signer,
+            super::SubmitOptions {
+                nonce: self.nonce,
+                block_hash: self.block_hash,
+                submit: self.submit,
+                review: !self.skip_review,
+                ignore_preflight_errors: self.ignore_preflight_errors,
+                policy_network: self.policy_network,
+            },
+        )
+        .await
+    }
+}
 
-                let signed_transaction = near_primitives::transaction::SignedTransaction::new(
-                    signature,
-                    unsigned_transaction,
-                );
-                let serialize_to_base64 = near_primitives::serialize::to_base64(
-                    signed_transaction
-                        .try_to_vec()
-                        .expect("Transaction is not expected to fail on serialization"),
-                );
-                println!("Your transaction was signed successfully.");
-                match submit {
-                    Some(submit) => submit.process_offline(serialize_to_base64),
-                    None => {
-                        let submit = super::Submit::choose_submit(connection_config.clone());
-                        submit.process_offline(serialize_to_base64)
-                    }
-                }
-            }
-            Some(network_connection_config) => {
-                let online_signer_access_key_response = self
-                    .rpc_client(network_connection_config.rpc_url().as_str())
-                    .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
-                        block_reference: near_primitives::types::Finality::Final.into(),
-                        request: near_primitives::views::QueryRequest::ViewAccessKey {
-                            account_id: prepopulated_unsigned_transaction.signer_id.clone(),
-                            public_key: public_key.clone(),
-                        },
-                    })
-                    .await
-                    .map_err(|err| {
-                        color_eyre::Report::msg(format!(
-                            "Failed to fetch public key information for nonce: {:?}",
-                            err
-                        ))
-                    })?;
-                let current_nonce =
-                    if let near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKey(
-                        online_signer_access_key,
-                    ) = online_signer_access_key_response.kind
-                    {
-                        online_signer_access_key.nonce
-                    } else {
-                        return Err(color_eyre::Report::msg(format!("Error current_nonce")));
-                    };
-                let unsigned_transaction = near_primitives::transaction::Transaction {
-                    public_key,
-                    block_hash: online_signer_access_key_response.block_hash,
-                    nonce: current_nonce + 1,
-                    ..prepopulated_unsigned_transaction
-                };
-                println!("\nUnsigned transaction:\n");
-                crate::common::print_transaction(unsigned_transaction.clone());
-                println!(
-                    "Confirm transaction signing on your Ledger device (HD Path: {})",
-                    seed_phrase_hd_path,
-                );
-                let signature = match near_ledger::sign_transaction(
-                    unsigned_transaction
-                        .try_to_vec()
-                        .expect("Transaction is not expected to fail on serialization"),
-                    seed_phrase_hd_path,
-                )
-                .await
-                {
-                    Ok(signature) => near_crypto::Signature::from_parts(
-                        near_crypto::KeyType::ED25519,
-                        &signature,
-                    )
-                    .expect("Signature is not expected to fail on deserialization"),
-                    Err(near_ledger_error) => {
-                        return Err(color_eyre::Report::msg(format!(
-                            "Error occurred while signing the transaction: {:?}",
-                            near_ledger_error
-                        )));
-                    }
-                };
+#[cfg(test)]
+mod tests {
+    use crate::common::tests::emulated_ledger_signer;
+    use crate::common::TransactionSigner;
 
-                let signed_transaction = near_primitives::transaction::SignedTransaction::new(
-                    signature,
-                    unsigned_transaction,
-                );
-                let serialize_to_base64 = near_primitives::serialize::to_base64(
-                    signed_transaction
-                        .try_to_vec()
-                        .expect("Transaction is not expected to fail on serialization"),
-                );
-                println!("Your transaction was signed successfully.");
-                match submit {
-                    None => {
-                        let submit = super::Submit::choose_submit(connection_config);
-                        submit
-                            .process_online(
-                                network_connection_config,
-                                signed_transaction,
-                                serialize_to_base64,
-                            )
-                            .await
-                    }
-                    Some(submit) => {
-                        submit
-                            .process_online(
-                                network_connection_config,
-                                signed_transaction,
-                                serialize_to_base64,
-                            )
-                            .await
-                    }
-                }
-            }
+    // The transactions are signed below `sign_and_submit`, so the tests do not depend on the
+    // signing policy files of the home directory
+
+    fn unsigned_transaction(
+        ledger_signer: &crate::common::LedgerSigner,
+    ) -> near_primitives::transaction::Transaction {
+        near_primitives::transaction::Transaction {
+            signer_id: "alice.testnet".parse().unwrap(),
+            public_key: actix::System::new()
+                .block_on(ledger_signer.public_key())
+                .unwrap(),
+            nonce: 7,
+            receiver_id: "contract.testnet".parse().unwrap(),
+            block_hash: near_primitives::hash::hash(b"recent block"),
+            actions: vec![near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: "set_greeting".to_owned(),
+                    args: vec![],
+                    gas: 10u64.pow(12),
+                    deposit: 0,
+                },
+            )],
         }
     }
+
+    #[test]
+    fn signature_verifies_against_the_transaction_hash() {
+        let ledger_signer = emulated_ledger_signer("44'/397'/0'/0'/2'", false);
+        let unsigned_transaction = unsigned_transaction(&ledger_signer);
+        let signed_transaction = actix::System::new()
+            .block_on(super::super::sign(
+                unsigned_transaction.clone(),
+                &ledger_signer,
+            ))
+            .unwrap();
+        let transaction_hash = unsigned_transaction.get_hash_and_size().0;
+        assert_eq!(signed_transaction.get_hash(), transaction_hash);
+        assert!(signed_transaction
+            .signature
+            .verify(transaction_hash.as_ref(), &unsigned_transaction.public_key));
+    }
+
+    #[test]
+    fn rejection_on_the_ledger_cancels_the_transaction() {
+        let unsigned_transaction =
+            unsigned_transaction(&emulated_ledger_signer("44'/397'/0'/0'/1'", false));
+        let error = actix::System::new()
+            .block_on(super::super::sign(
+                unsigned_transaction,
+                &emulated_ledger_signer("44'/397'/0'/0'/1'", true),
+            ))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The request was rejected on the Ledger device"
+        );
+    }
 }
@@ -1,5 +1,3 @@
-use near_primitives::borsh::BorshSerialize;
-
 /// подписание сформированной транзакции с помощью личных ключей
 #[derive(Debug, Default, Clone, clap::Clap)]
 #[clap(
@@ -13,9 +11,18 @@ pub struct CliSignPrivateKey {
     #[clap(long)]
     signer_private_key: Option<near_crypto::SecretKey>,
     #[clap(long)]
-    nonce: Option<u64>,
+    pub(super) nonce: Option<u64>,
+    #[clap(long)]
+    pub(super) block_hash: Option<near_primitives::hash::CryptoHash>,
+    /// Sign the transaction without reviewing its cost and risks first (for scripts and CI)
+    #[clap(long)]
+    skip_review: bool,
+    /// Send the transaction even when the pre-flight checks predict that it fails
     #[clap(long)]
-    block_hash: Option<near_primitives::hash::CryptoHash>,
+    ignore_preflight_errors: bool,
+    /// The network whose signing policy applies when signing offline (testnet, mainnet or betanet)
+    #[clap(long)]
+    policy_network: Option<String>,
     #[clap(subcommand)]
     submit: Option<super::Submit>,
 }
@@ -27,6 +34,9 @@ pub struct SignPrivateKey {
     pub nonce: Option<u64>,
     pub block_hash: Option<near_primitives::hash::CryptoHash>,
     pub submit: Option<super::Submit>,
+    pub skip_review: bool,
+    pub ignore_preflight_errors: bool,
+    pub policy_network: Option<String>,
 }
 
 impl CliSignPrivateKey {
@@ -36,6 +46,16 @@ impl CliSignPrivateKey {
             .as_ref()
             .map(|subcommand| subcommand.to_cli_args())
             .unwrap_or_default();
+        if let Some(policy_network) = &self.policy_network {
+            args.push_front(policy_network.to_owned());
+            args.push_front("--policy-network".to_owned());
+        }
+        if self.ignore_preflight_errors {
+            args.push_front("--ignore-preflight-errors".to_owned());
+        }
+        if self.skip_review {
+            args.push_front("--skip-review".to_owned());
+        }
         if let Some(block_hash) = &self.block_hash {
             args.push_front(block_hash.to_string());
             args.push_front("--block-hash".to_owned())
@@ -64,6 +84,9 @@ impl From<SignPrivateKey> for CliSignPrivateKey {
             nonce: sign_private_key.nonce,
             block_hash: sign_private_key.block_hash,
             submit: sign_private_key.submit,
+            skip_review: sign_private_key.skip_review,
+            ignore_preflight_errors: sign_private_key.ignore_preflight_errors,
+            policy_network: sign_private_key.policy_network,
         }
     }
 }
@@ -89,6 +112,9 @@ impl SignPrivateKey {
                 nonce: None,
                 block_hash: None,
                 submit,
+                skip_review: item.skip_review,
+                ignore_preflight_errors: item.ignore_preflight_errors,
+                policy_network: item.policy_network,
             },
             None => {
                 let nonce: u64 = match item.nonce {
@@ -99,6 +125,10 @@ impl SignPrivateKey {
                     Some(cli_block_hash) => cli_block_hash,
                     None => super::input_block_hash(),
                 };
+                let policy_network = match item.policy_network {
+                    Some(policy_network) => policy_network,
+                    None => crate::common::input_signing_policy_network(),
+                };
                 let public_key_origin: near_crypto::PublicKey =
                     near_crypto::SecretKey::public_key(&signer_private_key);
                 if &signer_public_key == &public_key_origin {
@@ -108,6 +138,9 @@ impl SignPrivateKey {
                         nonce: Some(nonce),
                         block_hash: Some(block_hash),
                         submit,
+                        skip_review: item.skip_review,
+                        ignore_preflight_errors: item.ignore_preflight_errors,
+                        policy_network: Some(policy_network),
                     }
                 } else {
                     println!("\nError: The key pair does not match. Re-enter the keys.\n");
@@ -122,6 +155,9 @@ impl SignPrivateKey {
                             nonce: Some(nonce),
                             block_hash: Some(block_hash),
                             submit: None,
+                            skip_review: item.skip_review,
+                            ignore_preflight_errors: item.ignore_preflight_errors,
+                            policy_network: Some(policy_network),
                         },
                         connection_config,
                     )
@@ -132,119 +168,27 @@ impl SignPrivateKey {
 }
 
 impl SignPrivateKey {
-    fn rpc_client(self, selected_server_url: &str) -> near_jsonrpc_client::JsonRpcClient {
-        near_jsonrpc_client::new_client(&selected_server_url)
-    }
-
     pub async fn process(
         self,
         prepopulated_unsigned_transaction: near_primitives::transaction::Transaction,
         connection_config: Option<crate::common::ConnectionConfig>,
     ) -> color_eyre::eyre::Result<Option<near_primitives::views::FinalExecutionOutcomeView>> {
-        let public_key: near_crypto::PublicKey = self.signer_public_key.clone();
-        let signer_secret_key: near_crypto::SecretKey = self.signer_private_key.clone();
-        let nonce: u64 = self.nonce.unwrap_or_default().clone();
-        let block_hash: near_primitives::hash::CryptoHash =
-            self.block_hash.unwrap_or_default().clone();
-        let submit: Option<super::Submit> = self.submit.clone();
-        match connection_config.clone() {
-            None => {
-                let unsigned_transaction = near_primitives::transaction::Transaction {
-                    public_key,
-                    nonce,
-                    block_hash,
-                    ..prepopulated_unsigned_transaction
-                };
-                let signature =
-                    signer_secret_key.sign(unsigned_transaction.get_hash_and_size().0.as_ref());
-                let signed_transaction = near_primitives::transaction::SignedTransaction::new(
-                    signature,
-                    unsigned_transaction,
-                );
-                let serialize_to_base64 = near_primitives::serialize::to_base64(
-                    signed_transaction
-                        .try_to_vec()
-                        .expect("Transaction is not expected to fail on serialization"),
-                );
-                println!("\nSigned transaction:\n");
-                crate::common::print_transaction(signed_transaction.transaction.clone());
-                println!("Your transaction was signed successfully.");
-                match submit {
-                    Some(submit) => submit.process_offline(serialize_to_base64),
-                    None => {
-                        let submit = super::Submit::choose_submit(connection_config.clone());
-                        submit.process_offline(serialize_to_base64)
-                    }
-                }
-            }
-            Some(network_connection_config) => {
-                let online_signer_access_key_response = self
-                    .rpc_client(network_connection_config.rpc_url().as_str())
-                    .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
-                        block_reference: near_primitives::types::Finality::Final.into(),
-                        request: near_primitives::views::QueryRequest::ViewAccessKey {
-                            account_id: prepopulated_unsigned_transaction.signer_id.clone(),
-                            public_key: public_key.clone(),
-                        },
-                    })
-                    .await
-                    .map_err(|err| {
-                        color_eyre::Report::msg(format!(
-                            "Failed to fetch public key information for nonce: {:?}",
-                            err
-                        ))
-                    })?;
-                let current_nonce =
-                    if let near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKey(
-                        online_signer_access_key,
-                    ) = online_signer_access_key_response.kind
-                    {
-                        online_signer_access_key.nonce
-                    } else {
-                        return Err(color_eyre::Report::msg(format!("Error current_nonce")));
-                    };
-                let unsigned_transaction = near_primitives::transaction::Transaction {
-                    public_key,
-                    block_hash: online_signer_access_key_response.block_hash,
-                    nonce: current_nonce + 1,
-                    ..prepopulated_unsigned_transaction
-                };
-                let signature =
-                    signer_secret_key.sign(unsigned_transaction.get_hash_and_size().0.as_ref());
-                let signed_transaction = near_primitives::transaction::SignedTransaction::new(
-                    signature,
-                    unsigned_transaction,
-                );
-                let serialize_to_base64 = near_primitives::serialize::to_base64(
-                    signed_transaction
-                        .try_to_vec()
-                        .expect("Transaction is not expected to fail on serialization"),
-                );
-                println!("\nSigned transaction:\n");
-                crate::common::print_transaction(signed_transaction.transaction.clone());
-                println!("Your transaction was signed successfully.");
-                match submit {
-                    None => {
-                        let submit = super::Submit::choose_submit(connection_config);
-                        submit
-                            .process_online(
-                                network_connection_config,
-                                signed_transaction,
-                                serialize_to_base64,
-                            )
-                            .await
-                    }
-                    Some(submit) => {
-                        submit
-                            .process_online(
-                                network_connection_config,
-                                signed_transaction,
-                                serialize_to_base64,
-                            )
-                            .await
-                    }
-                }
-            }
-        }
+        super::sign_and_submit(
+            prepopulated_unsigned_transaction,
+            self.signer_public_key,
+            connection_config,
+            &crate::common::SecretKeySigner {
+                secret_key: self.signer_private_key,
+            },
+            super::SubmitOptions {
+                nonce: self.nonce,
+                block_hash: self.block_hash,
+                submit: self.submit,
+                review: !self.skip_review,
+                ignore_preflight_errors: self.ignore_preflight_errors,
+                policy_network: self.policy_network,
+            },
+        )
+        .await
     }
 }
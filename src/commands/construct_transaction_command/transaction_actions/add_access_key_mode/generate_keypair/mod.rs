@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 /// Generate a key pair of private and public keys (use it anywhere you need
-/// Ed25519 keys)
+/// Ed25519 or Secp256k1 keys)
 #[derive(Debug, Default, Clone, clap::Clap)]
 #[clap(
     setting(clap::AppSettings::ColoredHelp),
@@ -9,22 +9,35 @@ use std::str::FromStr;
     setting(clap::AppSettings::VersionlessSubcommands)
 )]
 pub struct CliGenerateKeypair {
+    #[clap(long, default_value = "ed25519")]
+    key_type: crate::common::KeyType,
+    /// BIP-39 passphrase that protects the generated master seed phrase
+    #[clap(long)]
+    seed_phrase_passphrase: Option<String>,
     #[clap(subcommand)]
     permission: Option<super::add_access_key::CliAccessKeyPermission>,
 }
 
 #[derive(Debug, Clone)]
 pub struct GenerateKeypair {
+    pub key_type: crate::common::KeyType,
+    pub seed_phrase_passphrase: Option<String>,
     pub permission: super::add_access_key::AccessKeyPermission,
 }
 
 impl CliGenerateKeypair {
     pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
-        let args = self
+        let mut args = self
             .permission
             .as_ref()
             .map(|subcommand| subcommand.to_cli_args())
             .unwrap_or_default();
+        if let Some(seed_phrase_passphrase) = &self.seed_phrase_passphrase {
+            args.push_front(seed_phrase_passphrase.to_owned());
+            args.push_front("--seed-phrase-passphrase".to_owned());
+        }
+        args.push_front(self.key_type.to_string());
+        args.push_front("--key-type".to_owned());
         args
     }
 }
@@ -32,6 +45,8 @@ impl CliGenerateKeypair {
 impl From<GenerateKeypair> for CliGenerateKeypair {
     fn from(generate_keypair: GenerateKeypair) -> Self {
         Self {
+            key_type: generate_keypair.key_type,
+            seed_phrase_passphrase: generate_keypair.seed_phrase_passphrase,
             permission: Some(generate_keypair.permission.into()),
         }
     }
@@ -54,7 +69,11 @@ impl GenerateKeypair {
                 sender_account_id,
             )?,
         };
-        Ok(Self { permission })
+        Ok(Self {
+            key_type: item.key_type,
+            seed_phrase_passphrase: item.seed_phrase_passphrase,
+            permission,
+        })
     }
 }
 
@@ -65,7 +84,11 @@ impl GenerateKeypair {
         network_connection_config: Option<crate::common::ConnectionConfig>,
     ) -> crate::CliResult {
         let key_pair_properties: crate::common::KeyPairProperties =
-            crate::common::generate_keypair().await?;
+            crate::common::generate_keypair(
+                self.key_type,
+                self.seed_phrase_passphrase.as_deref().unwrap_or_default(),
+            )
+            .await?;
         crate::common::save_access_key_to_keychain(
             network_connection_config.clone(),
             key_pair_properties.clone(),
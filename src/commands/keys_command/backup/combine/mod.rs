@@ -0,0 +1,186 @@
+use std::str::FromStr;
+
+use dialoguer::{theme::ColorfulTheme, Input, Select};
+
+/// Reconstruct a master seed phrase or a private key from Shamir secret shares
+#[derive(Debug, Clone, clap::Clap)]
+pub struct CliCombine {
+    /// Secret share in the `near-sss-1:<threshold>:<index>:<hex>` format; can be repeated
+    #[clap(long = "share")]
+    pub shares: Vec<crate::common::SecretShare>,
+    /// Public key the reconstructed secret must correspond to
+    #[clap(long)]
+    pub public_key: Option<near_crypto::PublicKey>,
+    /// Import the reconstructed key to the keychain for this account
+    #[clap(long)]
+    pub account_id: Option<near_primitives::types::AccountId>,
+    /// Network whose keychain the key is imported to (testnet, mainnet, betanet or an RPC URL)
+    #[clap(long)]
+    pub network: Option<String>,
+    /// BIP-39 passphrase that protects the master seed phrase
+    #[clap(long)]
+    pub seed_phrase_passphrase: Option<String>,
+    #[clap(long, default_value = "m/44'/397'/0'")]
+    pub seed_phrase_hd_path: slip10::BIP32Path,
+    #[clap(long, default_value = "ed25519")]
+    pub key_type: crate::common::KeyType,
+    #[clap(long, default_value = "plaintext")]
+    pub format: crate::common::OutputFormat,
+}
+
+impl Default for CliCombine {
+    fn default() -> Self {
+        Self {
+            shares: vec![],
+            public_key: None,
+            account_id: None,
+            network: None,
+            seed_phrase_passphrase: None,
+            seed_phrase_hd_path: slip10::BIP32Path::from_str("m/44'/397'/0'").unwrap(),
+            key_type: crate::common::KeyType::Ed25519,
+            format: crate::common::OutputFormat::Plaintext,
+        }
+    }
+}
+
+impl CliCombine {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        args.push_front(self.format.to_string());
+        args.push_front("--format".to_owned());
+        args.push_front(self.key_type.to_string());
+        args.push_front("--key-type".to_owned());
+        args.push_front(self.seed_phrase_hd_path.to_string());
+        args.push_front("--seed-phrase-hd-path".to_owned());
+        if let Some(seed_phrase_passphrase) = &self.seed_phrase_passphrase {
+            args.push_front(seed_phrase_passphrase.to_owned());
+            args.push_front("--seed-phrase-passphrase".to_owned());
+        }
+        if let Some(network) = &self.network {
+            args.push_front(network.to_owned());
+            args.push_front("--network".to_owned());
+        }
+        if let Some(account_id) = &self.account_id {
+            args.push_front(account_id.to_string());
+            args.push_front("--account-id".to_owned());
+        }
+        if let Some(public_key) = &self.public_key {
+            args.push_front(public_key.to_string());
+            args.push_front("--public-key".to_owned());
+        }
+        for share in self.shares.iter().rev() {
+            args.push_front(share.to_string());
+            args.push_front("--share".to_owned());
+        }
+        args
+    }
+
+    fn input_share(shares_count: usize) -> crate::common::SecretShare {
+        Input::new()
+            .with_prompt(format!("Enter the share #{}", shares_count + 1))
+            .interact_text()
+            .unwrap()
+    }
+
+    fn choose_network() -> crate::common::ConnectionConfig {
+        println!();
+        let networks = [
+            crate::common::ConnectionConfig::Testnet,
+            crate::common::ConnectionConfig::Mainnet,
+            crate::common::ConnectionConfig::Betanet,
+        ];
+        let selected_network = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select the network whose keychain the key is imported to")
+            .items(&["Testnet", "Mainnet", "Betanet"])
+            .default(0)
+            .interact()
+            .unwrap();
+        networks[selected_network].clone()
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        let connection_config = match (&self.account_id, &self.network) {
+            (None, None) => None,
+            (None, Some(_)) => {
+                return Err(color_eyre::Report::msg(
+                    "--network is used together with --account-id",
+                ))
+            }
+            (Some(_), Some(network)) => {
+                Some(crate::common::ConnectionConfig::from_network_name(network)?)
+            }
+            (Some(_), None) => Some(Self::choose_network()),
+        };
+        let mut shares = self.shares.clone();
+        loop {
+            let threshold = match shares.first() {
+                Some(share) => share.threshold as usize,
+                None => 1,
+            };
+            if shares.len() >= threshold {
+                break;
+            }
+            shares.push(Self::input_share(shares.len()));
+        }
+        let secret = String::from_utf8(crate::common::combine_secret_shares(&shares)?)
+            .map_err(|_| {
+                color_eyre::Report::msg(
+                    "The shares do not reconstruct a valid secret, check that they belong to the same backup",
+                )
+            })?;
+        let key_pair_properties = super::key_pair_properties_from_secret(
+            &secret,
+            &self.seed_phrase_hd_path,
+            self.seed_phrase_passphrase.as_deref().unwrap_or_default(),
+            self.key_type,
+        )?;
+        if let Some(public_key) = &self.public_key {
+            if public_key.to_string() != key_pair_properties.public_key_str {
+                return Err(color_eyre::Report::msg(format!(
+                    "The reconstructed secret corresponds to the public key {}, not {}",
+                    key_pair_properties.public_key_str, public_key
+                )));
+            }
+            println!(
+                "The reconstructed secret matches the public key {}",
+                public_key
+            );
+        }
+        if let Some(account_id) = &self.account_id {
+            return crate::common::save_access_key_to_keychain(
+                connection_config,
+                key_pair_properties,
+                &account_id.to_string(),
+            )
+            .await;
+        }
+        match self.format {
+            crate::common::OutputFormat::Plaintext => {
+                if key_pair_properties.master_seed_phrase.is_empty() {
+                    println!("Public Key: {}", key_pair_properties.public_key_str);
+                } else {
+                    println!(
+                        "Master Seed Phrase: {}\nSeed Phrase HD Path: {}\nPublic Key: {}",
+                        key_pair_properties.master_seed_phrase,
+                        key_pair_properties.seed_phrase_hd_path.to_string(),
+                        key_pair_properties.public_key_str,
+                    );
+                }
+                println!("SECRET KEYPAIR: {}", key_pair_properties.secret_keypair_str);
+            }
+            crate::common::OutputFormat::Json => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&serde_json::json!({
+                        "master_seed_phrase": key_pair_properties.master_seed_phrase,
+                        "seed_phrase_hd_path": key_pair_properties.seed_phrase_hd_path.to_string(),
+                        "public_key": key_pair_properties.public_key_str,
+                        "private_key": key_pair_properties.secret_keypair_str,
+                    }))
+                    .unwrap()
+                );
+            }
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+use std::str::FromStr;
+
+use dialoguer::{theme::ColorfulTheme, Select};
+use strum::{EnumDiscriminants, EnumIter, EnumMessage, IntoEnumIterator};
+
+mod combine;
+mod split;
+
+/// Back up a master seed phrase or a private key as Shamir secret shares
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliBackup {
+    #[clap(subcommand)]
+    action: Option<CliBackupAction>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Backup {
+    action: BackupAction,
+}
+
+impl CliBackup {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        self.action
+            .as_ref()
+            .map(|subcommand| subcommand.to_cli_args())
+            .unwrap_or_default()
+    }
+}
+
+impl From<Backup> for CliBackup {
+    fn from(item: Backup) -> Self {
+        Self {
+            action: Some(item.action.into()),
+        }
+    }
+}
+
+impl From<CliBackup> for Backup {
+    fn from(item: CliBackup) -> Self {
+        let action = match item.action {
+            Some(cli_action) => BackupAction::from(cli_action),
+            None => BackupAction::choose_action(),
+        };
+        Self { action }
+    }
+}
+
+impl Backup {
+    pub async fn process(self) -> crate::CliResult {
+        self.action.process().await
+    }
+}
+
+#[derive(Debug, Clone, clap::Clap)]
+pub enum CliBackupAction {
+    /// Split a master seed phrase or a private key into shares
+    Split(self::split::CliSplit),
+    /// Reconstruct a master seed phrase or a private key from shares
+    Combine(self::combine::CliCombine),
+}
+
+#[derive(Debug, Clone, EnumDiscriminants)]
+#[strum_discriminants(derive(EnumMessage, EnumIter))]
+pub enum BackupAction {
+    #[strum_discriminants(strum(
+        message = "Split a master seed phrase or a private key into shares"
+    ))]
+    Split(self::split::CliSplit),
+    #[strum_discriminants(strum(
+        message = "Reconstruct a master seed phrase or a private key from shares"
+    ))]
+    Combine(self::combine::CliCombine),
+}
+
+impl CliBackupAction {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        match self {
+            Self::Split(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("split".to_owned());
+                args
+            }
+            Self::Combine(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("combine".to_owned());
+                args
+            }
+        }
+    }
+}
+
+impl From<BackupAction> for CliBackupAction {
+    fn from(action: BackupAction) -> Self {
+        match action {
+            BackupAction::Split(split) => Self::Split(split),
+            BackupAction::Combine(combine) => Self::Combine(combine),
+        }
+    }
+}
+
+impl From<CliBackupAction> for BackupAction {
+    fn from(item: CliBackupAction) -> Self {
+        match item {
+            CliBackupAction::Split(split) => Self::Split(split),
+            CliBackupAction::Combine(combine) => Self::Combine(combine),
+        }
+    }
+}
+
+impl BackupAction {
+    fn choose_action() -> Self {
+        println!();
+        let variants = BackupActionDiscriminants::iter().collect::<Vec<_>>();
+        let actions = variants
+            .iter()
+            .map(|p| p.get_message().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        let selected_action = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Сhoose what you want to do with the backup")
+            .items(&actions)
+            .default(0)
+            .interact()
+            .unwrap();
+        let cli_action = match variants[selected_action] {
+            BackupActionDiscriminants::Split => CliBackupAction::Split(Default::default()),
+            BackupActionDiscriminants::Combine => CliBackupAction::Combine(Default::default()),
+        };
+        Self::from(cli_action)
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        match self {
+            BackupAction::Split(split) => split.process().await,
+            BackupAction::Combine(combine) => combine.process().await,
+        }
+    }
+}
+
+/// Returns the key pair for the backed up secret, which is either a private key
+/// or a master seed phrase to derive the key from.
+fn key_pair_properties_from_secret(
+    secret: &str,
+    seed_phrase_hd_path: &slip10::BIP32Path,
+    seed_phrase_passphrase: &str,
+    key_type: crate::common::KeyType,
+) -> color_eyre::eyre::Result<crate::common::KeyPairProperties> {
+    match near_crypto::SecretKey::from_str(secret) {
+        Ok(secret_key) => Ok(crate::common::KeyPairProperties {
+            seed_phrase_hd_path: seed_phrase_hd_path.clone(),
+            master_seed_phrase: "".to_owned(),
+            implicit_account_id: None,
+            public_key_str: secret_key.public_key().to_string(),
+            secret_keypair_str: secret_key.to_string(),
+        }),
+        Err(_) => crate::common::get_key_pair_properties_from_seed_phrase(
+            seed_phrase_hd_path.clone(),
+            secret.to_owned(),
+            seed_phrase_passphrase,
+            key_type,
+        )
+        .map_err(|err| {
+            color_eyre::Report::msg(format!(
+                "The secret is neither a private key nor a valid master seed phrase: {}",
+                err
+            ))
+        }),
+    }
+}
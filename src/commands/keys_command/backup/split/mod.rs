@@ -0,0 +1,138 @@
+use std::str::FromStr;
+
+use dialoguer::Input;
+
+/// Split a master seed phrase or a private key into Shamir secret shares
+#[derive(Debug, Clone, clap::Clap)]
+pub struct CliSplit {
+    /// Number of shares required to reconstruct the secret
+    #[clap(long)]
+    pub threshold: Option<u8>,
+    /// Number of shares to create
+    #[clap(long)]
+    pub shares: Option<u8>,
+    /// Master seed phrase or private key to split
+    #[clap(long)]
+    pub secret: Option<String>,
+    /// BIP-39 passphrase that protects the master seed phrase
+    #[clap(long)]
+    pub seed_phrase_passphrase: Option<String>,
+    #[clap(long, default_value = "m/44'/397'/0'")]
+    pub seed_phrase_hd_path: slip10::BIP32Path,
+    #[clap(long, default_value = "ed25519")]
+    pub key_type: crate::common::KeyType,
+    #[clap(long, default_value = "plaintext")]
+    pub format: crate::common::OutputFormat,
+}
+
+impl Default for CliSplit {
+    fn default() -> Self {
+        Self {
+            threshold: None,
+            shares: None,
+            secret: None,
+            seed_phrase_passphrase: None,
+            seed_phrase_hd_path: slip10::BIP32Path::from_str("m/44'/397'/0'").unwrap(),
+            key_type: crate::common::KeyType::Ed25519,
+            format: crate::common::OutputFormat::Plaintext,
+        }
+    }
+}
+
+impl CliSplit {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        args.push_front(self.format.to_string());
+        args.push_front("--format".to_owned());
+        args.push_front(self.key_type.to_string());
+        args.push_front("--key-type".to_owned());
+        args.push_front(self.seed_phrase_hd_path.to_string());
+        args.push_front("--seed-phrase-hd-path".to_owned());
+        if let Some(seed_phrase_passphrase) = &self.seed_phrase_passphrase {
+            args.push_front(seed_phrase_passphrase.to_owned());
+            args.push_front("--seed-phrase-passphrase".to_owned());
+        }
+        if let Some(secret) = &self.secret {
+            args.push_front(secret.to_owned());
+            args.push_front("--secret".to_owned());
+        }
+        if let Some(shares) = &self.shares {
+            args.push_front(shares.to_string());
+            args.push_front("--shares".to_owned());
+        }
+        if let Some(threshold) = &self.threshold {
+            args.push_front(threshold.to_string());
+            args.push_front("--threshold".to_owned());
+        }
+        args
+    }
+
+    fn input_secret() -> String {
+        Input::new()
+            .with_prompt("Enter the master seed phrase or the private key to split")
+            .interact_text()
+            .unwrap()
+    }
+
+    fn input_shares() -> u8 {
+        Input::new()
+            .with_prompt("How many shares do you want to create?")
+            .interact_text()
+            .unwrap()
+    }
+
+    fn input_threshold() -> u8 {
+        Input::new()
+            .with_prompt("How many shares should be required to reconstruct the secret?")
+            .interact_text()
+            .unwrap()
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        let secret = match &self.secret {
+            Some(secret) => secret.trim().to_owned(),
+            None => Self::input_secret().trim().to_owned(),
+        };
+        let shares = match self.shares {
+            Some(shares) => shares,
+            None => Self::input_shares(),
+        };
+        let threshold = match self.threshold {
+            Some(threshold) => threshold,
+            None => Self::input_threshold(),
+        };
+        let key_pair_properties = super::key_pair_properties_from_secret(
+            &secret,
+            &self.seed_phrase_hd_path,
+            self.seed_phrase_passphrase.as_deref().unwrap_or_default(),
+            self.key_type,
+        )?;
+        let secret_shares = crate::common::split_secret(secret.as_bytes(), threshold, shares)?;
+        match self.format {
+            crate::common::OutputFormat::Plaintext => {
+                println!(
+                    "Any {} of the {} shares reconstruct the secret of the public key {}",
+                    threshold, shares, key_pair_properties.public_key_str
+                );
+                for secret_share in secret_shares.iter() {
+                    println!("Share {}: {}", secret_share.index, secret_share);
+                }
+            }
+            crate::common::OutputFormat::Json => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&serde_json::json!({
+                        "threshold": threshold,
+                        "public_key": key_pair_properties.public_key_str,
+                        "shares": secret_shares
+                            .iter()
+                            .map(|secret_share| secret_share.to_string())
+                            .collect::<Vec<String>>(),
+                    }))
+                    .unwrap()
+                );
+            }
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+use dialoguer::{theme::ColorfulTheme, Select};
+use strum::{EnumDiscriminants, EnumIter, EnumMessage, IntoEnumIterator};
+
+mod backup;
+mod recover;
+mod rotate;
+
+/// инструмент выбора to keys action
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliKeysAction {
+    #[clap(subcommand)]
+    action: Option<CliAction>,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeysAction {
+    pub action: Action,
+}
+
+impl CliKeysAction {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        self.action
+            .as_ref()
+            .map(|subcommand| subcommand.to_cli_args())
+            .unwrap_or_default()
+    }
+}
+
+impl From<KeysAction> for CliKeysAction {
+    fn from(item: KeysAction) -> Self {
+        Self {
+            action: Some(item.action.into()),
+        }
+    }
+}
+
+impl From<CliKeysAction> for KeysAction {
+    fn from(item: CliKeysAction) -> Self {
+        let action = match item.action {
+            Some(cli_action) => Action::from(cli_action),
+            None => Action::choose_action(),
+        };
+        Self { action }
+    }
+}
+
+impl KeysAction {
+    pub async fn process(self) -> crate::CliResult {
+        self.action.process().await
+    }
+}
+
+#[derive(Debug, Clone, clap::Clap)]
+pub enum CliAction {
+    /// Replace a full access key of an account with a new one
+    Rotate(self::rotate::CliRotateAccessKey),
+    /// Find the access keys of an account derived from a seed phrase and import them to the keychain
+    Recover(self::recover::CliRecoverAccessKeys),
+    /// Back up a master seed phrase or a private key as Shamir secret shares
+    Backup(self::backup::CliBackup),
+}
+
+#[derive(Debug, Clone, EnumDiscriminants)]
+#[strum_discriminants(derive(EnumMessage, EnumIter))]
+pub enum Action {
+    #[strum_discriminants(strum(
+        message = "Replace a full access key of an account with a new one"
+    ))]
+    Rotate(self::rotate::RotateAccessKey),
+    #[strum_discriminants(strum(
+        message = "Find the access keys of an account derived from a seed phrase"
+    ))]
+    Recover(self::recover::RecoverAccessKeys),
+    #[strum_discriminants(strum(
+        message = "Back up a master seed phrase or a private key as Shamir secret shares"
+    ))]
+    Backup(self::backup::Backup),
+}
+
+impl CliAction {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        match self {
+            Self::Rotate(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("rotate".to_owned());
+                args
+            }
+            Self::Recover(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("recover".to_owned());
+                args
+            }
+            Self::Backup(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("backup".to_owned());
+                args
+            }
+        }
+    }
+}
+
+impl From<Action> for CliAction {
+    fn from(action: Action) -> Self {
+        match action {
+            Action::Rotate(rotate_access_key) => Self::Rotate(rotate_access_key.into()),
+            Action::Recover(recover_access_keys) => Self::Recover(recover_access_keys.into()),
+            Action::Backup(backup) => Self::Backup(backup.into()),
+        }
+    }
+}
+
+impl From<CliAction> for Action {
+    fn from(item: CliAction) -> Self {
+        match item {
+            CliAction::Rotate(cli_rotate_access_key) => Self::Rotate(cli_rotate_access_key.into()),
+            CliAction::Recover(cli_recover_access_keys) => {
+                Self::Recover(cli_recover_access_keys.into())
+            }
+            CliAction::Backup(cli_backup) => Self::Backup(cli_backup.into()),
+        }
+    }
+}
+
+impl Action {
+    fn choose_action() -> Self {
+        println!();
+        let variants = ActionDiscriminants::iter().collect::<Vec<_>>();
+        let actions = variants
+            .iter()
+            .map(|p| p.get_message().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        let selected_action = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Сhoose what you want to do with the keys")
+            .items(&actions)
+            .default(0)
+            .interact()
+            .unwrap();
+        let cli_action = match variants[selected_action] {
+            ActionDiscriminants::Rotate => CliAction::Rotate(Default::default()),
+            ActionDiscriminants::Recover => CliAction::Recover(Default::default()),
+            ActionDiscriminants::Backup => CliAction::Backup(Default::default()),
+        };
+        Self::from(cli_action)
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        match self {
+            Action::Rotate(rotate_access_key) => rotate_access_key.process().await,
+            Action::Recover(recover_access_keys) => recover_access_keys.process().await,
+            Action::Backup(backup) => backup.process().await,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use dialoguer::Input;
+
+pub mod operation_mode;
+
+/// Find the access keys of an account derived from a seed phrase and import them to the keychain
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliRecoverAccessKeys {
+    account_id: Option<near_primitives::types::AccountId>,
+    #[clap(long)]
+    seed_phrase: Option<String>,
+    /// Passphrase candidate to scan; can be repeated, the empty passphrase is always scanned
+    #[clap(long = "passphrase")]
+    seed_phrase_passphrases: Vec<String>,
+    /// The first account index N of the `m/44'/397'/N'` paths to scan
+    #[clap(long, parse(try_from_str = crate::common::parse_hardened_index))]
+    from_index: Option<u32>,
+    /// Number of the `m/44'/397'/N'` paths to scan
+    #[clap(long)]
+    count: Option<u32>,
+    #[clap(long)]
+    key_type: Option<crate::common::KeyType>,
+    #[clap(subcommand)]
+    mode: Option<self::operation_mode::CliMode>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecoverAccessKeys {
+    account_id: near_primitives::types::AccountId,
+    seed_phrase_recovery: SeedPhraseRecovery,
+    mode: self::operation_mode::Mode,
+}
+
+/// The derivation paths and passphrases to scan for the account's access keys.
+#[derive(Debug, Clone)]
+pub struct SeedPhraseRecovery {
+    pub master_seed_phrase: String,
+    pub seed_phrase_passphrases: Vec<String>,
+    pub from_index: u32,
+    pub count: u32,
+    pub key_type: crate::common::KeyType,
+}
+
+impl CliRecoverAccessKeys {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = self
+            .mode
+            .as_ref()
+            .map(|subcommand| subcommand.to_cli_args())
+            .unwrap_or_default();
+        if let Some(key_type) = &self.key_type {
+            args.push_front(key_type.to_string());
+            args.push_front("--key-type".to_owned());
+        };
+        if let Some(count) = &self.count {
+            args.push_front(count.to_string());
+            args.push_front("--count".to_owned());
+        };
+        if let Some(from_index) = &self.from_index {
+            args.push_front(from_index.to_string());
+            args.push_front("--from-index".to_owned());
+        };
+        for seed_phrase_passphrase in self.seed_phrase_passphrases.iter().rev() {
+            args.push_front(seed_phrase_passphrase.to_owned());
+            args.push_front("--passphrase".to_owned());
+        }
+        if let Some(seed_phrase) = &self.seed_phrase {
+            args.push_front(seed_phrase.to_owned());
+            args.push_front("--seed-phrase".to_owned());
+        };
+        if let Some(account_id) = &self.account_id {
+            args.push_front(account_id.to_string());
+        };
+        args
+    }
+}
+
+impl From<RecoverAccessKeys> for CliRecoverAccessKeys {
+    fn from(recover_access_keys: RecoverAccessKeys) -> Self {
+        let seed_phrase_recovery = recover_access_keys.seed_phrase_recovery;
+        Self {
+            account_id: Some(recover_access_keys.account_id),
+            seed_phrase: Some(seed_phrase_recovery.master_seed_phrase),
+            seed_phrase_passphrases: seed_phrase_recovery
+                .seed_phrase_passphrases
+                .into_iter()
+                .filter(|seed_phrase_passphrase| !seed_phrase_passphrase.is_empty())
+                .collect(),
+            from_index: Some(seed_phrase_recovery.from_index),
+            count: Some(seed_phrase_recovery.count),
+            key_type: Some(seed_phrase_recovery.key_type),
+            mode: Some(recover_access_keys.mode.into()),
+        }
+    }
+}
+
+impl From<CliRecoverAccessKeys> for RecoverAccessKeys {
+    fn from(item: CliRecoverAccessKeys) -> Self {
+        let account_id: near_primitives::types::AccountId = match item.account_id {
+            Some(cli_account_id) => cli_account_id,
+            None => RecoverAccessKeys::input_account_id(),
+        };
+        let master_seed_phrase = match item.seed_phrase {
+            Some(cli_seed_phrase) => cli_seed_phrase,
+            None => RecoverAccessKeys::input_master_seed_phrase(),
+        };
+        let mut seed_phrase_passphrases = vec!["".to_owned()];
+        seed_phrase_passphrases.extend(
+            item.seed_phrase_passphrases
+                .into_iter()
+                .filter(|seed_phrase_passphrase| !seed_phrase_passphrase.is_empty()),
+        );
+        let mode = match item.mode {
+            Some(cli_mode) => self::operation_mode::Mode::from(cli_mode),
+            None => self::operation_mode::Mode::choose_mode(),
+        };
+        Self {
+            account_id,
+            seed_phrase_recovery: SeedPhraseRecovery {
+                master_seed_phrase,
+                seed_phrase_passphrases,
+                from_index: item.from_index.unwrap_or(0),
+                count: item.count.unwrap_or(20),
+                key_type: item.key_type.unwrap_or_default(),
+            },
+            mode,
+        }
+    }
+}
+
+impl RecoverAccessKeys {
+    fn input_account_id() -> near_primitives::types::AccountId {
+        println!();
+        Input::new()
+            .with_prompt("Which account do you want to recover the access keys for?")
+            .interact_text()
+            .unwrap()
+    }
+
+    fn input_master_seed_phrase() -> String {
+        Input::new()
+            .with_prompt("Enter the master seed phrase")
+            .interact_text()
+            .unwrap()
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        self.mode
+            .process(self.account_id, self.seed_phrase_recovery)
+            .await
+    }
+}
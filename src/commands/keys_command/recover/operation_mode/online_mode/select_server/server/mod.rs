@@ -0,0 +1,135 @@
+use std::str::FromStr;
+
+use dialoguer::Input;
+
+/// предустановленный RPC-сервер
+#[derive(Debug, Default, Clone, clap::Clap)]
+pub struct CliServer {}
+
+/// данные для custom server
+#[derive(Debug, Default, Clone, clap::Clap)]
+pub struct CliCustomServer {
+    #[clap(long)]
+    pub url: Option<crate::common::AvailableRpcServerUrl>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Server {
+    pub connection_config: crate::common::ConnectionConfig,
+}
+
+impl CliCustomServer {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        if let Some(url) = &self.url {
+            args.push_front(url.to_string());
+            args.push_front("--url".to_string());
+        }
+        args
+    }
+}
+
+impl From<Server> for CliCustomServer {
+    fn from(server: Server) -> Self {
+        Self {
+            url: Some(
+                crate::common::AvailableRpcServerUrl::from_str(
+                    server.connection_config.rpc_url().as_str(),
+                )
+                .unwrap(),
+            ),
+        }
+    }
+}
+
+impl CliServer {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        std::collections::VecDeque::new()
+    }
+}
+
+impl From<Server> for CliServer {
+    fn from(_: Server) -> Self {
+        Self {}
+    }
+}
+
+impl CliServer {
+    pub fn into_server(self, connection_config: crate::common::ConnectionConfig) -> Server {
+        Server { connection_config }
+    }
+}
+
+impl CliCustomServer {
+    pub fn into_server(self) -> Server {
+        let url: crate::common::AvailableRpcServerUrl = match self.url {
+            Some(url) => url,
+            None => Input::new()
+                .with_prompt("What is the RPC endpoint?")
+                .interact_text()
+                .unwrap(),
+        };
+        Server {
+            connection_config: crate::common::ConnectionConfig::Custom { url: url.inner },
+        }
+    }
+}
+
+impl Server {
+    pub async fn process(
+        self,
+        account_id: near_primitives::types::AccountId,
+        seed_phrase_recovery: crate::commands::keys_command::recover::SeedPhraseRecovery,
+    ) -> crate::CliResult {
+        let account_indexes = seed_phrase_recovery.from_index
+            ..seed_phrase_recovery
+                .from_index
+                .saturating_add(seed_phrase_recovery.count);
+        println!(
+            "\nScanning the paths m/44'/397'/{}'..m/44'/397'/{}' with {} passphrase candidate(s) ...",
+            account_indexes.start,
+            account_indexes.end.saturating_sub(1),
+            seed_phrase_recovery.seed_phrase_passphrases.len()
+        );
+        let recovered_access_keys = crate::common::recover_access_keys_from_seed_phrase(
+            &near_jsonrpc_client::new_client(self.connection_config.rpc_url().as_str()),
+            &account_id,
+            &seed_phrase_recovery.master_seed_phrase,
+            &seed_phrase_recovery.seed_phrase_passphrases,
+            account_indexes,
+            seed_phrase_recovery.key_type,
+        )
+        .await?;
+        if recovered_access_keys.is_empty() {
+            return Err(color_eyre::Report::msg(format!(
+                "None of the scanned keys is an access key of <{}>",
+                account_id
+            )));
+        }
+        for recovered_access_key in recovered_access_keys {
+            println!(
+                "Found the access key {} at the path {}{}",
+                recovered_access_key.key_pair_properties.public_key_str,
+                recovered_access_key
+                    .key_pair_properties
+                    .seed_phrase_hd_path
+                    .to_string(),
+                if recovered_access_key.seed_phrase_passphrase.is_empty() {
+                    " without a passphrase".to_owned()
+                } else {
+                    format!(
+                        " with the passphrase <{}>",
+                        recovered_access_key.seed_phrase_passphrase
+                    )
+                }
+            );
+            crate::common::save_access_key_to_keychain(
+                Some(self.connection_config.clone()),
+                recovered_access_key.key_pair_properties,
+                &account_id.to_string(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use dialoguer::Input;
+
+pub mod operation_mode;
+
+/// Replace a full access key of the account with a newly generated one
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliRotateAccessKey {
+    account_id: Option<near_primitives::types::AccountId>,
+    #[clap(subcommand)]
+    mode: Option<self::operation_mode::CliMode>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RotateAccessKey {
+    account_id: near_primitives::types::AccountId,
+    mode: self::operation_mode::Mode,
+}
+
+impl CliRotateAccessKey {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = self
+            .mode
+            .as_ref()
+            .map(|subcommand| subcommand.to_cli_args())
+            .unwrap_or_default();
+        if let Some(account_id) = &self.account_id {
+            args.push_front(account_id.to_string());
+        };
+        args
+    }
+}
+
+impl From<RotateAccessKey> for CliRotateAccessKey {
+    fn from(rotate_access_key: RotateAccessKey) -> Self {
+        Self {
+            account_id: Some(rotate_access_key.account_id),
+            mode: Some(rotate_access_key.mode.into()),
+        }
+    }
+}
+
+impl From<CliRotateAccessKey> for RotateAccessKey {
+    fn from(item: CliRotateAccessKey) -> Self {
+        let account_id: near_primitives::types::AccountId = match item.account_id {
+            Some(cli_account_id) => cli_account_id,
+            None => RotateAccessKey::input_account_id(),
+        };
+        let mode = match item.mode {
+            Some(cli_mode) => self::operation_mode::Mode::from(cli_mode),
+            None => self::operation_mode::Mode::choose_mode(),
+        };
+        Self { account_id, mode }
+    }
+}
+
+impl RotateAccessKey {
+    fn input_account_id() -> near_primitives::types::AccountId {
+        println!();
+        Input::new()
+            .with_prompt("Which account do you want to rotate the access key for?")
+            .interact_text()
+            .unwrap()
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        self.mode.process(self.account_id).await
+    }
+}
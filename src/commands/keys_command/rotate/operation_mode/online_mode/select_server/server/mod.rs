@@ -0,0 +1,694 @@
+use std::io::Write;
+use std::str::FromStr;
+
+use dialoguer::{theme::ColorfulTheme, Input, Select};
+
+/// предустановленный RPC-сервер
+#[derive(Debug, Default, Clone, clap::Clap)]
+pub struct CliServer {
+    /// Type of the new access key
+    #[clap(long, default_value = "ed25519")]
+    pub key_type: crate::common::KeyType,
+}
+
+/// данные для custom server
+#[derive(Debug, Default, Clone, clap::Clap)]
+pub struct CliCustomServer {
+    #[clap(long)]
+    pub url: Option<crate::common::AvailableRpcServerUrl>,
+    /// Type of the new access key
+    #[clap(long, default_value = "ed25519")]
+    pub key_type: crate::common::KeyType,
+}
+
+#[derive(Debug, Clone)]
+pub struct Server {
+    pub connection_config: crate::common::ConnectionConfig,
+    pub key_type: crate::common::KeyType,
+}
+
+impl CliCustomServer {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        args.push_front(self.key_type.to_string());
+        args.push_front("--key-type".to_owned());
+        if let Some(url) = &self.url {
+            args.push_front(url.to_string());
+            args.push_front("--url".to_string());
+        }
+        args
+    }
+}
+
+impl From<Server> for CliCustomServer {
+    fn from(server: Server) -> Self {
+        Self {
+            url: Some(
+                crate::common::AvailableRpcServerUrl::from_str(
+                    server.connection_config.rpc_url().as_str(),
+                )
+                .unwrap(),
+            ),
+            key_type: server.key_type,
+        }
+    }
+}
+
+impl CliServer {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        args.push_front(self.key_type.to_string());
+        args.push_front("--key-type".to_owned());
+        args
+    }
+}
+
+impl From<Server> for CliServer {
+    fn from(server: Server) -> Self {
+        Self {
+            key_type: server.key_type,
+        }
+    }
+}
+
+impl CliServer {
+    pub fn into_server(self, connection_config: crate::common::ConnectionConfig) -> Server {
+        Server {
+            connection_config,
+            key_type: self.key_type,
+        }
+    }
+}
+
+impl CliCustomServer {
+    pub fn into_server(self) -> Server {
+        let url: crate::common::AvailableRpcServerUrl = match self.url {
+            Some(url) => url,
+            None => Input::new()
+                .with_prompt("What is the RPC endpoint?")
+                .interact_text()
+                .unwrap(),
+        };
+        Server {
+            connection_config: crate::common::ConnectionConfig::Custom { url: url.inner },
+            key_type: self.key_type,
+        }
+    }
+}
+
+/// The state of an unfinished rotation. It is saved before the first transaction is sent,
+/// so the new private key is never lost and the rotation can be resumed.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct PendingRotation {
+    account_id: near_primitives::types::AccountId,
+    old_public_key: near_crypto::PublicKey,
+    old_private_key: near_crypto::SecretKey,
+    new_master_seed_phrase: String,
+    new_seed_phrase_hd_path: String,
+    new_public_key: near_crypto::PublicKey,
+    new_private_key: near_crypto::SecretKey,
+}
+
+/// The step a rotation continues with, judging by which of its keys are on the account.
+#[derive(Debug, PartialEq)]
+enum RotationStep {
+    /// Only the old key is on the account: the new key is added next
+    AddNewKey,
+    /// Both keys are on the account: the old key is deleted next
+    DeleteOldKey,
+    /// Only the new key is on the account: only the keychain is left to update
+    UpdateKeychain,
+    /// None of the keys is on the account
+    Unrecoverable,
+}
+
+fn next_rotation_step(
+    access_keys: &[near_crypto::PublicKey],
+    pending_rotation: &PendingRotation,
+) -> RotationStep {
+    match (
+        access_keys.contains(&pending_rotation.old_public_key),
+        access_keys.contains(&pending_rotation.new_public_key),
+    ) {
+        (true, false) => RotationStep::AddNewKey,
+        (true, true) => RotationStep::DeleteOldKey,
+        (false, true) => RotationStep::UpdateKeychain,
+        (false, false) => RotationStep::Unrecoverable,
+    }
+}
+
+impl Server {
+    pub async fn process(self, account_id: near_primitives::types::AccountId) -> crate::CliResult {
+        let pending_rotation_path =
+            pending_rotation_path(self.connection_config.dir_name(), &account_id);
+        let access_key_list = view_access_key_list(&account_id, &self.connection_config).await?;
+        let pending_rotation = match read_pending_rotation(&pending_rotation_path)? {
+            Some(pending_rotation) => {
+                println!(
+                    "Resuming the unfinished rotation of the access key {} to {} (see {}).",
+                    pending_rotation.old_public_key,
+                    pending_rotation.new_public_key,
+                    pending_rotation_path.display()
+                );
+                pending_rotation
+            }
+            None => {
+                let current_access_key = choose_current_access_key(
+                    &account_id,
+                    &access_key_list,
+                    &self.connection_config,
+                )?;
+                let key_pair_properties =
+                    crate::common::generate_keypair(self.key_type, "").await?;
+                let pending_rotation = PendingRotation {
+                    account_id: account_id.clone(),
+                    old_public_key: current_access_key.public_key,
+                    old_private_key: current_access_key.private_key,
+                    new_master_seed_phrase: key_pair_properties.master_seed_phrase,
+                    new_seed_phrase_hd_path: key_pair_properties.seed_phrase_hd_path.to_string(),
+                    new_public_key: near_crypto::PublicKey::from_str(
+                        &key_pair_properties.public_key_str,
+                    )?,
+                    new_private_key: near_crypto::SecretKey::from_str(
+                        &key_pair_properties.secret_keypair_str,
+                    )?,
+                };
+                write_file_atomically(
+                    &pending_rotation_path,
+                    &serde_json::to_string(&pending_rotation)?,
+                )?;
+                pending_rotation
+            }
+        };
+
+        let access_keys = access_key_list
+            .iter()
+            .map(|access_key| access_key.public_key.clone())
+            .collect::<Vec<_>>();
+        let rotation_step = next_rotation_step(&access_keys, &pending_rotation);
+        if rotation_step == RotationStep::Unrecoverable {
+            return Err(color_eyre::Report::msg(format!(
+                "Neither the old access key {} nor the new access key {} is found on <{}>. The rotation cannot be resumed, the keys are kept in {}.",
+                pending_rotation.old_public_key,
+                pending_rotation.new_public_key,
+                account_id,
+                pending_rotation_path.display()
+            )));
+        }
+
+        if rotation_step == RotationStep::AddNewKey {
+            println!(
+                "\nStep 1/2: adding the new full access key {} signed by the current key {} ...",
+                pending_rotation.new_public_key, pending_rotation.old_public_key
+            );
+            let add_key_action = near_primitives::transaction::Action::AddKey(
+                near_primitives::transaction::AddKeyAction {
+                    public_key: pending_rotation.new_public_key.clone(),
+                    access_key: near_primitives::account::AccessKey {
+                        nonce: 0,
+                        permission: near_primitives::account::AccessKeyPermission::FullAccess,
+                    },
+                },
+            );
+            if let Err(err) = send_transaction(
+                &account_id,
+                &pending_rotation.old_public_key,
+                &pending_rotation.old_private_key,
+                add_key_action,
+                &self.connection_config,
+            )
+            .await
+            {
+                // A transaction that timed out can still land, so the new key is kept until the
+                // account shows which step the rotation has reached
+                let is_new_key_added = view_access_key_list(&account_id, &self.connection_config)
+                    .await
+                    .map(|access_key_list| {
+                        access_key_list.iter().any(|access_key| {
+                            access_key.public_key == pending_rotation.new_public_key
+                        })
+                    })
+                    .unwrap_or(false);
+                if !is_new_key_added {
+                    return Err(color_eyre::Report::msg(format!(
+                        "Failed to add the new access key: {}\nThe new key is kept in {}. Run `keys rotate {}` again to resume the rotation.",
+                        err,
+                        pending_rotation_path.display(),
+                        account_id
+                    )));
+                }
+            }
+            wait_for_access_key(
+                &account_id,
+                &pending_rotation.new_public_key,
+                &self.connection_config,
+            )
+            .await
+            .map_err(|err| {
+                color_eyre::Report::msg(format!(
+                    "{}\nRun `keys rotate {}` again to resume the rotation.",
+                    err, account_id
+                ))
+            })?;
+            save_new_access_key_to_keychain(&pending_rotation, &self.connection_config)?;
+        }
+
+        if rotation_step != RotationStep::UpdateKeychain {
+            println!(
+                "\nStep 2/2: deleting the old access key {} signed by the new key {} ...",
+                pending_rotation.old_public_key, pending_rotation.new_public_key
+            );
+            let delete_key_action = near_primitives::transaction::Action::DeleteKey(
+                near_primitives::transaction::DeleteKeyAction {
+                    public_key: pending_rotation.old_public_key.clone(),
+                },
+            );
+            send_transaction(
+                &account_id,
+                &pending_rotation.new_public_key,
+                &pending_rotation.new_private_key,
+                delete_key_action,
+                &self.connection_config,
+            )
+            .await
+            .map_err(|err| {
+                color_eyre::Report::msg(format!(
+                    "Failed to delete the old access key: {}\nThe new access key is already added and saved in the keychain. Run `keys rotate {}` again to finish the rotation.",
+                    err, account_id
+                ))
+            })?;
+        }
+
+        save_new_access_key_to_keychain(&pending_rotation, &self.connection_config)?;
+        remove_old_access_key_from_keychain(&pending_rotation, &self.connection_config)?;
+        std::fs::remove_file(&pending_rotation_path)?;
+        println!(
+            "\nThe access key for <{}> was rotated: {} -> {}",
+            account_id, pending_rotation.old_public_key, pending_rotation.new_public_key
+        );
+        Ok(())
+    }
+}
+
+fn rpc_client(selected_server_url: &str) -> near_jsonrpc_client::JsonRpcClient {
+    near_jsonrpc_client::new_client(&selected_server_url)
+}
+
+fn pending_rotation_path(
+    dir_name: &str,
+    account_id: &near_primitives::types::AccountId,
+) -> std::path::PathBuf {
+    let mut path = dirs::home_dir().expect("Impossible to get your home dir!");
+    path.push(dir_name);
+    path.push(format!("{}.rotation.json", account_id));
+    path
+}
+
+/// Reads the unfinished rotation saved by a previous run, if there is one.
+fn read_pending_rotation(
+    pending_rotation_path: &std::path::Path,
+) -> color_eyre::eyre::Result<Option<PendingRotation>> {
+    let data = match std::fs::read_to_string(pending_rotation_path) {
+        Ok(data) => data,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(color_eyre::Report::msg(format!(
+                "Failed to read the unfinished rotation from {}: {}",
+                pending_rotation_path.display(),
+                err
+            )))
+        }
+    };
+    let pending_rotation = serde_json::from_str(&data).map_err(|err| {
+        color_eyre::Report::msg(format!(
+            "Failed to read the unfinished rotation from {}: {}",
+            pending_rotation_path.display(),
+            err
+        ))
+    })?;
+    Ok(Some(pending_rotation))
+}
+
+fn access_key_file_path(dir_name: &str, account_id: &str, public_key: &str) -> std::path::PathBuf {
+    let mut path = dirs::home_dir().expect("Impossible to get your home dir!");
+    path.push(dir_name);
+    path.push(account_id);
+    path.push(format!("{}.json", public_key.replace(":", "_")));
+    path
+}
+
+/// Writes the file next to the target first and then renames it,
+/// so the keychain never contains a partially written file.
+fn write_file_atomically(path: &std::path::Path, buf: &str) -> crate::CliResult {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = std::path::PathBuf::from(tmp_path);
+    std::fs::File::create(&tmp_path)
+        .map_err(|err| color_eyre::Report::msg(format!("Failed to create file: {:?}", err)))?
+        .write_all(buf.as_bytes())
+        .map_err(|err| color_eyre::Report::msg(format!("Failed to write to file: {:?}", err)))?;
+    std::fs::rename(&tmp_path, path)
+        .map_err(|err| color_eyre::Report::msg(format!("Failed to replace file: {:?}", err)))?;
+    Ok(())
+}
+
+async fn view_access_key_list(
+    account_id: &near_primitives::types::AccountId,
+    network_connection_config: &crate::common::ConnectionConfig,
+) -> color_eyre::eyre::Result<Vec<near_primitives::views::AccessKeyInfoView>> {
+    let query_view_method_response = rpc_client(network_connection_config.rpc_url().as_str())
+        .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
+            block_reference: near_primitives::types::Finality::Final.into(),
+            request: near_primitives::views::QueryRequest::ViewAccessKeyList {
+                account_id: account_id.clone(),
+            },
+        })
+        .await
+        .map_err(|err| {
+            color_eyre::Report::msg(format!(
+                "Failed to fetch query for view key list: {:?}",
+                err
+            ))
+        })?;
+    if let near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKeyList(result) =
+        query_view_method_response.kind
+    {
+        Ok(result.keys)
+    } else {
+        Err(color_eyre::Report::msg("Error call result"))
+    }
+}
+
+/// Finds the full access keys of the account that are saved in the keychain
+/// and asks which one to rotate if there are several of them.
+fn choose_current_access_key(
+    account_id: &near_primitives::types::AccountId,
+    access_key_list: &[near_primitives::views::AccessKeyInfoView],
+    network_connection_config: &crate::common::ConnectionConfig,
+) -> color_eyre::eyre::Result<crate::common::KeychainAccessKey> {
+    let keychain_access_keys =
+        crate::common::read_keychain_access_keys(network_connection_config.dir_name(), account_id);
+    let mut full_access_keys: Vec<crate::common::KeychainAccessKey> = vec![];
+    for access_key in access_key_list {
+        if let near_primitives::views::AccessKeyPermissionView::FullAccess =
+            access_key.access_key.permission
+        {
+            if let Some((_, keychain_access_key)) =
+                keychain_access_keys
+                    .iter()
+                    .find(|(_, keychain_access_key)| {
+                        keychain_access_key.public_key == access_key.public_key
+                    })
+            {
+                full_access_keys.push(keychain_access_key.clone());
+            }
+        }
+    }
+    match full_access_keys.len() {
+        0 => Err(color_eyre::Report::msg(format!(
+            "There are no full access keys of <{}> found in the keychain. Log in before rotating the access key.",
+            account_id
+        ))),
+        1 => Ok(full_access_keys.remove(0)),
+        _ => {
+            let public_keys = full_access_keys
+                .iter()
+                .map(|access_key| access_key.public_key.to_string())
+                .collect::<Vec<_>>();
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Select the access key to rotate")
+                .items(&public_keys)
+                .default(0)
+                .interact()
+                .unwrap();
+            Ok(full_access_keys.remove(selection))
+        }
+    }
+}
+
+async fn send_transaction(
+    account_id: &near_primitives::types::AccountId,
+    signer_public_key: &near_crypto::PublicKey,
+    signer_private_key: &near_crypto::SecretKey,
+    action: near_primitives::transaction::Action,
+    network_connection_config: &crate::common::ConnectionConfig,
+) -> crate::CliResult {
+    let unsigned_transaction = near_primitives::transaction::Transaction {
+        signer_id: account_id.clone(),
+        public_key: signer_public_key.clone(),
+        nonce: 0,
+        receiver_id: account_id.clone(),
+        block_hash: Default::default(),
+        actions: vec![action],
+    };
+    let sign_private_key = crate::commands::construct_transaction_command::sign_transaction::sign_with_private_key::SignPrivateKey {
+        signer_public_key: signer_public_key.clone(),
+        signer_private_key: signer_private_key.clone(),
+        nonce: None,
+        block_hash: None,
+        submit: Some(crate::commands::construct_transaction_command::sign_transaction::Submit::Send),
+        skip_review: false,
+        ignore_preflight_errors: false,
+        policy_network: None,
+    };
+    match sign_private_key
+        .process(
+            unsigned_transaction,
+            Some(network_connection_config.clone()),
+        )
+        .await?
+    {
+        Some(transaction_info) => {
+            let is_failed = matches!(
+                transaction_info.status,
+                near_primitives::views::FinalExecutionStatus::Failure(_)
+            );
+            crate::common::print_transaction_status(
+                transaction_info,
+                Some(network_connection_config.clone()),
+            )
+            .await;
+            if is_failed {
+                return Err(color_eyre::Report::msg("The transaction has failed"));
+            }
+            Ok(())
+        }
+        None => Err(color_eyre::Report::msg("The transaction was not sent")),
+    }
+}
+
+/// Polls the network until the access key is visible on chain.
+async fn wait_for_access_key(
+    account_id: &near_primitives::types::AccountId,
+    public_key: &near_crypto::PublicKey,
+    network_connection_config: &crate::common::ConnectionConfig,
+) -> crate::CliResult {
+    for _ in 0..10 {
+        if view_access_key_list(account_id, network_connection_config)
+            .await?
+            .iter()
+            .any(|access_key| &access_key.public_key == public_key)
+        {
+            println!("The new access key {} is confirmed on chain.", public_key);
+            return Ok(());
+        }
+        actix::clock::sleep(std::time::Duration::from_secs(1)).await;
+    }
+    Err(color_eyre::Report::msg(format!(
+        "The new access key {} is not confirmed on chain.",
+        public_key
+    )))
+}
+
+fn save_new_access_key_to_keychain(
+    pending_rotation: &PendingRotation,
+    network_connection_config: &crate::common::ConnectionConfig,
+) -> crate::CliResult {
+    let buf = serde_json::json!({
+        "master_seed_phrase": pending_rotation.new_master_seed_phrase,
+        "seed_phrase_hd_path": pending_rotation.new_seed_phrase_hd_path,
+        "account_id": pending_rotation.account_id.to_string(),
+        "public_key": pending_rotation.new_public_key.to_string(),
+        "private_key": pending_rotation.new_private_key.to_string(),
+    })
+    .to_string();
+    let path_with_key_name = access_key_file_path(
+        network_connection_config.dir_name(),
+        &pending_rotation.account_id.to_string(),
+        &pending_rotation.new_public_key.to_string(),
+    );
+    write_file_atomically(&path_with_key_name, &buf)?;
+    println!(
+        "The data for the access key is saved in a file {}",
+        &path_with_key_name.display()
+    );
+
+    let mut path_with_account_name = dirs::home_dir().expect("Impossible to get your home dir!");
+    path_with_account_name.push(network_connection_config.dir_name());
+    path_with_account_name.push(format!("{}.json", pending_rotation.account_id));
+    let is_old_access_key_in_account_file = match std::fs::read_to_string(&path_with_account_name) {
+        Ok(data) => serde_json::from_str::<crate::common::KeychainAccessKey>(&data)
+            .map(|access_key| access_key.public_key == pending_rotation.old_public_key)
+            .unwrap_or(false),
+        Err(_) => true,
+    };
+    if is_old_access_key_in_account_file {
+        write_file_atomically(&path_with_account_name, &buf)?;
+        println!(
+            "The data for the access key is saved in a file {}",
+            &path_with_account_name.display()
+        );
+    }
+    Ok(())
+}
+
+fn remove_old_access_key_from_keychain(
+    pending_rotation: &PendingRotation,
+    network_connection_config: &crate::common::ConnectionConfig,
+) -> crate::CliResult {
+    let path_with_key_name = access_key_file_path(
+        network_connection_config.dir_name(),
+        &pending_rotation.account_id.to_string(),
+        &pending_rotation.old_public_key.to_string(),
+    );
+    if path_with_key_name.exists() {
+        std::fs::remove_file(&path_with_key_name)?;
+        println!(
+            "The file {} with the old access key is removed",
+            &path_with_key_name.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending_rotation_fixture() -> PendingRotation {
+        let old_private_key =
+            near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, "old key");
+        let new_private_key =
+            near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, "new key");
+        PendingRotation {
+            account_id: "alice.testnet".parse().unwrap(),
+            old_public_key: old_private_key.public_key(),
+            old_private_key,
+            new_master_seed_phrase: "new seed phrase".to_string(),
+            new_seed_phrase_hd_path: "m/44'/397'/0'".to_string(),
+            new_public_key: new_private_key.public_key(),
+            new_private_key,
+        }
+    }
+
+    fn temp_rotation_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "near-cli-rotation-{}-{}/alice.testnet.rotation.json",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn pending_rotation_is_resumed_from_the_saved_file() {
+        let path = temp_rotation_path("resume");
+        let pending_rotation = pending_rotation_fixture();
+        write_file_atomically(&path, &serde_json::to_string(&pending_rotation).unwrap()).unwrap();
+
+        let resumed_rotation = read_pending_rotation(&path).unwrap().unwrap();
+        assert_eq!(resumed_rotation.account_id, pending_rotation.account_id);
+        assert_eq!(
+            resumed_rotation.old_public_key,
+            pending_rotation.old_public_key
+        );
+        assert_eq!(
+            resumed_rotation.old_private_key,
+            pending_rotation.old_private_key
+        );
+        assert_eq!(
+            resumed_rotation.new_master_seed_phrase,
+            pending_rotation.new_master_seed_phrase
+        );
+        assert_eq!(
+            resumed_rotation.new_seed_phrase_hd_path,
+            pending_rotation.new_seed_phrase_hd_path
+        );
+        assert_eq!(
+            resumed_rotation.new_public_key,
+            pending_rotation.new_public_key
+        );
+        assert_eq!(
+            resumed_rotation.new_private_key,
+            pending_rotation.new_private_key
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn missing_rotation_file_starts_a_new_rotation() {
+        let path = temp_rotation_path("missing");
+        assert!(read_pending_rotation(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn corrupted_rotation_file_is_reported_instead_of_starting_over() {
+        let path = temp_rotation_path("corrupted");
+        write_file_atomically(&path, "{\"account_id\": \"alice.testnet\"").unwrap();
+
+        let err = read_pending_rotation(&path).unwrap_err();
+        assert!(err.to_string().contains(&path.display().to_string()));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn rotation_adds_the_new_key_first_and_again_after_a_failed_add() {
+        let path = temp_rotation_path("add-failed");
+        let pending_rotation = pending_rotation_fixture();
+        write_file_atomically(&path, &serde_json::to_string(&pending_rotation).unwrap()).unwrap();
+        // The add-key transaction failed and the new key never appeared: the pending rotation is
+        // still there and it starts over with adding the new key
+        let resumed_rotation = read_pending_rotation(&path).unwrap().unwrap();
+        assert_eq!(
+            next_rotation_step(
+                &[resumed_rotation.old_public_key.clone()],
+                &resumed_rotation
+            ),
+            RotationStep::AddNewKey
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn rotation_deletes_the_old_key_when_the_new_key_is_already_added() {
+        let pending_rotation = pending_rotation_fixture();
+        assert_eq!(
+            next_rotation_step(
+                &[
+                    pending_rotation.new_public_key.clone(),
+                    pending_rotation.old_public_key.clone()
+                ],
+                &pending_rotation
+            ),
+            RotationStep::DeleteOldKey
+        );
+    }
+
+    #[test]
+    fn rotation_only_updates_the_keychain_when_the_old_key_is_already_deleted() {
+        let pending_rotation = pending_rotation_fixture();
+        assert_eq!(
+            next_rotation_step(
+                &[pending_rotation.new_public_key.clone()],
+                &pending_rotation
+            ),
+            RotationStep::UpdateKeychain
+        );
+        assert_eq!(
+            next_rotation_step(&[], &pending_rotation),
+            RotationStep::Unrecoverable
+        );
+    }
+}
@@ -5,23 +5,44 @@ use url_open::UrlOpen;
 
 /// предустановленный RPC-сервер
 #[derive(Debug, Default, Clone, clap::Clap)]
-pub struct CliServer {}
+pub struct CliServer {
+    /// BIP-39 passphrase that protects the generated master seed phrase
+    #[clap(long)]
+    pub seed_phrase_passphrase: Option<String>,
+    /// Type of the generated access key
+    #[clap(long, default_value = "ed25519")]
+    pub key_type: crate::common::KeyType,
+}
 
 /// данные для custom server
 #[derive(Debug, Default, Clone, clap::Clap)]
 pub struct CliCustomServer {
     #[clap(long)]
     pub url: Option<url::Url>,
+    /// BIP-39 passphrase that protects the generated master seed phrase
+    #[clap(long)]
+    pub seed_phrase_passphrase: Option<String>,
+    /// Type of the generated access key
+    #[clap(long, default_value = "ed25519")]
+    pub key_type: crate::common::KeyType,
 }
 
 #[derive(Debug, Clone)]
 pub struct Server {
     pub connection_config: crate::common::ConnectionConfig,
+    pub seed_phrase_passphrase: Option<String>,
+    pub key_type: crate::common::KeyType,
 }
 
 impl CliCustomServer {
     pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
         let mut args = std::collections::VecDeque::new();
+        args.push_front(self.key_type.to_string());
+        args.push_front("--key-type".to_owned());
+        if let Some(seed_phrase_passphrase) = &self.seed_phrase_passphrase {
+            args.push_front(seed_phrase_passphrase.to_owned());
+            args.push_front("--seed-phrase-passphrase".to_owned());
+        }
         if let Some(url) = &self.url {
             args.push_front(url.to_string());
             args.push_front("--url".to_string());
@@ -40,25 +61,41 @@ impl From<Server> for CliCustomServer {
                 .unwrap()
                 .inner,
             ),
+            seed_phrase_passphrase: server.seed_phrase_passphrase,
+            key_type: server.key_type,
         }
     }
 }
 
 impl CliServer {
     pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
-        std::collections::VecDeque::new()
+        let mut args = std::collections::VecDeque::new();
+        args.push_front(self.key_type.to_string());
+        args.push_front("--key-type".to_owned());
+        if let Some(seed_phrase_passphrase) = &self.seed_phrase_passphrase {
+            args.push_front(seed_phrase_passphrase.to_owned());
+            args.push_front("--seed-phrase-passphrase".to_owned());
+        }
+        args
     }
 }
 
 impl From<Server> for CliServer {
-    fn from(_: Server) -> Self {
-        Self {}
+    fn from(server: Server) -> Self {
+        Self {
+            seed_phrase_passphrase: server.seed_phrase_passphrase,
+            key_type: server.key_type,
+        }
     }
 }
 
 impl CliServer {
     pub fn into_server(self, connection_config: crate::common::ConnectionConfig) -> Server {
-        Server { connection_config }
+        Server {
+            connection_config,
+            seed_phrase_passphrase: self.seed_phrase_passphrase,
+            key_type: self.key_type,
+        }
     }
 }
 
@@ -73,6 +110,8 @@ impl CliCustomServer {
         };
         Server {
             connection_config: crate::common::ConnectionConfig::Custom { url },
+            seed_phrase_passphrase: self.seed_phrase_passphrase,
+            key_type: self.key_type,
         }
     }
 }
@@ -80,7 +119,11 @@ impl CliCustomServer {
 impl Server {
     pub async fn process(self) -> crate::CliResult {
         let key_pair_properties: crate::common::KeyPairProperties =
-            crate::common::generate_keypair().await?;
+            crate::common::generate_keypair(
+                self.key_type,
+                self.seed_phrase_passphrase.as_deref().unwrap_or_default(),
+            )
+            .await?;
         let mut url: url::Url = self.connection_config.wallet_url().join("login/")?;
         url.query_pairs_mut()
             .append_pair("title", "NEAR CLI")
@@ -2,10 +2,12 @@ use dialoguer::{theme::ColorfulTheme, Select};
 use strum::{EnumDiscriminants, EnumIter, EnumMessage, IntoEnumIterator};
 
 pub mod add_command;
+pub mod agent_command;
 pub mod construct_transaction_command;
 pub mod delete_command;
 pub mod execute_command;
 pub mod generate_shell_completions_command;
+pub mod keys_command;
 pub mod login;
 pub mod transfer_command;
 pub mod utils_command;
@@ -15,6 +17,8 @@ pub mod view_command;
 pub enum CliTopLevelCommand {
     /// Use these to add access key, contract code, stake proposal, sub-account, implicit-account
     Add(self::add_command::CliAddAction),
+    /// Keep the keys of the keychain in memory and sign transactions for other near-cli processes
+    Agent(self::agent_command::CliAgent),
     /// Prepare and, optionally, submit a new transaction
     ConstructTransaction(self::construct_transaction_command::operation_mode::CliOperationMode),
     /// Use these to delete access key, sub-account
@@ -23,6 +27,8 @@ pub enum CliTopLevelCommand {
     Execute(self::execute_command::CliOptionMethod),
     /// Use these to generate static shell completions
     GenerateShellCompletions(self::generate_shell_completions_command::CliGenerateShellCompletions),
+    /// Use these to manage access keys in the keychain
+    Keys(self::keys_command::CliKeysAction),
     /// Use these to login with wallet authorization
     Login(self::login::operation_mode::CliOperationMode),
     /// Use these to transfer tokens
@@ -52,10 +58,14 @@ pub enum TopLevelCommand {
     Add(self::add_command::AddAction),
     #[strum_discriminants(strum(message = "Delete access key, account"))]
     Delete(self::delete_command::DeleteAction),
+    #[strum_discriminants(strum(message = "Manage access keys in the keychain"))]
+    Keys(self::keys_command::KeysAction),
     #[strum_discriminants(strum(message = "Construct a new transaction"))]
     ConstructTransaction(self::construct_transaction_command::operation_mode::OperationMode),
     #[strum_discriminants(strum(message = "Helpers"))]
     Utils(self::utils_command::Utils),
+    #[strum_discriminants(strum(message = "Start an agent that keeps the keys in memory"))]
+    Agent(self::agent_command::CliAgent),
 }
 
 impl CliTopLevelCommand {
@@ -96,11 +106,21 @@ impl CliTopLevelCommand {
                 args.push_front("construct-transaction".to_owned());
                 args
             }
+            Self::Keys(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("keys".to_owned());
+                args
+            }
             Self::Utils(subcommand) => {
                 let mut args = subcommand.to_cli_args();
                 args.push_front("utils".to_owned());
                 args
             }
+            Self::Agent(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("agent".to_owned());
+                args
+            }
             Self::GenerateShellCompletions(_) => std::collections::VecDeque::new(),
         }
     }
@@ -118,7 +138,9 @@ impl From<TopLevelCommand> for CliTopLevelCommand {
             TopLevelCommand::ConstructTransaction(operation_mode) => {
                 Self::ConstructTransaction(operation_mode.into())
             }
+            TopLevelCommand::Keys(keys_action) => Self::Keys(keys_action.into()),
             TopLevelCommand::Utils(utils) => Self::Utils(utils.into()),
+            TopLevelCommand::Agent(agent) => Self::Agent(agent),
         }
     }
 }
@@ -129,6 +151,7 @@ impl From<CliTopLevelCommand> for TopLevelCommand {
             CliTopLevelCommand::Add(cli_add_action) => {
                 TopLevelCommand::Add(self::add_command::AddAction::from(cli_add_action).unwrap())
             }
+            CliTopLevelCommand::Agent(cli_agent) => TopLevelCommand::Agent(cli_agent),
             CliTopLevelCommand::ConstructTransaction(cli_operation_mode) => {
                 TopLevelCommand::ConstructTransaction(
                     self::construct_transaction_command::operation_mode::OperationMode::from(
@@ -146,6 +169,9 @@ impl From<CliTopLevelCommand> for TopLevelCommand {
             CliTopLevelCommand::GenerateShellCompletions(_) => {
                 unreachable!("This variant is handled in the main function")
             }
+            CliTopLevelCommand::Keys(cli_keys_action) => {
+                TopLevelCommand::Keys(cli_keys_action.into())
+            }
             CliTopLevelCommand::Login(cli_option_method) => {
                 TopLevelCommand::Login(cli_option_method.into())
             }
@@ -176,6 +202,7 @@ impl TopLevelCommand {
             .unwrap();
         let cli_top_level_command = match variants[selection] {
             TopLevelCommandDiscriminants::Add => CliTopLevelCommand::Add(Default::default()),
+            TopLevelCommandDiscriminants::Agent => CliTopLevelCommand::Agent(Default::default()),
             TopLevelCommandDiscriminants::ConstructTransaction => {
                 CliTopLevelCommand::ConstructTransaction(Default::default())
             }
@@ -183,6 +210,7 @@ impl TopLevelCommand {
             TopLevelCommandDiscriminants::Execute => {
                 CliTopLevelCommand::Execute(Default::default())
             }
+            TopLevelCommandDiscriminants::Keys => CliTopLevelCommand::Keys(Default::default()),
             TopLevelCommandDiscriminants::Login => CliTopLevelCommand::Login(Default::default()),
             TopLevelCommandDiscriminants::Transfer => {
                 CliTopLevelCommand::Transfer(Default::default())
@@ -204,9 +232,11 @@ impl TopLevelCommand {
         };
         match self {
             Self::Add(add_action) => add_action.process(unsigned_transaction).await,
+            Self::Agent(agent) => agent.process().await,
             Self::ConstructTransaction(mode) => mode.process(unsigned_transaction).await,
             Self::Delete(delete_action) => delete_action.process(unsigned_transaction).await,
             Self::Execute(option_method) => option_method.process(unsigned_transaction).await,
+            Self::Keys(keys_action) => keys_action.process().await,
             Self::Login(mode) => mode.process().await,
             Self::Transfer(currency) => currency.process(unsigned_transaction).await,
             Self::Utils(util_type) => util_type.process().await,
@@ -0,0 +1,613 @@
+use std::io::Write;
+use std::str::FromStr;
+
+use dialoguer::{Confirm, Input};
+use near_primitives::borsh::BorshSerialize;
+
+pub mod operation_mode;
+
+/// Transfer NEAR tokens to many receivers listed in a CSV file
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliBulkTransfer {
+    /// CSV file with receiver,amount lines, e.g. "bob.testnet,1.5 NEAR" (a header line is skipped)
+    csv: Option<std::path::PathBuf>,
+    #[clap(long)]
+    sender_account_id: Option<near_primitives::types::AccountId>,
+    /// Access key of the sender in the keychain [default: the first full access key]
+    #[clap(long)]
+    signer_public_key: Option<near_crypto::PublicKey>,
+    /// Report of the transfers [default: <csv>.report.csv]; the transfers reported as
+    /// successful are skipped when the same CSV is sent again
+    #[clap(long)]
+    report: Option<std::path::PathBuf>,
+    /// Send the transfers without asking for a confirmation (for scripts)
+    #[clap(long)]
+    yes: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct BulkTransfer {
+    sender_account_id: near_primitives::types::AccountId,
+    signer_public_key: Option<near_crypto::PublicKey>,
+    csv: std::path::PathBuf,
+    report: std::path::PathBuf,
+    yes: bool,
+    payments: Vec<Payment>,
+}
+
+#[derive(Debug, Clone)]
+struct Payment {
+    line: usize,
+    receiver_id: near_primitives::types::AccountId,
+    amount: crate::common::NearBalance,
+    /// 1 for the first transfer of the amount to the receiver in the CSV, 2 for the second one...
+    occurrence: usize,
+}
+
+/// What is done with a transfer of the CSV file, judging by its latest row in the report.
+#[derive(Debug, PartialEq)]
+enum Resume {
+    /// The transfer was not sent yet, or it failed on chain
+    Send,
+    /// The transfer succeeded
+    Skip,
+    /// The transfer may have been sent: its outcome is looked up by the transaction hash
+    LookUp(String),
+}
+
+const REPORT_HEADER: &str =
+    "line,receiver_id,amount_yoctonear,occurrence,status,transaction_hash,error";
+
+impl CliBulkTransfer {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        if self.yes {
+            args.push_front("--yes".to_owned());
+        }
+        if let Some(report) = &self.report {
+            args.push_front(report.display().to_string());
+            args.push_front("--report".to_owned());
+        }
+        if let Some(signer_public_key) = &self.signer_public_key {
+            args.push_front(signer_public_key.to_string());
+            args.push_front("--signer-public-key".to_owned());
+        }
+        if let Some(sender_account_id) = &self.sender_account_id {
+            args.push_front(sender_account_id.to_string());
+            args.push_front("--sender-account-id".to_owned());
+        }
+        if let Some(csv) = &self.csv {
+            args.push_front(csv.display().to_string());
+        }
+        args
+    }
+}
+
+impl From<BulkTransfer> for CliBulkTransfer {
+    fn from(bulk_transfer: BulkTransfer) -> Self {
+        Self {
+            csv: Some(bulk_transfer.csv),
+            sender_account_id: Some(bulk_transfer.sender_account_id),
+            signer_public_key: bulk_transfer.signer_public_key,
+            report: Some(bulk_transfer.report),
+            yes: bulk_transfer.yes,
+        }
+    }
+}
+
+impl BulkTransfer {
+    pub fn from(
+        item: CliBulkTransfer,
+        connection_config: crate::common::ConnectionConfig,
+    ) -> color_eyre::eyre::Result<Self> {
+        let csv = match item.csv {
+            Some(csv) => csv,
+            None => Input::<String>::new()
+                .with_prompt("Enter the path to the CSV file with receiver,amount lines")
+                .interact_text()
+                .unwrap()
+                .into(),
+        };
+        let sender_account_id = match item.sender_account_id {
+            Some(sender_account_id) => sender_account_id,
+            None => Input::new()
+                .with_prompt("What is the account ID of the sender?")
+                .interact_text()
+                .unwrap(),
+        };
+        let report = item.report.unwrap_or_else(|| {
+            let mut report = csv.clone().into_os_string();
+            report.push(".report.csv");
+            report.into()
+        });
+
+        let payments = read_payments(&csv)?;
+        let invalid_receivers = payments
+            .iter()
+            .filter(|payment| !crate::common::is_64_len_hex(&payment.receiver_id))
+            .map(|payment| {
+                Ok(
+                    match crate::common::check_account_id(
+                        connection_config.clone(),
+                        payment.receiver_id.clone(),
+                    )? {
+                        Some(_) => None,
+                        None => Some(format!(
+                            "line {}: account <{}> doesn't exist",
+                            payment.line, payment.receiver_id
+                        )),
+                    },
+                )
+            })
+            .collect::<color_eyre::eyre::Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        if !invalid_receivers.is_empty() {
+            return Err(color_eyre::Report::msg(format!(
+                "Invalid receivers in {}:\n{}",
+                csv.display(),
+                invalid_receivers.join("\n")
+            )));
+        }
+
+        let report_rows = read_report(&report)?;
+        let mut pending_transactions = vec![];
+        let mut payments_to_send = vec![];
+        for payment in payments.iter() {
+            match resume(report_rows.get(&payment_key(payment))) {
+                Resume::Send => payments_to_send.push(payment.clone()),
+                Resume::Skip => {}
+                Resume::LookUp(transaction_hash) => {
+                    match transaction_status(
+                        &connection_config,
+                        &transaction_hash,
+                        &sender_account_id,
+                    ) {
+                        Some(near_primitives::views::FinalExecutionStatus::SuccessValue(_)) => {}
+                        Some(near_primitives::views::FinalExecutionStatus::Failure(_)) => {
+                            payments_to_send.push(payment.clone())
+                        }
+                        _ => pending_transactions.push((payment.line, transaction_hash)),
+                    }
+                }
+            }
+        }
+        if !pending_transactions.is_empty() {
+            println!(
+                "\nThe outcome of these transfers is unknown, they are not sent again \
+                (check them with `view transaction-status` and remove their lines from {}):",
+                report.display()
+            );
+            for (line, transaction_hash) in pending_transactions {
+                println!("   line {}: transaction {}", line, transaction_hash);
+            }
+        }
+        let total_amount = payments_to_send
+            .iter()
+            .map(|payment| payment.amount.to_yoctonear())
+            .sum::<u128>();
+        println!(
+            "\n{} transfers from <{}> on {}: {} to send, {} already done or pending",
+            payments.len(),
+            sender_account_id,
+            connection_config.rpc_url(),
+            payments_to_send.len(),
+            payments.len() - payments_to_send.len()
+        );
+        println!(
+            "Total amount to send: {}",
+            crate::common::NearBalance::from_yoctonear(total_amount)
+        );
+        if item.yes {
+            crate::common::check_signing_policy_without_prompts(&connection_config)?;
+        }
+        if !payments_to_send.is_empty()
+            && !item.yes
+            && !Confirm::new()
+                .with_prompt("Do you want to send these transfers?")
+                .default(false)
+                .interact()?
+        {
+            return Err(color_eyre::Report::msg("The bulk transfer is canceled"));
+        }
+        Ok(Self {
+            sender_account_id,
+            signer_public_key: item.signer_public_key,
+            csv,
+            report,
+            yes: item.yes,
+            payments: payments_to_send,
+        })
+    }
+}
+
+fn read_payments(csv: &std::path::Path) -> color_eyre::eyre::Result<Vec<Payment>> {
+    let mut payments: Vec<Payment> = vec![];
+    for csv_line in crate::common::read_csv_lines(csv, "amount")? {
+        let line = csv_line.line;
+        let (receiver_id, amount) = match csv_line.columns.as_slice() {
+            [receiver_id, amount] => (receiver_id, amount),
+            _ => {
+                return Err(color_eyre::Report::msg(format!(
+                    "Line {} of {} has to be receiver,amount",
+                    line,
+                    csv.display()
+                )))
+            }
+        };
+        let receiver_id: near_primitives::types::AccountId =
+            receiver_id.parse().map_err(|err| {
+                color_eyre::Report::msg(format!(
+                    "Line {}: invalid receiver <{}>: {}",
+                    line, receiver_id, err
+                ))
+            })?;
+        let amount = crate::common::NearBalance::from_str(amount)
+            .map_err(|err| color_eyre::Report::msg(format!("Line {}: {}", line, err)))?;
+        let occurrence = payments
+            .iter()
+            .filter(|payment| {
+                payment.receiver_id == receiver_id
+                    && payment.amount.to_yoctonear() == amount.to_yoctonear()
+            })
+            .count()
+            + 1;
+        payments.push(Payment {
+            line,
+            receiver_id,
+            amount,
+            occurrence,
+        });
+    }
+    if payments.is_empty() {
+        return Err(color_eyre::Report::msg(format!(
+            "There are no transfers in {}",
+            csv.display()
+        )));
+    }
+    Ok(payments)
+}
+
+/// Identifies a transfer in the report by its receiver, its amount and how many times the same
+/// transfer is listed before it, so that editing other lines of the CSV does not change it.
+fn payment_key(payment: &Payment) -> (String, u128, usize) {
+    (
+        payment.receiver_id.to_string(),
+        payment.amount.to_yoctonear(),
+        payment.occurrence,
+    )
+}
+
+/// Decides what to do with a transfer from its latest status and transaction hash in the report.
+/// A transfer with an error may still have been sent, so it is not sent again blindly.
+fn resume(report_row: Option<&(String, String)>) -> Resume {
+    match report_row {
+        Some((status, _)) if status == "success" => Resume::Skip,
+        Some((status, transaction_hash))
+            if (status == "pending" || status == "error") && !transaction_hash.is_empty() =>
+        {
+            Resume::LookUp(transaction_hash.clone())
+        }
+        _ => Resume::Send,
+    }
+}
+
+/// Looks up the status of a transaction by its hash; `None` when the outcome is unknown.
+fn transaction_status(
+    connection_config: &crate::common::ConnectionConfig,
+    transaction_hash: &str,
+    sender_account_id: &near_primitives::types::AccountId,
+) -> Option<near_primitives::views::FinalExecutionStatus> {
+    actix::System::new()
+        .block_on(
+            near_jsonrpc_client::new_client(connection_config.archival_rpc_url().as_str())
+                .tx(transaction_hash.to_owned(), sender_account_id.clone()),
+        )
+        .ok()
+        .map(|transaction_info| transaction_info.status)
+}
+
+/// Reads the latest status and transaction hash of every transfer in the report.
+fn read_report(
+    report: &std::path::Path,
+) -> color_eyre::eyre::Result<std::collections::HashMap<(String, u128, usize), (String, String)>> {
+    let data = match std::fs::read_to_string(report) {
+        Ok(data) => data,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Default::default()),
+        Err(err) => {
+            return Err(color_eyre::Report::msg(format!(
+                "Failed to read the report {}: {}",
+                report.display(),
+                err
+            )))
+        }
+    };
+    Ok(data
+        .lines()
+        .filter(|line| *line != REPORT_HEADER)
+        .filter_map(|line| {
+            let columns = line.splitn(7, ',').collect::<Vec<_>>();
+            match columns.as_slice() {
+                [_, receiver_id, amount, occurrence, status, transaction_hash, _] => Some((
+                    (
+                        receiver_id.to_string(),
+                        amount.parse().ok()?,
+                        occurrence.parse().ok()?,
+                    ),
+                    (status.to_string(), transaction_hash.to_string()),
+                )),
+                _ => None,
+            }
+        })
+        .collect())
+}
+
+impl BulkTransfer {
+    pub async fn process(
+        self,
+        connection_config: crate::common::ConnectionConfig,
+    ) -> crate::CliResult {
+        if self.payments.is_empty() {
+            println!("\nThere is nothing to send.");
+            return Ok(());
+        }
+        let signer_public_key = match &self.signer_public_key {
+            Some(signer_public_key) => signer_public_key.clone(),
+            None => {
+                crate::common::find_full_access_keychain_key(
+                    &connection_config,
+                    &self.sender_account_id,
+                )
+                .await?
+            }
+        };
+        let signer = crate::common::KeychainSigner {
+            dir_name: connection_config.dir_name().to_owned(),
+            account_id: self.sender_account_id.clone(),
+            public_key: signer_public_key.clone(),
+        };
+        let is_new_report = std::fs::metadata(&self.report)
+            .map(|metadata| metadata.len() == 0)
+            .unwrap_or(true);
+        let mut report = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.report)
+            .map_err(|err| {
+                color_eyre::Report::msg(format!(
+                    "Failed to open the report {}: {}",
+                    self.report.display(),
+                    err
+                ))
+            })?;
+        if is_new_report {
+            writeln!(report, "{}", REPORT_HEADER)?;
+        }
+        let mut succeeded = 0;
+        for payment in self.payments.iter() {
+            println!(
+                "\nline {}: {} to <{}>",
+                payment.line, payment.amount, payment.receiver_id
+            );
+            let mut nonce_retried = false;
+            let (transaction_hash, outcome) = loop {
+                let (nonce, block_hash) =
+                    crate::commands::construct_transaction_command::sign_transaction::reserve_nonce(
+                        &connection_config,
+                        &self.sender_account_id,
+                        &signer_public_key,
+                    )
+                    .await?;
+                let unsigned_transaction = near_primitives::transaction::Transaction {
+                    signer_id: self.sender_account_id.clone(),
+                    public_key: signer_public_key.clone(),
+                    nonce,
+                    receiver_id: payment.receiver_id.clone(),
+                    block_hash,
+                    actions: vec![near_primitives::transaction::Action::Transfer(
+                        near_primitives::transaction::TransferAction {
+                            deposit: payment.amount.to_yoctonear(),
+                        },
+                    )],
+                };
+                crate::common::enforce_signing_policy(&unsigned_transaction, &connection_config)?;
+                let signature =
+                    crate::common::TransactionSigner::sign(&signer, &unsigned_transaction).await?;
+                let signed_transaction = near_primitives::transaction::SignedTransaction::new(
+                    signature,
+                    unsigned_transaction,
+                );
+                let transaction_hash = signed_transaction.get_hash().to_string();
+                write_report_row(&mut report, payment, "pending", &transaction_hash, "")?;
+                let serialize_to_base64 = near_primitives::serialize::to_base64(
+                    signed_transaction
+                        .try_to_vec()
+                        .expect("Transaction is not expected to fail on serialization"),
+                );
+                let outcome =
+                    crate::commands::construct_transaction_command::sign_transaction::Submit::Send
+                        .process_online(
+                            connection_config.clone(),
+                            signed_transaction,
+                            serialize_to_base64,
+                            false,
+                        )
+                        .await;
+                match outcome {
+                    Err(err)
+                        if !nonce_retried
+                            && err
+                                .downcast_ref::<crate::common::InvalidNonceError>()
+                                .is_some() =>
+                    {
+                        println!("{}\nRe-signing the transaction with a fresh nonce ...", err);
+                        write_report_row(
+                            &mut report,
+                            payment,
+                            "error",
+                            &transaction_hash,
+                            &err.to_string(),
+                        )?;
+                        nonce_retried = true;
+                    }
+                    outcome => break (transaction_hash, outcome),
+                }
+            };
+            let (status, error) = match outcome {
+                Ok(Some(transaction_info)) => match transaction_info.status {
+                    near_primitives::views::FinalExecutionStatus::SuccessValue(_) => {
+                        succeeded += 1;
+                        ("success", String::new())
+                    }
+                    near_primitives::views::FinalExecutionStatus::Failure(tx_execution_error) => {
+                        ("failure", format!("{:?}", tx_execution_error))
+                    }
+                    near_primitives::views::FinalExecutionStatus::NotStarted
+                    | near_primitives::views::FinalExecutionStatus::Started => {
+                        ("pending", String::new())
+                    }
+                },
+                Ok(None) => (
+                    "error",
+                    "The transaction was not accepted by the node".to_owned(),
+                ),
+                Err(err) => ("error", err.to_string()),
+            };
+            write_report_row(&mut report, payment, status, &transaction_hash, &error)?;
+        }
+        println!(
+            "\n{} of {} transfers succeeded, the report is saved in a file {}",
+            succeeded,
+            self.payments.len(),
+            self.report.display()
+        );
+        Ok(())
+    }
+}
+
+fn write_report_row(
+    report: &mut std::fs::File,
+    payment: &Payment,
+    status: &str,
+    transaction_hash: &str,
+    error: &str,
+) -> crate::CliResult {
+    writeln!(
+        report,
+        "{},{},{},{},{},{},{}",
+        payment.line,
+        payment.receiver_id,
+        payment.amount.to_yoctonear(),
+        payment.occurrence,
+        status,
+        transaction_hash,
+        error.replace(|c| c == ',' || c == '\n', " ")
+    )?;
+    report.sync_data()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_payments_skips_the_header_comments_and_blank_lines() {
+        let csv = crate::common::write_temp_csv(
+            "payments.csv",
+            "# team\nreceiver,amount\nalice.testnet, 1.5 NEAR\n\nbob.testnet,2 NEAR\nalice.testnet,1.5 NEAR\n",
+        );
+        let payments = read_payments(&csv).unwrap();
+        assert_eq!(
+            payments
+                .iter()
+                .map(|payment| (
+                    payment.line,
+                    payment.receiver_id.to_string(),
+                    payment.amount.to_yoctonear(),
+                    payment.occurrence
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (3, "alice.testnet".to_owned(), 15 * 10u128.pow(23), 1),
+                (5, "bob.testnet".to_owned(), 2 * 10u128.pow(24), 1),
+                (6, "alice.testnet".to_owned(), 15 * 10u128.pow(23), 2),
+            ]
+        );
+        std::fs::remove_file(csv).unwrap();
+    }
+
+    #[test]
+    fn read_payments_rejects_malformed_lines() {
+        let csv =
+            crate::common::write_temp_csv("malformed.csv", "alice.testnet,1 NEAR\nbob.testnet\n");
+        assert_eq!(
+            read_payments(&csv).unwrap_err().to_string(),
+            format!("Line 2 of {} has to be receiver,amount", csv.display())
+        );
+        std::fs::remove_file(csv).unwrap();
+        let csv = crate::common::write_temp_csv("invalid-amount.csv", "alice.testnet,one NEAR\n");
+        assert!(read_payments(&csv)
+            .unwrap_err()
+            .to_string()
+            .starts_with("Line 1: "));
+        std::fs::remove_file(csv).unwrap();
+        let csv = crate::common::write_temp_csv("empty.csv", "receiver,amount\n# nothing yet\n");
+        assert_eq!(
+            read_payments(&csv).unwrap_err().to_string(),
+            format!("There are no transfers in {}", csv.display())
+        );
+        std::fs::remove_file(csv).unwrap();
+    }
+
+    #[test]
+    fn read_report_keeps_the_latest_row_of_every_transfer() {
+        let report = crate::common::write_temp_csv(
+            "payments.csv.report.csv",
+            &format!(
+                "{}\n\
+                1,alice.testnet,100,1,pending,hash1,\n\
+                2,alice.testnet,100,1,success,hash1,\n\
+                2,bob.testnet,200,1,error,,Failed to fetch the nonce, try again\n\
+                not a report row\n",
+                REPORT_HEADER
+            ),
+        );
+        let report_rows = read_report(&report).unwrap();
+        assert_eq!(report_rows.len(), 2);
+        assert_eq!(
+            report_rows.get(&("alice.testnet".to_owned(), 100, 1)),
+            Some(&("success".to_owned(), "hash1".to_owned()))
+        );
+        assert_eq!(
+            report_rows.get(&("bob.testnet".to_owned(), 200, 1)),
+            Some(&("error".to_owned(), String::new()))
+        );
+        std::fs::remove_file(&report).unwrap();
+        assert!(read_report(&report).unwrap().is_empty());
+    }
+
+    #[test]
+    fn resume_looks_up_the_transfers_that_may_have_been_sent() {
+        let row =
+            |status: &str, transaction_hash: &str| (status.to_owned(), transaction_hash.to_owned());
+        assert_eq!(resume(None), Resume::Send);
+        assert_eq!(resume(Some(&row("success", "hash1"))), Resume::Skip);
+        assert_eq!(
+            resume(Some(&row("pending", "hash2"))),
+            Resume::LookUp("hash2".to_owned())
+        );
+        assert_eq!(
+            resume(Some(&row("error", "hash3"))),
+            Resume::LookUp("hash3".to_owned())
+        );
+        assert_eq!(resume(Some(&row("error", ""))), Resume::Send);
+        assert_eq!(resume(Some(&row("failure", "hash4"))), Resume::Send);
+    }
+}
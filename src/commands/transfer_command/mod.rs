@@ -1,6 +1,7 @@
 use dialoguer::{theme::ColorfulTheme, Select};
 use strum::{EnumDiscriminants, EnumIter, EnumMessage, IntoEnumIterator};
 
+mod bulk;
 pub mod operation_mode;
 mod receiver;
 mod sender;
@@ -65,6 +66,8 @@ impl Currency {
 enum CliCurrencySelection {
     /// отправка трансфера в NEAR tokens
     NEAR(self::operation_mode::CliOperationMode),
+    /// Transfer NEAR tokens to many receivers listed in a CSV file
+    Bulk(self::bulk::operation_mode::CliOperationMode),
 }
 
 #[derive(Debug, Clone, EnumDiscriminants)]
@@ -72,6 +75,8 @@ enum CliCurrencySelection {
 enum CurrencySelection {
     #[strum_discriminants(strum(message = "NEAR tokens"))]
     NEAR(self::operation_mode::OperationMode),
+    #[strum_discriminants(strum(message = "NEAR tokens to many receivers from a CSV file"))]
+    Bulk(self::bulk::operation_mode::OperationMode),
 }
 
 impl CliCurrencySelection {
@@ -82,6 +87,11 @@ impl CliCurrencySelection {
                 args.push_front("near".to_owned());
                 args
             }
+            Self::Bulk(operation_mode) => {
+                let mut args = operation_mode.to_cli_args();
+                args.push_front("bulk".to_owned());
+                args
+            }
         }
     }
 }
@@ -92,6 +102,9 @@ impl From<CurrencySelection> for CliCurrencySelection {
             CurrencySelection::NEAR(operation_mode) => {
                 Self::NEAR(self::operation_mode::CliOperationMode::from(operation_mode))
             }
+            CurrencySelection::Bulk(operation_mode) => Self::Bulk(
+                self::bulk::operation_mode::CliOperationMode::from(operation_mode),
+            ),
         }
     }
 }
@@ -102,6 +115,9 @@ impl CurrencySelection {
             CliCurrencySelection::NEAR(cli_operation_mode) => Ok(Self::NEAR(
                 self::operation_mode::OperationMode::from(cli_operation_mode)?,
             )),
+            CliCurrencySelection::Bulk(cli_operation_mode) => Ok(Self::Bulk(
+                self::bulk::operation_mode::OperationMode::from(cli_operation_mode)?,
+            )),
         }
     }
 }
@@ -122,6 +138,7 @@ impl CurrencySelection {
             .unwrap();
         let cli_currency = match variants[selected_currency] {
             CurrencySelectionDiscriminants::NEAR => CliCurrencySelection::NEAR(Default::default()),
+            CurrencySelectionDiscriminants::Bulk => CliCurrencySelection::Bulk(Default::default()),
         };
         Ok(Self::from(cli_currency)?)
     }
@@ -136,6 +153,7 @@ impl CurrencySelection {
                     .process(prepopulated_unsigned_transaction)
                     .await
             }
+            Self::Bulk(operation_mode) => operation_mode.process().await,
         }
     }
 }
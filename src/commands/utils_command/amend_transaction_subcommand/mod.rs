@@ -0,0 +1,395 @@
+use std::str::FromStr;
+
+use dialoguer::Input;
+use near_primitives::borsh::BorshSerialize;
+
+pub mod operation_mode;
+
+/// Change the nonce, the block hash, the receiver or the gas and deposit of one action of a
+/// serialized transaction (signed or not). Without a sign option the amended transaction is
+/// printed unsigned.
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliAmendTransaction {
+    /// Base64-encoded transaction, signed or unsigned
+    transaction: Option<String>,
+    #[clap(long)]
+    nonce: Option<u64>,
+    #[clap(long)]
+    block_hash: Option<near_primitives::hash::CryptoHash>,
+    #[clap(long)]
+    receiver_id: Option<near_primitives::types::AccountId>,
+    /// Index (starting from 0) of the action to change with --gas and --deposit
+    #[clap(long)]
+    action_index: Option<usize>,
+    #[clap(long)]
+    gas: Option<crate::common::NearGas>,
+    #[clap(long)]
+    deposit: Option<crate::common::NearBalance>,
+    /// In the network mode the nonce and the block hash are refreshed and the sign option works
+    /// online
+    #[clap(subcommand)]
+    mode: Option<self::operation_mode::CliMode>,
+}
+
+impl CliAmendTransaction {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = self
+            .mode
+            .as_ref()
+            .map(|subcommand| subcommand.to_cli_args())
+            .unwrap_or_default();
+        if let Some(deposit) = &self.deposit {
+            args.push_front(deposit.to_string());
+            args.push_front("--deposit".to_owned());
+        }
+        if let Some(gas) = &self.gas {
+            args.push_front(gas.to_string());
+            args.push_front("--gas".to_owned());
+        }
+        if let Some(action_index) = &self.action_index {
+            args.push_front(action_index.to_string());
+            args.push_front("--action-index".to_owned());
+        }
+        if let Some(receiver_id) = &self.receiver_id {
+            args.push_front(receiver_id.to_string());
+            args.push_front("--receiver-id".to_owned());
+        }
+        if let Some(block_hash) = &self.block_hash {
+            args.push_front(block_hash.to_string());
+            args.push_front("--block-hash".to_owned());
+        }
+        if let Some(nonce) = &self.nonce {
+            args.push_front(nonce.to_string());
+            args.push_front("--nonce".to_owned());
+        }
+        if let Some(transaction) = &self.transaction {
+            args.push_front(transaction.to_owned());
+        }
+        args
+    }
+
+    fn decode_transaction(
+        transaction: &str,
+    ) -> color_eyre::eyre::Result<near_primitives::transaction::Transaction> {
+        if let Ok(transaction) = crate::common::TransactionAsBase64::from_str(transaction) {
+            return Ok(transaction.inner);
+        }
+        let signed_transaction = crate::common::SignedTransactionAsBase64::from_str(transaction)
+            .map_err(color_eyre::Report::msg)?
+            .inner;
+        println!(
+            "The signature of the transaction {} is dropped: the amended transaction has to be signed again.",
+            signed_transaction.get_hash()
+        );
+        Ok(signed_transaction.transaction)
+    }
+
+    async fn fetch_nonce_and_block_hash(
+        connection_config: &crate::common::ConnectionConfig,
+        transaction: &near_primitives::transaction::Transaction,
+    ) -> color_eyre::eyre::Result<(u64, near_primitives::hash::CryptoHash)> {
+        let access_key_response =
+            near_jsonrpc_client::new_client(connection_config.rpc_url().as_str())
+                .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
+                    block_reference: near_primitives::types::Finality::Final.into(),
+                    request: near_primitives::views::QueryRequest::ViewAccessKey {
+                        account_id: transaction.signer_id.clone(),
+                        public_key: transaction.public_key.clone(),
+                    },
+                })
+                .await
+                .map_err(|err| {
+                    color_eyre::Report::msg(format!(
+                        "Failed to fetch public key information for nonce: {:?}",
+                        err
+                    ))
+                })?;
+        match access_key_response.kind {
+            near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKey(access_key) => {
+                Ok((access_key.nonce + 1, access_key_response.block_hash))
+            }
+            _ => Err(color_eyre::Report::msg("Error current_nonce")),
+        }
+    }
+
+    fn amend_action(
+        &self,
+        transaction: &mut near_primitives::transaction::Transaction,
+    ) -> crate::CliResult {
+        if self.gas.is_none() && self.deposit.is_none() {
+            if self.action_index.is_some() {
+                return Err(color_eyre::Report::msg(
+                    "--action-index is used together with --gas or --deposit",
+                ));
+            }
+            return Ok(());
+        }
+        let action_index = match (self.action_index, transaction.actions.len()) {
+            (Some(action_index), _) => action_index,
+            (None, 1) => 0,
+            (None, actions_count) => {
+                return Err(color_eyre::Report::msg(format!(
+                    "The transaction has {} actions, specify the one to change with --action-index",
+                    actions_count
+                )))
+            }
+        };
+        let action = transaction.actions.get_mut(action_index).ok_or_else(|| {
+            color_eyre::Report::msg(format!("There is no action with index {}", action_index))
+        })?;
+        match action {
+            near_primitives::transaction::Action::FunctionCall(function_call_action) => {
+                if let Some(gas) = &self.gas {
+                    function_call_action.gas = gas.inner;
+                }
+                if let Some(deposit) = &self.deposit {
+                    function_call_action.deposit = deposit.to_yoctonear();
+                }
+            }
+            near_primitives::transaction::Action::Transfer(transfer_action)
+                if self.gas.is_none() =>
+            {
+                if let Some(deposit) = &self.deposit {
+                    transfer_action.deposit = deposit.to_yoctonear();
+                }
+            }
+            _ => {
+                return Err(color_eyre::Report::msg(format!(
+                    "The action with index {} has no {}",
+                    action_index,
+                    if self.gas.is_some() { "gas" } else { "deposit" }
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the changes given on the command line; the nonce and the block hash fetched in the
+    /// network mode are applied afterwards.
+    fn amend(
+        &self,
+        transaction: &mut near_primitives::transaction::Transaction,
+    ) -> crate::CliResult {
+        if let Some(receiver_id) = &self.receiver_id {
+            transaction.receiver_id = receiver_id.clone();
+        }
+        self.amend_action(transaction)?;
+        if let Some(nonce) = self.nonce {
+            transaction.nonce = nonce;
+        }
+        if let Some(block_hash) = self.block_hash {
+            transaction.block_hash = block_hash;
+        }
+        Ok(())
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        let mut transaction = match &self.transaction {
+            Some(transaction) => Self::decode_transaction(transaction)?,
+            None => loop {
+                let transaction: String = Input::new()
+                    .with_prompt("Enter the serialized transaction (base64)")
+                    .interact_text()
+                    .unwrap();
+                match Self::decode_transaction(&transaction) {
+                    Ok(transaction) => break transaction,
+                    Err(err) => println!("{}", err),
+                }
+            },
+        };
+        let mode = match self.mode.clone() {
+            Some(cli_mode) => self::operation_mode::Mode::from(cli_mode),
+            None => self::operation_mode::Mode::choose_mode(),
+        };
+        let connection_config = mode.connection_config();
+        if connection_config.is_some() && (self.nonce.is_some() || self.block_hash.is_some()) {
+            return Err(color_eyre::Report::msg(
+                "--nonce and --block-hash cannot be combined with the network mode, which refreshes them",
+            ));
+        }
+        self.amend(&mut transaction)?;
+        if let Some(connection_config) = &connection_config {
+            let (nonce, block_hash) =
+                Self::fetch_nonce_and_block_hash(connection_config, &transaction).await?;
+            transaction.nonce = nonce;
+            transaction.block_hash = block_hash;
+        }
+        match mode.sign_option() {
+            None => {
+                println!("\nAmended unsigned transaction:\n");
+                crate::common::print_transaction(transaction.clone());
+                println!(
+                    "\nSerialize_to_base64:\n{}",
+                    near_primitives::serialize::to_base64(
+                        transaction
+                            .try_to_vec()
+                            .expect("Transaction is not expected to fail on serialization")
+                    )
+                );
+            }
+            Some(mut cli_sign_option) => {
+                cli_sign_option.prefill_offline_values(
+                    &transaction.public_key,
+                    transaction.nonce,
+                    transaction.block_hash,
+                );
+                let sign_option = crate::commands::construct_transaction_command::sign_transaction::SignTransaction::from(
+                    cli_sign_option,
+                    connection_config.clone(),
+                    transaction.signer_id.clone(),
+                )?;
+                if let Some(transaction_info) = sign_option
+                    .process(transaction, connection_config.clone())
+                    .await?
+                {
+                    crate::common::print_transaction_status(transaction_info, connection_config)
+                        .await;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unsigned_transaction() -> near_primitives::transaction::Transaction {
+        near_primitives::transaction::Transaction {
+            signer_id: "alice.testnet".parse().unwrap(),
+            public_key: near_crypto::PublicKey::empty(near_crypto::KeyType::ED25519),
+            nonce: 0,
+            receiver_id: "contract.testnet".parse().unwrap(),
+            block_hash: Default::default(),
+            actions: vec![near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: "set_greeting".to_owned(),
+                    args: vec![],
+                    gas: 10u64.pow(12),
+                    deposit: 0,
+                },
+            )],
+        }
+    }
+
+    fn signed_transaction_base64(
+        transaction: &near_primitives::transaction::Transaction,
+    ) -> (String, near_crypto::Signature) {
+        let secret_key = near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, "amend");
+        let transaction = near_primitives::transaction::Transaction {
+            public_key: secret_key.public_key(),
+            ..transaction.clone()
+        };
+        let signature = secret_key.sign(transaction.get_hash_and_size().0.as_ref());
+        let signed_transaction =
+            near_primitives::transaction::SignedTransaction::new(signature.clone(), transaction);
+        (
+            near_primitives::serialize::to_base64(signed_transaction.try_to_vec().unwrap()),
+            signature,
+        )
+    }
+
+    #[test]
+    fn signed_and_unsigned_transactions_are_decoded() {
+        let transaction = unsigned_transaction();
+        let unsigned_transaction_base64 =
+            near_primitives::serialize::to_base64(transaction.try_to_vec().unwrap());
+        assert_eq!(
+            CliAmendTransaction::decode_transaction(&unsigned_transaction_base64).unwrap(),
+            transaction
+        );
+
+        let (signed_transaction_base64, signature) = signed_transaction_base64(&transaction);
+        let decoded_transaction =
+            CliAmendTransaction::decode_transaction(&signed_transaction_base64).unwrap();
+        assert_eq!(decoded_transaction.actions, transaction.actions);
+        assert!(
+            crate::common::verify_transaction_signature(&decoded_transaction, &signature).is_ok()
+        );
+
+        assert!(CliAmendTransaction::decode_transaction("not a transaction").is_err());
+    }
+
+    #[test]
+    fn amended_transaction_gets_a_new_hash_and_needs_a_new_signature() {
+        let (signed_transaction_base64, signature) =
+            signed_transaction_base64(&unsigned_transaction());
+        let mut transaction =
+            CliAmendTransaction::decode_transaction(&signed_transaction_base64).unwrap();
+        let original_hash = transaction.get_hash_and_size().0;
+
+        let amend_transaction = CliAmendTransaction {
+            nonce: Some(42),
+            block_hash: Some(near_primitives::hash::hash(b"recent block")),
+            receiver_id: Some("other.testnet".parse().unwrap()),
+            deposit: Some(crate::common::NearBalance::from_yoctonear(5)),
+            ..CliAmendTransaction::default()
+        };
+        amend_transaction.amend(&mut transaction).unwrap();
+
+        assert_eq!(transaction.nonce, 42);
+        assert_eq!(
+            transaction.block_hash,
+            near_primitives::hash::hash(b"recent block")
+        );
+        assert_eq!(transaction.receiver_id.as_ref(), "other.testnet");
+        assert_ne!(transaction.get_hash_and_size().0, original_hash);
+        assert!(crate::common::verify_transaction_signature(&transaction, &signature).is_err());
+    }
+
+    #[test]
+    fn gas_and_deposit_change_the_selected_action() {
+        let mut transaction = unsigned_transaction();
+        CliAmendTransaction {
+            gas: Some(crate::common::NearGas::from(30 * 10u64.pow(12))),
+            deposit: Some(crate::common::NearBalance::from_yoctonear(1)),
+            ..CliAmendTransaction::default()
+        }
+        .amend(&mut transaction)
+        .unwrap();
+        assert_eq!(
+            transaction.actions,
+            vec![near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: "set_greeting".to_owned(),
+                    args: vec![],
+                    gas: 30 * 10u64.pow(12),
+                    deposit: 1,
+                },
+            )]
+        );
+    }
+
+    #[test]
+    fn ambiguous_or_unsupported_action_changes_are_rejected() {
+        let mut transaction = unsigned_transaction();
+        transaction
+            .actions
+            .push(near_primitives::transaction::Action::Transfer(
+                near_primitives::transaction::TransferAction { deposit: 1 },
+            ));
+        let original_transaction = transaction.clone();
+
+        let amend_gas = |action_index| CliAmendTransaction {
+            action_index,
+            gas: Some(crate::common::NearGas::from(10u64.pow(12))),
+            ..CliAmendTransaction::default()
+        };
+        assert!(amend_gas(None).amend(&mut transaction).is_err());
+        assert!(amend_gas(Some(1)).amend(&mut transaction).is_err());
+        assert!(amend_gas(Some(2)).amend(&mut transaction).is_err());
+        assert!(CliAmendTransaction {
+            action_index: Some(0),
+            ..CliAmendTransaction::default()
+        }
+        .amend(&mut transaction)
+        .is_err());
+        assert_eq!(transaction, original_transaction);
+    }
+}
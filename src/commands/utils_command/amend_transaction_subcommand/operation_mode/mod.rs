@@ -0,0 +1,105 @@
+use dialoguer::{theme::ColorfulTheme, Select};
+use strum::{EnumDiscriminants, EnumIter, EnumMessage, IntoEnumIterator};
+
+pub mod offline_mode;
+pub mod online_mode;
+
+#[derive(Debug, Clone, clap::Clap)]
+pub enum CliMode {
+    /// Refresh the nonce and the block hash of the transaction online
+    Network(self::online_mode::CliNetworkArgs),
+    /// Amend the transaction offline
+    Offline(self::offline_mode::CliOfflineArgs),
+}
+
+#[derive(Debug, Clone, EnumDiscriminants)]
+#[strum_discriminants(derive(EnumMessage, EnumIter))]
+pub enum Mode {
+    #[strum_discriminants(strum(message = "Yes, I keep it simple"))]
+    Network(self::online_mode::NetworkArgs),
+    #[strum_discriminants(strum(
+        message = "No, I want to work in no-network (air-gapped) environment"
+    ))]
+    Offline(self::offline_mode::OfflineArgs),
+}
+
+impl CliMode {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        match self {
+            Self::Network(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("network".to_owned());
+                args
+            }
+            Self::Offline(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("offline".to_owned());
+                args
+            }
+        }
+    }
+}
+
+impl From<Mode> for CliMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Network(network_args) => {
+                Self::Network(self::online_mode::CliNetworkArgs::from(network_args))
+            }
+            Mode::Offline(offline_args) => {
+                Self::Offline(self::offline_mode::CliOfflineArgs::from(offline_args))
+            }
+        }
+    }
+}
+
+impl From<CliMode> for Mode {
+    fn from(item: CliMode) -> Self {
+        match item {
+            CliMode::Network(cli_network_args) => Self::Network(cli_network_args.into()),
+            CliMode::Offline(cli_offline_args) => Self::Offline(cli_offline_args.into()),
+        }
+    }
+}
+
+impl Mode {
+    pub fn choose_mode() -> Self {
+        println!();
+        let variants = ModeDiscriminants::iter().collect::<Vec<_>>();
+        let modes = variants
+            .iter()
+            .map(|p| p.get_message().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        let selected_mode = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(
+                "Do you want to refresh the nonce and the block hash of the transaction online?",
+            )
+            .items(&modes)
+            .default(0)
+            .interact()
+            .unwrap();
+        let cli_mode = match variants[selected_mode] {
+            ModeDiscriminants::Network => CliMode::Network(Default::default()),
+            ModeDiscriminants::Offline => CliMode::Offline(Default::default()),
+        };
+        Self::from(cli_mode)
+    }
+
+    /// The network the nonce and the block hash are refreshed from; `None` offline.
+    pub fn connection_config(&self) -> Option<crate::common::ConnectionConfig> {
+        match self {
+            Self::Network(network_args) => Some(network_args.server().connection_config.clone()),
+            Self::Offline(_) => None,
+        }
+    }
+
+    pub fn sign_option(
+        &self,
+    ) -> Option<crate::commands::construct_transaction_command::sign_transaction::CliSignTransaction>
+    {
+        match self {
+            Self::Network(network_args) => network_args.server().sign_option.clone(),
+            Self::Offline(offline_args) => offline_args.sign_option.clone(),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+/// аргументы, необходимые для изменения транзакции в offline mode
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliOfflineArgs {
+    #[clap(subcommand)]
+    pub sign_option: Option<
+        crate::commands::construct_transaction_command::sign_transaction::CliSignTransaction,
+    >,
+}
+
+#[derive(Debug, Clone)]
+pub struct OfflineArgs {
+    pub sign_option: Option<
+        crate::commands::construct_transaction_command::sign_transaction::CliSignTransaction,
+    >,
+}
+
+impl CliOfflineArgs {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        self.sign_option
+            .as_ref()
+            .map(|subcommand| subcommand.to_cli_args())
+            .unwrap_or_default()
+    }
+}
+
+impl From<OfflineArgs> for CliOfflineArgs {
+    fn from(offline_args: OfflineArgs) -> Self {
+        Self {
+            sign_option: offline_args.sign_option,
+        }
+    }
+}
+
+impl From<CliOfflineArgs> for OfflineArgs {
+    fn from(item: CliOfflineArgs) -> Self {
+        Self {
+            sign_option: item.sign_option,
+        }
+    }
+}
@@ -89,6 +89,7 @@ impl CombineTransactionSignature {
     }
 
     pub async fn process(self) -> crate::CliResult {
+        crate::common::verify_transaction_signature(&self.unsigned_transaction, &self.signature)?;
         let signed_transaction = near_primitives::transaction::SignedTransaction::new(
             self.signature,
             self.unsigned_transaction,
@@ -0,0 +1,289 @@
+use std::str::FromStr;
+
+use dialoguer::Input;
+
+const HARDEND: u32 = 1 << 31;
+
+/// Derive a range of account keys from one master seed phrase
+#[derive(Debug, Clone, clap::Clap)]
+pub struct CliDeriveKeys {
+    #[clap(long)]
+    pub master_seed_phrase: Option<String>,
+    /// BIP-39 passphrase that protects the master seed phrase
+    #[clap(long)]
+    pub seed_phrase_passphrase: Option<String>,
+    /// HD path to which the hardened account index is appended
+    #[clap(long, default_value = "m/44'/397'")]
+    pub seed_phrase_hd_path_prefix: slip10::BIP32Path,
+    #[clap(long, default_value = "0", parse(try_from_str = crate::common::parse_hardened_index))]
+    pub from_index: u32,
+    #[clap(long, default_value = "10")]
+    pub count: u32,
+    #[clap(long, default_value = "ed25519")]
+    pub key_type: crate::common::KeyType,
+    #[clap(long, default_value = "plaintext")]
+    pub format: crate::common::OutputFormat,
+}
+
+impl Default for CliDeriveKeys {
+    fn default() -> Self {
+        Self {
+            master_seed_phrase: None,
+            seed_phrase_passphrase: None,
+            seed_phrase_hd_path_prefix: slip10::BIP32Path::from_str("m/44'/397'").unwrap(),
+            from_index: 0,
+            count: 10,
+            key_type: crate::common::KeyType::Ed25519,
+            format: crate::common::OutputFormat::Plaintext,
+        }
+    }
+}
+
+impl CliDeriveKeys {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        args.push_front(self.format.to_string());
+        args.push_front("--format".to_owned());
+        args.push_front(self.key_type.to_string());
+        args.push_front("--key-type".to_owned());
+        args.push_front(self.count.to_string());
+        args.push_front("--count".to_owned());
+        args.push_front(self.from_index.to_string());
+        args.push_front("--from-index".to_owned());
+        args.push_front(self.seed_phrase_hd_path_prefix.to_string());
+        args.push_front("--seed-phrase-hd-path-prefix".to_owned());
+        if let Some(seed_phrase_passphrase) = &self.seed_phrase_passphrase {
+            args.push_front(seed_phrase_passphrase.to_owned());
+            args.push_front("--seed-phrase-passphrase".to_owned());
+        }
+        if let Some(master_seed_phrase) = &self.master_seed_phrase {
+            args.push_front(master_seed_phrase.to_owned());
+            args.push_front("--master-seed-phrase".to_owned());
+        }
+        args
+    }
+
+    fn input_master_seed_phrase() -> String {
+        Input::new()
+            .with_prompt("Enter the master seed phrase")
+            .interact_text()
+            .unwrap()
+    }
+
+    fn seed_phrase_hd_path(&self, index: u32) -> color_eyre::eyre::Result<slip10::BIP32Path> {
+        let mut path = (0..self.seed_phrase_hd_path_prefix.depth())
+            .map(|depth| *self.seed_phrase_hd_path_prefix.index(depth).unwrap())
+            .chain(std::iter::once(index | HARDEND))
+            .map(|value| {
+                if value < HARDEND {
+                    value.to_string()
+                } else {
+                    format!("{}'", value - HARDEND)
+                }
+            })
+            .collect::<Vec<String>>();
+        path.insert(0, "m".to_owned());
+        slip10::BIP32Path::from_str(&path.join("/"))
+            .map_err(|err| color_eyre::Report::msg(format!("Invalid HD path: {:?}", err)))
+    }
+
+    /// Derives the keys for the account indexes `from_index..from_index + count`.
+    fn derive_keys(
+        &self,
+        master_seed: &[u8],
+    ) -> color_eyre::eyre::Result<
+        Vec<(
+            u32,
+            slip10::BIP32Path,
+            String,
+            Option<near_primitives::types::AccountId>,
+        )>,
+    > {
+        let to_index = self
+            .from_index
+            .checked_add(self.count)
+            .filter(|to_index| *to_index <= crate::common::HARDENED_INDEX_BOUND)
+            .ok_or_else(|| {
+                color_eyre::Report::msg(format!(
+                    "The {} account indexes starting from {} go beyond 2^31 = {}",
+                    self.count,
+                    self.from_index,
+                    crate::common::HARDENED_INDEX_BOUND
+                ))
+            })?;
+        let mut derived_keys = vec![];
+        for index in self.from_index..to_index {
+            let seed_phrase_hd_path = self.seed_phrase_hd_path(index)?;
+            let (implicit_account_id, public_key_str, _) =
+                crate::common::derive_key_pair_from_seed(
+                    master_seed,
+                    &seed_phrase_hd_path,
+                    self.key_type,
+                )?;
+            derived_keys.push((
+                index,
+                seed_phrase_hd_path,
+                public_key_str,
+                implicit_account_id,
+            ));
+        }
+        Ok(derived_keys)
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        let master_seed_phrase = match &self.master_seed_phrase {
+            Some(master_seed_phrase) => master_seed_phrase.to_owned(),
+            None => Self::input_master_seed_phrase(),
+        };
+        let master_seed = bip39::Mnemonic::parse(&master_seed_phrase)?
+            .to_seed(self.seed_phrase_passphrase.as_deref().unwrap_or_default());
+        let derived_keys = self.derive_keys(&master_seed)?;
+        match self.format {
+            crate::common::OutputFormat::Plaintext => {
+                for (index, seed_phrase_hd_path, public_key_str, implicit_account_id) in
+                    derived_keys.iter()
+                {
+                    println!(
+                        "{: >4}. {} {}{}",
+                        index,
+                        seed_phrase_hd_path.to_string(),
+                        public_key_str,
+                        implicit_account_id
+                            .as_ref()
+                            .map(|account_id| format!(" {}", account_id))
+                            .unwrap_or_default()
+                    );
+                }
+            }
+            crate::common::OutputFormat::Json => {
+                let derived_keys = derived_keys
+                    .iter()
+                    .map(
+                        |(index, seed_phrase_hd_path, public_key_str, implicit_account_id)| {
+                            serde_json::json!({
+                                "index": index,
+                                "seed_phrase_hd_path": seed_phrase_hd_path.to_string(),
+                                "public_key": public_key_str,
+                                "account_id": implicit_account_id,
+                            })
+                        },
+                    )
+                    .collect::<Vec<_>>();
+                println!("{}", serde_json::to_string_pretty(&derived_keys)?);
+            }
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MASTER_SEED_PHRASE: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn master_seed(seed_phrase_passphrase: &str) -> [u8; 64] {
+        bip39::Mnemonic::parse(MASTER_SEED_PHRASE)
+            .unwrap()
+            .to_seed(seed_phrase_passphrase)
+    }
+
+    #[test]
+    fn hardened_account_index_is_appended_to_the_prefix() {
+        let derive_keys = CliDeriveKeys::default();
+        assert_eq!(
+            derive_keys.seed_phrase_hd_path(3).unwrap().to_string(),
+            "m/44'/397'/3'"
+        );
+        let derive_keys = CliDeriveKeys {
+            seed_phrase_hd_path_prefix: slip10::BIP32Path::from_str("m/44'/397'/0'/1").unwrap(),
+            ..CliDeriveKeys::default()
+        };
+        assert_eq!(
+            derive_keys.seed_phrase_hd_path(7).unwrap().to_string(),
+            "m/44'/397'/0'/1/7'"
+        );
+    }
+
+    #[test]
+    fn account_indexes_beyond_the_hardened_bound_are_rejected() {
+        assert!(crate::common::parse_hardened_index("2147483647").is_ok());
+        assert!(crate::common::parse_hardened_index("2147483648").is_err());
+        let derive_keys = CliDeriveKeys {
+            from_index: crate::common::HARDENED_INDEX_BOUND - 2,
+            count: 3,
+            ..CliDeriveKeys::default()
+        };
+        assert!(derive_keys.derive_keys(&master_seed("")).is_err());
+    }
+
+    #[test]
+    fn derived_keys_match_the_generated_key_pair_for_the_same_path() {
+        let derive_keys = CliDeriveKeys {
+            from_index: 0,
+            count: 3,
+            ..CliDeriveKeys::default()
+        };
+        let derived_keys = derive_keys.derive_keys(&master_seed("")).unwrap();
+        assert_eq!(
+            derived_keys
+                .iter()
+                .map(|(index, _, _, _)| *index)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        let key_pair_properties = crate::common::get_key_pair_properties_from_seed_phrase(
+            slip10::BIP32Path::from_str("m/44'/397'/0'").unwrap(),
+            MASTER_SEED_PHRASE.to_owned(),
+            "",
+            crate::common::KeyType::Ed25519,
+        )
+        .unwrap();
+        let (_, seed_phrase_hd_path, public_key_str, implicit_account_id) = &derived_keys[0];
+        assert_eq!(seed_phrase_hd_path.to_string(), "m/44'/397'/0'");
+        assert_eq!(public_key_str, &key_pair_properties.public_key_str);
+        assert_eq!(
+            implicit_account_id,
+            &key_pair_properties.implicit_account_id
+        );
+        assert_ne!(derived_keys[0].2, derived_keys[1].2);
+        assert_ne!(derived_keys[1].2, derived_keys[2].2);
+    }
+
+    #[test]
+    fn seed_phrase_passphrase_changes_the_derived_keys() {
+        let derive_keys = CliDeriveKeys {
+            count: 1,
+            ..CliDeriveKeys::default()
+        };
+        let without_passphrase = derive_keys.derive_keys(&master_seed("")).unwrap();
+        let with_passphrase = derive_keys.derive_keys(&master_seed("secret")).unwrap();
+        assert_ne!(without_passphrase[0].2, with_passphrase[0].2);
+    }
+
+    #[test]
+    fn secp256k1_keys_have_no_implicit_account() {
+        let derive_keys = CliDeriveKeys {
+            count: 2,
+            key_type: crate::common::KeyType::Secp256k1,
+            ..CliDeriveKeys::default()
+        };
+        let derived_keys = derive_keys.derive_keys(&master_seed("")).unwrap();
+        assert!(derived_keys
+            .iter()
+            .all(|(_, _, public_key_str, implicit_account_id)| {
+                public_key_str.starts_with("secp256k1:") && implicit_account_id.is_none()
+            }));
+    }
+
+    #[test]
+    fn too_large_index_range_is_rejected() {
+        let derive_keys = CliDeriveKeys {
+            from_index: u32::MAX,
+            count: 2,
+            ..CliDeriveKeys::default()
+        };
+        assert!(derive_keys.derive_keys(&master_seed("")).is_err());
+    }
+}
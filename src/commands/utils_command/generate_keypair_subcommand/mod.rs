@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 /// Generate a key pair of private and public keys (use it anywhere you need
-/// Ed25519 keys)
+/// Ed25519 or Secp256k1 keys)
 #[derive(Debug, Clone, clap::Clap)]
 pub struct CliGenerateKeypair {
     #[clap(long)]
@@ -10,8 +10,16 @@ pub struct CliGenerateKeypair {
     pub new_master_seed_phrase_words_count: usize,
     #[clap(long, default_value = "m/44'/397'/0'")]
     pub seed_phrase_hd_path: slip10::BIP32Path,
+    /// BIP-39 passphrase that protects the master seed phrase
+    #[clap(long)]
+    pub seed_phrase_passphrase: Option<String>,
+    #[clap(long, default_value = "ed25519")]
+    pub key_type: crate::common::KeyType,
     #[clap(long, default_value = "plaintext")]
     pub format: crate::common::OutputFormat,
+    /// Also save a printable paper wallet (HTML with QR codes) to this file
+    #[clap(long)]
+    pub paper_wallet: Option<std::path::PathBuf>,
 }
 
 impl Default for CliGenerateKeypair {
@@ -20,23 +28,49 @@ impl Default for CliGenerateKeypair {
             master_seed_phrase: None,
             new_master_seed_phrase_words_count: 12,
             seed_phrase_hd_path: slip10::BIP32Path::from_str("m/44'/397'/0'").unwrap(),
+            seed_phrase_passphrase: None,
+            key_type: crate::common::KeyType::Ed25519,
             format: crate::common::OutputFormat::Json,
+            paper_wallet: None,
         }
     }
 }
 
 impl CliGenerateKeypair {
     pub async fn process(self) -> crate::CliResult {
-        let key_pair_properties = crate::common::generate_keypair().await?;
+        let master_seed_phrase = match self.master_seed_phrase.as_deref() {
+            Some(master_seed_phrase) => master_seed_phrase.to_owned(),
+            None => bip39::Mnemonic::generate(self.new_master_seed_phrase_words_count)?
+                .word_iter()
+                .collect::<Vec<&str>>()
+                .join(" "),
+        };
+        let key_pair_properties = crate::common::get_key_pair_properties_from_seed_phrase(
+            self.seed_phrase_hd_path.clone(),
+            master_seed_phrase,
+            self.seed_phrase_passphrase.as_deref().unwrap_or_default(),
+            self.key_type,
+        )?;
+        if let Some(paper_wallet) = &self.paper_wallet {
+            std::fs::write(paper_wallet, paper_wallet_html(&key_pair_properties)?)?;
+            println!(
+                "The paper wallet is saved in a file {}",
+                paper_wallet.display()
+            );
+        }
         match self.format {
             crate::common::OutputFormat::Plaintext => {
                 println!(
-                    "Master Seed Phrase: {}\nSeed Phrase HD Path: {}\nImplicit Account ID: {}\nPublic Key: {}\nSECRET KEYPAIR: {}",
+                    "Master Seed Phrase: {}\nSeed Phrase HD Path: {}",
                     key_pair_properties.master_seed_phrase,
                     key_pair_properties.seed_phrase_hd_path.to_string(),
-                    key_pair_properties.implicit_account_id,
-                    key_pair_properties.public_key_str,
-                    key_pair_properties.secret_keypair_str,
+                );
+                if let Some(implicit_account_id) = &key_pair_properties.implicit_account_id {
+                    println!("Implicit Account ID: {}", implicit_account_id);
+                }
+                println!(
+                    "Public Key: {}\nSECRET KEYPAIR: {}",
+                    key_pair_properties.public_key_str, key_pair_properties.secret_keypair_str,
                 );
             }
             crate::common::OutputFormat::Json => {
@@ -56,3 +90,40 @@ impl CliGenerateKeypair {
         Ok(())
     }
 }
+
+/// Renders a printable page with the keys and their QR codes.
+fn paper_wallet_html(
+    key_pair_properties: &crate::common::KeyPairProperties,
+) -> color_eyre::eyre::Result<String> {
+    let mut entries = vec![];
+    if let Some(implicit_account_id) = &key_pair_properties.implicit_account_id {
+        entries.push(("Implicit Account ID", implicit_account_id.to_string()));
+    }
+    entries.push(("Public Key", key_pair_properties.public_key_str.clone()));
+    entries.push((
+        "Master Seed Phrase",
+        key_pair_properties.master_seed_phrase.clone(),
+    ));
+    entries.push((
+        "Seed Phrase HD Path",
+        key_pair_properties.seed_phrase_hd_path.to_string(),
+    ));
+    entries.push((
+        "SECRET KEYPAIR",
+        key_pair_properties.secret_keypair_str.clone(),
+    ));
+    let mut sections = String::new();
+    for (title, value) in entries {
+        sections.push_str(&format!(
+            "<section><h2>{}</h2><img src=\"data:image/svg+xml;base64,{}\" alt=\"{}\"><pre>{}</pre></section>\n",
+            title,
+            base64::encode(crate::common::qr_code_to_svg(&value)?),
+            title,
+            value
+        ));
+    }
+    Ok(format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>NEAR paper wallet</title>\n<style>\nbody {{ font-family: sans-serif; }}\nsection {{ page-break-inside: avoid; margin-bottom: 24px; }}\nimg {{ width: 192px; height: 192px; }}\npre {{ white-space: pre-wrap; word-break: break-all; }}\n</style>\n</head>\n<body>\n<h1>NEAR paper wallet</h1>\n<p>Keep this page secret: anyone with the seed phrase or the secret key controls the account.</p>\n{}</body>\n</html>\n",
+        sections
+    ))
+}
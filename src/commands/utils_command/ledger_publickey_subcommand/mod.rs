@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+use crate::common::TransactionSigner;
+
 /// Generate a key pair of private and public keys (use it anywhere you need
 /// Ed25519 keys)
 #[derive(Debug, Clone, clap::Clap)]
@@ -30,20 +32,18 @@ impl CliLedgerPublicKey {
     }
 
     pub async fn process(self) -> crate::CliResult {
-        println!(
-            "Please allow getting the PublicKey on Ledger device (HD Path: {})",
-            self.seed_phrase_hd_path.to_string(),
-        );
-        let public_key = near_ledger::get_public_key(self.seed_phrase_hd_path.clone())
-            .await
-            .map_err(|near_ledger_error| {
-                color_eyre::Report::msg(format!(
-                    "An error occurred while trying to get PublicKey from Ledger device: {:?}",
-                    near_ledger_error
-                ))
-            })?;
+        let public_key = crate::common::LedgerSigner::new(self.seed_phrase_hd_path.clone())?
+            .public_key()
+            .await?;
 
-        let implicit_account_id = hex::encode(&public_key);
+        let implicit_account_id = match &public_key {
+            near_crypto::PublicKey::ED25519(ed25519_public_key) => {
+                hex::encode(&ed25519_public_key.0)
+            }
+            near_crypto::PublicKey::SECP256K1(_) => {
+                unreachable!("Ledger device provides ed25519 keys only")
+            }
+        };
 
         match self.format {
             crate::common::OutputFormat::Plaintext => {
@@ -51,9 +51,7 @@ impl CliLedgerPublicKey {
                     "Seed Phrase HD Path: {}\nImplicit Account ID: {}\nPublic Key: {}",
                     self.seed_phrase_hd_path.to_string(),
                     implicit_account_id,
-                    near_crypto::PublicKey::ED25519(near_crypto::ED25519PublicKey::from(
-                        public_key.to_bytes(),
-                    )),
+                    public_key,
                 );
             }
             crate::common::OutputFormat::Json => {
@@ -62,11 +60,7 @@ impl CliLedgerPublicKey {
                     serde_json::to_string_pretty(&serde_json::json!({
                         "seed_phrase_hd_path": self.seed_phrase_hd_path.to_string(),
                         "account_id": implicit_account_id,
-                        "public_key": near_crypto::PublicKey::ED25519(
-                            near_crypto::ED25519PublicKey::from(
-                                public_key.to_bytes(),
-                            )
-                        ),
+                        "public_key": public_key,
                     }))
                     .unwrap()
                 );
@@ -1,12 +1,26 @@
 use dialoguer::{theme::ColorfulTheme, Select};
 use strum::{EnumDiscriminants, EnumIter, EnumMessage, IntoEnumIterator};
 
+mod amend_transaction_subcommand;
 mod combine_transaction_subcommand_with_signature;
+mod derive_keys_subcommand;
 pub mod generate_keypair_subcommand;
 mod ledger_publickey_subcommand;
+mod prepare_bundle_subcommand;
+mod qr_code_subcommand;
+mod send_parallel_subcommand;
 mod send_signed_transaction;
+mod sign_bundle_subcommand;
+mod sign_message_subcommand;
 mod sign_transaction_subcommand_with_secret_key;
 mod sign_transaction_with_ledger_subcommand;
+mod submit_bundle_subcommand;
+mod transaction_from_json_subcommand;
+mod transaction_info_subcommand;
+mod transaction_to_json_subcommand;
+mod vanity_implicit_account_subcommand;
+mod verify_signature_subcommand;
+mod verify_signed_transaction_subcommand;
 mod view_serialized_transaction;
 
 /// набор утилит-помощников
@@ -63,6 +77,10 @@ impl Utils {
 enum CliUtil {
     /// It generates a random key pair
     GenerateKeypair(self::generate_keypair_subcommand::CliGenerateKeypair),
+    /// Derive a range of account keys from one master seed phrase
+    DeriveKeys(self::derive_keys_subcommand::CliDeriveKeys),
+    /// Search for a key pair with a custom implicit account ID prefix or suffix
+    VanityImplicitAccount(self::vanity_implicit_account_subcommand::CliVanityImplicitAccount),
     /// Предоставьте данные для подписания данных с помощью private key
     SignTransactionPrivateKey(
         self::sign_transaction_subcommand_with_secret_key::CliSignTransactionPrivateKey,
@@ -81,6 +99,30 @@ enum CliUtil {
     LedgerPublicKey(self::ledger_publickey_subcommand::CliLedgerPublicKey),
     /// Send signed transaction
     SendSignedTransaction(self::send_signed_transaction::operation_mode::CliOperationMode),
+    /// Show a public key, a signature or a serialized transaction as a QR code
+    QrCode(self::qr_code_subcommand::CliQrCode),
+    /// Fetch the nonce and a recent block hash and save the transaction as a bundle to sign offline
+    PrepareBundle(self::prepare_bundle_subcommand::CliPrepareBundle),
+    /// Sign a transaction bundle (works offline)
+    SignBundle(self::sign_bundle_subcommand::CliSignBundle),
+    /// Verify a signed transaction bundle and send it
+    SubmitBundle(self::submit_bundle_subcommand::CliSubmitBundle),
+    /// Sign an off-chain message (NEP-413)
+    SignMessage(self::sign_message_subcommand::CliSignMessage),
+    /// Verify the signature of an off-chain message (NEP-413)
+    VerifySignature(self::verify_signature_subcommand::CliVerifySignature),
+    /// Check the signature of a signed transaction offline and show its hash
+    VerifySignedTransaction(self::verify_signed_transaction_subcommand::CliVerifySignedTransaction),
+    /// Print a serialized transaction (signed or not) as JSON
+    TransactionToJson(self::transaction_to_json_subcommand::CliTransactionToJson),
+    /// Build a serialized transaction from JSON
+    TransactionFromJson(self::transaction_from_json_subcommand::CliTransactionFromJson),
+    /// Change fields of a serialized transaction and re-sign it
+    AmendTransaction(self::amend_transaction_subcommand::CliAmendTransaction),
+    /// Show the hash, size, deposit, gas and expiry of a serialized transaction
+    TransactionInfo(self::transaction_info_subcommand::CliTransactionInfo),
+    /// Send many transactions in parallel through a pool of access keys
+    SendParallel(self::send_parallel_subcommand::CliSendParallel),
 }
 
 #[derive(Debug, Clone, EnumDiscriminants)]
@@ -88,6 +130,12 @@ enum CliUtil {
 pub enum Util {
     #[strum_discriminants(strum(message = "Generate a key pair"))]
     GenerateKeypair(self::generate_keypair_subcommand::CliGenerateKeypair),
+    #[strum_discriminants(strum(message = "Derive a range of account keys from a seed phrase"))]
+    DeriveKeys(self::derive_keys_subcommand::CliDeriveKeys),
+    #[strum_discriminants(strum(
+        message = "Search for a key pair with a custom implicit account ID prefix or suffix"
+    ))]
+    VanityImplicitAccount(self::vanity_implicit_account_subcommand::CliVanityImplicitAccount),
     #[strum_discriminants(strum(message = "Sign a transaction with private key"))]
     SignTransactionPrivateKey(
         self::sign_transaction_subcommand_with_secret_key::SignTransactionPrivateKey,
@@ -106,6 +154,44 @@ pub enum Util {
     LedgerPublicKey(self::ledger_publickey_subcommand::CliLedgerPublicKey),
     #[strum_discriminants(strum(message = "Send signed transaction"))]
     SendSignedTransaction(self::send_signed_transaction::operation_mode::OperationMode),
+    #[strum_discriminants(strum(
+        message = "Show a public key, a signature or a serialized transaction as a QR code"
+    ))]
+    QrCode(self::qr_code_subcommand::CliQrCode),
+    #[strum_discriminants(strum(
+        message = "Fetch the nonce and a recent block hash and save the transaction as a bundle to sign offline"
+    ))]
+    PrepareBundle(self::prepare_bundle_subcommand::PrepareBundle),
+    #[strum_discriminants(strum(message = "Sign a transaction bundle (works offline)"))]
+    SignBundle(self::sign_bundle_subcommand::CliSignBundle),
+    #[strum_discriminants(strum(message = "Verify a signed transaction bundle and send it"))]
+    SubmitBundle(self::submit_bundle_subcommand::CliSubmitBundle),
+    #[strum_discriminants(strum(message = "Sign an off-chain message (NEP-413)"))]
+    SignMessage(self::sign_message_subcommand::CliSignMessage),
+    #[strum_discriminants(strum(
+        message = "Verify the signature of an off-chain message (NEP-413)"
+    ))]
+    VerifySignature(self::verify_signature_subcommand::CliVerifySignature),
+    #[strum_discriminants(strum(message = "Check the signature of a signed transaction offline"))]
+    VerifySignedTransaction(self::verify_signed_transaction_subcommand::CliVerifySignedTransaction),
+    #[strum_discriminants(strum(
+        message = "Print a serialized transaction (signed or not) as JSON"
+    ))]
+    TransactionToJson(self::transaction_to_json_subcommand::CliTransactionToJson),
+    #[strum_discriminants(strum(message = "Build a serialized transaction from JSON"))]
+    TransactionFromJson(self::transaction_from_json_subcommand::CliTransactionFromJson),
+    #[strum_discriminants(strum(
+        message = "Change fields of a serialized transaction and re-sign it"
+    ))]
+    AmendTransaction(self::amend_transaction_subcommand::CliAmendTransaction),
+    #[strum_discriminants(strum(
+        message = "Show the hash, size, deposit, gas and expiry of a serialized transaction"
+    ))]
+    TransactionInfo(self::transaction_info_subcommand::CliTransactionInfo),
+    #[strum_discriminants(strum(
+        message = "Send many transactions in parallel through a pool of access keys"
+    ))]
+    SendParallel(self::send_parallel_subcommand::SendParallel),
 }
 
 impl CliUtil {
@@ -116,6 +202,16 @@ impl CliUtil {
                 args.push_front("generate-keypair".to_owned());
                 args
             }
+            Self::DeriveKeys(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("derive-keys".to_owned());
+                args
+            }
+            Self::VanityImplicitAccount(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("vanity-implicit-account".to_owned());
+                args
+            }
             Self::SignTransactionPrivateKey(subcommand) => {
                 let mut args = subcommand.to_cli_args();
                 args.push_front("sign-transaction-private-key".to_owned());
@@ -146,6 +242,66 @@ impl CliUtil {
                 args.push_front("send-signed-transaction".to_owned());
                 args
             }
+            Self::QrCode(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("qr-code".to_owned());
+                args
+            }
+            Self::PrepareBundle(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("prepare-bundle".to_owned());
+                args
+            }
+            Self::SignBundle(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("sign-bundle".to_owned());
+                args
+            }
+            Self::SubmitBundle(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("submit-bundle".to_owned());
+                args
+            }
+            Self::SignMessage(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("sign-message".to_owned());
+                args
+            }
+            Self::VerifySignature(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("verify-signature".to_owned());
+                args
+            }
+            Self::VerifySignedTransaction(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("verify-signed-transaction".to_owned());
+                args
+            }
+            Self::TransactionToJson(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("transaction-to-json".to_owned());
+                args
+            }
+            Self::TransactionFromJson(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("transaction-from-json".to_owned());
+                args
+            }
+            Self::AmendTransaction(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("amend-transaction".to_owned());
+                args
+            }
+            Self::TransactionInfo(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("transaction-info".to_owned());
+                args
+            }
+            Self::SendParallel(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("send-parallel".to_owned());
+                args
+            }
         }
     }
 }
@@ -154,6 +310,10 @@ impl From<Util> for CliUtil {
     fn from(util: Util) -> Self {
         match util {
             Util::GenerateKeypair(generate_keypair) => Self::GenerateKeypair(generate_keypair),
+            Util::DeriveKeys(derive_keys) => Self::DeriveKeys(derive_keys),
+            Util::VanityImplicitAccount(vanity_implicit_account) => {
+                Self::VanityImplicitAccount(vanity_implicit_account)
+            }
             Util::SignTransactionPrivateKey(sign_transaction_secret_key) => {
                 Self::SignTransactionPrivateKey(sign_transaction_secret_key.into())
             }
@@ -170,6 +330,24 @@ impl From<Util> for CliUtil {
             Util::SendSignedTransaction(operation_mode) => {
                 Self::SendSignedTransaction(operation_mode.into())
             }
+            Util::QrCode(qr_code) => Self::QrCode(qr_code),
+            Util::PrepareBundle(prepare_bundle) => Self::PrepareBundle(prepare_bundle.into()),
+            Util::SignBundle(sign_bundle) => Self::SignBundle(sign_bundle),
+            Util::SubmitBundle(submit_bundle) => Self::SubmitBundle(submit_bundle),
+            Util::SignMessage(sign_message) => Self::SignMessage(sign_message),
+            Util::VerifySignature(verify_signature) => Self::VerifySignature(verify_signature),
+            Util::VerifySignedTransaction(verify_signed_transaction) => {
+                Self::VerifySignedTransaction(verify_signed_transaction)
+            }
+            Util::TransactionToJson(transaction_to_json) => {
+                Self::TransactionToJson(transaction_to_json)
+            }
+            Util::TransactionFromJson(transaction_from_json) => {
+                Self::TransactionFromJson(transaction_from_json)
+            }
+            Util::AmendTransaction(amend_transaction) => Self::AmendTransaction(amend_transaction),
+            Util::TransactionInfo(transaction_info) => Self::TransactionInfo(transaction_info),
+            Util::SendParallel(send_parallel) => Self::SendParallel(send_parallel.into()),
         }
     }
 }
@@ -178,6 +356,10 @@ impl From<CliUtil> for Util {
     fn from(item: CliUtil) -> Self {
         match item {
             CliUtil::GenerateKeypair(generate_keypair) => Util::GenerateKeypair(generate_keypair),
+            CliUtil::DeriveKeys(derive_keys) => Util::DeriveKeys(derive_keys),
+            CliUtil::VanityImplicitAccount(vanity_implicit_account) => {
+                Util::VanityImplicitAccount(vanity_implicit_account)
+            }
             CliUtil::SignTransactionPrivateKey(cli_sign_transaction) => {
                 let sign_transaction =
                     self::sign_transaction_subcommand_with_secret_key::SignTransactionPrivateKey::from(cli_sign_transaction);
@@ -206,6 +388,30 @@ impl From<CliUtil> for Util {
             CliUtil::SendSignedTransaction(cli_operation_mode) => {
                 Util::SendSignedTransaction(cli_operation_mode.into())
             }
+            CliUtil::QrCode(qr_code) => Util::QrCode(qr_code),
+            CliUtil::PrepareBundle(cli_prepare_bundle) => {
+                Util::PrepareBundle(cli_prepare_bundle.into())
+            }
+            CliUtil::SignBundle(sign_bundle) => Util::SignBundle(sign_bundle),
+            CliUtil::SubmitBundle(submit_bundle) => Util::SubmitBundle(submit_bundle),
+            CliUtil::SignMessage(sign_message) => Util::SignMessage(sign_message),
+            CliUtil::VerifySignature(verify_signature) => Util::VerifySignature(verify_signature),
+            CliUtil::VerifySignedTransaction(verify_signed_transaction) => {
+                Util::VerifySignedTransaction(verify_signed_transaction)
+            }
+            CliUtil::TransactionToJson(transaction_to_json) => {
+                Util::TransactionToJson(transaction_to_json)
+            }
+            CliUtil::TransactionFromJson(transaction_from_json) => {
+                Util::TransactionFromJson(transaction_from_json)
+            }
+            CliUtil::AmendTransaction(amend_transaction) => {
+                Util::AmendTransaction(amend_transaction)
+            }
+            CliUtil::TransactionInfo(transaction_info) => Util::TransactionInfo(transaction_info),
+            CliUtil::SendParallel(cli_send_parallel) => {
+                Util::SendParallel(cli_send_parallel.into())
+            }
         }
     }
 }
@@ -228,6 +434,12 @@ impl Util {
             UtilDiscriminants::GenerateKeypair => CliUtil::GenerateKeypair(
                 self::generate_keypair_subcommand::CliGenerateKeypair::default(),
             ),
+            UtilDiscriminants::DeriveKeys => {
+                CliUtil::DeriveKeys(self::derive_keys_subcommand::CliDeriveKeys::default())
+            }
+            UtilDiscriminants::VanityImplicitAccount => CliUtil::VanityImplicitAccount(
+                self::vanity_implicit_account_subcommand::CliVanityImplicitAccount::default(),
+            ),
             UtilDiscriminants::SignTransactionPrivateKey => {
                 CliUtil::SignTransactionPrivateKey(Default::default())
             }
@@ -246,6 +458,22 @@ impl Util {
             UtilDiscriminants::SendSignedTransaction => {
                 CliUtil::SendSignedTransaction(Default::default())
             }
+            UtilDiscriminants::QrCode => CliUtil::QrCode(Default::default()),
+            UtilDiscriminants::PrepareBundle => CliUtil::PrepareBundle(Default::default()),
+            UtilDiscriminants::SignBundle => CliUtil::SignBundle(Default::default()),
+            UtilDiscriminants::SubmitBundle => CliUtil::SubmitBundle(Default::default()),
+            UtilDiscriminants::SignMessage => CliUtil::SignMessage(Default::default()),
+            UtilDiscriminants::VerifySignature => CliUtil::VerifySignature(Default::default()),
+            UtilDiscriminants::VerifySignedTransaction => {
+                CliUtil::VerifySignedTransaction(Default::default())
+            }
+            UtilDiscriminants::TransactionToJson => CliUtil::TransactionToJson(Default::default()),
+            UtilDiscriminants::TransactionFromJson => {
+                CliUtil::TransactionFromJson(Default::default())
+            }
+            UtilDiscriminants::AmendTransaction => CliUtil::AmendTransaction(Default::default()),
+            UtilDiscriminants::TransactionInfo => CliUtil::TransactionInfo(Default::default()),
+            UtilDiscriminants::SendParallel => CliUtil::SendParallel(Default::default()),
         };
         Self::from(cli_util)
     }
@@ -253,6 +481,10 @@ impl Util {
     pub async fn process(self) -> crate::CliResult {
         match self {
             Self::GenerateKeypair(generate_keypair) => generate_keypair.process().await,
+            Self::DeriveKeys(derive_keys) => derive_keys.process().await,
+            Self::VanityImplicitAccount(vanity_implicit_account) => {
+                vanity_implicit_account.process().await
+            }
             Self::SignTransactionPrivateKey(sign_transaction) => sign_transaction.process().await,
             Self::SignTransactionWithLedger(sign_transaction) => sign_transaction.process().await,
             Self::CombineTransactionSignature(combine_transaction) => {
@@ -263,6 +495,22 @@ impl Util {
             }
             Self::LedgerPublicKey(ledger_publickey) => ledger_publickey.process().await,
             Self::SendSignedTransaction(operation_mode) => operation_mode.process().await,
+            Self::QrCode(qr_code) => qr_code.process().await,
+            Self::PrepareBundle(prepare_bundle) => prepare_bundle.process().await,
+            Self::SignBundle(sign_bundle) => sign_bundle.process().await,
+            Self::SubmitBundle(submit_bundle) => submit_bundle.process().await,
+            Self::SignMessage(sign_message) => sign_message.process().await,
+            Self::VerifySignature(verify_signature) => verify_signature.process().await,
+            Self::VerifySignedTransaction(verify_signed_transaction) => {
+                verify_signed_transaction.process().await
+            }
+            Self::TransactionToJson(transaction_to_json) => transaction_to_json.process().await,
+            Self::TransactionFromJson(transaction_from_json) => {
+                transaction_from_json.process().await
+            }
+            Self::AmendTransaction(amend_transaction) => amend_transaction.process().await,
+            Self::TransactionInfo(transaction_info) => transaction_info.process().await,
+            Self::SendParallel(send_parallel) => send_parallel.process().await,
         }
     }
 }
@@ -0,0 +1,112 @@
+use dialoguer::Input;
+use near_primitives::borsh::BorshSerialize;
+
+pub mod operation_mode;
+
+/// Fetch the nonce and a recent block hash for an unsigned transaction and save it as a bundle
+/// to sign on an air-gapped machine
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliPrepareBundle {
+    #[clap(long)]
+    unsigned_transaction: Option<crate::common::TransactionAsBase64>,
+    /// The bundle file to create
+    #[clap(long)]
+    output: Option<std::path::PathBuf>,
+    #[clap(subcommand)]
+    mode: Option<self::operation_mode::CliMode>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PrepareBundle {
+    unsigned_transaction: near_primitives::transaction::Transaction,
+    output: std::path::PathBuf,
+    mode: self::operation_mode::Mode,
+}
+
+impl CliPrepareBundle {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = self
+            .mode
+            .as_ref()
+            .map(|subcommand| subcommand.to_cli_args())
+            .unwrap_or_default();
+        if let Some(output) = &self.output {
+            args.push_front(output.display().to_string());
+            args.push_front("--output".to_owned());
+        }
+        if let Some(unsigned_transaction) = &self.unsigned_transaction {
+            args.push_front(near_primitives::serialize::to_base64(
+                unsigned_transaction
+                    .inner
+                    .try_to_vec()
+                    .expect("Transaction is not expected to fail on serialization"),
+            ));
+            args.push_front("--unsigned-transaction".to_owned());
+        }
+        args
+    }
+}
+
+impl From<PrepareBundle> for CliPrepareBundle {
+    fn from(prepare_bundle: PrepareBundle) -> Self {
+        Self {
+            unsigned_transaction: Some(crate::common::TransactionAsBase64 {
+                inner: prepare_bundle.unsigned_transaction,
+            }),
+            output: Some(prepare_bundle.output),
+            mode: Some(prepare_bundle.mode.into()),
+        }
+    }
+}
+
+impl From<CliPrepareBundle> for PrepareBundle {
+    fn from(item: CliPrepareBundle) -> Self {
+        let unsigned_transaction = match item.unsigned_transaction {
+            Some(cli_unsigned_transaction) => cli_unsigned_transaction.inner,
+            None => PrepareBundle::input_unsigned_transaction(),
+        };
+        let output = match item.output {
+            Some(cli_output) => cli_output,
+            None => PrepareBundle::input_output(),
+        };
+        let mode = match item.mode {
+            Some(cli_mode) => self::operation_mode::Mode::from(cli_mode),
+            None => self::operation_mode::Mode::choose_mode(),
+        };
+        Self {
+            unsigned_transaction,
+            output,
+            mode,
+        }
+    }
+}
+
+impl PrepareBundle {
+    fn input_unsigned_transaction() -> near_primitives::transaction::Transaction {
+        let input: crate::common::TransactionAsBase64 = Input::new()
+            .with_prompt("Enter an unsigned transaction")
+            .interact_text()
+            .unwrap();
+        input.inner
+    }
+
+    fn input_output() -> std::path::PathBuf {
+        let output: String = Input::new()
+            .with_prompt("Where do you want to save the bundle?")
+            .with_initial_text("transaction-bundle.json")
+            .interact_text()
+            .unwrap();
+        output.into()
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        self.mode
+            .process(self.unsigned_transaction, self.output)
+            .await
+    }
+}
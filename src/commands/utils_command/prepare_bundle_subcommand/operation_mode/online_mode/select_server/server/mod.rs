@@ -0,0 +1,140 @@
+use std::str::FromStr;
+
+use dialoguer::Input;
+use near_primitives::borsh::BorshSerialize;
+
+/// предустановленный RPC-сервер
+#[derive(Debug, Default, Clone, clap::Clap)]
+pub struct CliServer {}
+
+/// данные для custom server
+#[derive(Debug, Default, Clone, clap::Clap)]
+pub struct CliCustomServer {
+    #[clap(long)]
+    pub url: Option<crate::common::AvailableRpcServerUrl>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Server {
+    pub connection_config: crate::common::ConnectionConfig,
+}
+
+impl CliCustomServer {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        if let Some(url) = &self.url {
+            args.push_front(url.to_string());
+            args.push_front("--url".to_string());
+        }
+        args
+    }
+}
+
+impl From<Server> for CliCustomServer {
+    fn from(server: Server) -> Self {
+        Self {
+            url: Some(
+                crate::common::AvailableRpcServerUrl::from_str(
+                    server.connection_config.rpc_url().as_str(),
+                )
+                .unwrap(),
+            ),
+        }
+    }
+}
+
+impl CliServer {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        std::collections::VecDeque::new()
+    }
+}
+
+impl From<Server> for CliServer {
+    fn from(_: Server) -> Self {
+        Self {}
+    }
+}
+
+impl CliServer {
+    pub fn into_server(self, connection_config: crate::common::ConnectionConfig) -> Server {
+        Server { connection_config }
+    }
+}
+
+impl CliCustomServer {
+    pub fn into_server(self) -> Server {
+        let url: crate::common::AvailableRpcServerUrl = match self.url {
+            Some(url) => url,
+            None => Input::new()
+                .with_prompt("What is the RPC endpoint?")
+                .interact_text()
+                .unwrap(),
+        };
+        Server {
+            connection_config: crate::common::ConnectionConfig::Custom { url: url.inner },
+        }
+    }
+}
+
+impl Server {
+    pub async fn process(
+        self,
+        unsigned_transaction: near_primitives::transaction::Transaction,
+        output: std::path::PathBuf,
+    ) -> crate::CliResult {
+        let online_signer_access_key_response =
+            near_jsonrpc_client::new_client(self.connection_config.rpc_url().as_str())
+                .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
+                    block_reference: near_primitives::types::Finality::Final.into(),
+                    request: near_primitives::views::QueryRequest::ViewAccessKey {
+                        account_id: unsigned_transaction.signer_id.clone(),
+                        public_key: unsigned_transaction.public_key.clone(),
+                    },
+                })
+                .await
+                .map_err(|err| {
+                    color_eyre::Report::msg(format!(
+                        "Failed to fetch public key information for nonce: {:?}",
+                        err
+                    ))
+                })?;
+        let current_nonce =
+            if let near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKey(
+                online_signer_access_key,
+            ) = online_signer_access_key_response.kind
+            {
+                online_signer_access_key.nonce
+            } else {
+                return Err(color_eyre::Report::msg("Error current_nonce"));
+            };
+        let unsigned_transaction = near_primitives::transaction::Transaction {
+            block_hash: online_signer_access_key_response.block_hash,
+            nonce: current_nonce + 1,
+            ..unsigned_transaction
+        };
+        let network = match &self.connection_config {
+            crate::common::ConnectionConfig::Testnet => "testnet",
+            crate::common::ConnectionConfig::Mainnet => "mainnet",
+            crate::common::ConnectionConfig::Betanet => "betanet",
+            crate::common::ConnectionConfig::Custom { url: _ } => "custom",
+        };
+        let bundle = crate::common::TransactionBundle {
+            version: crate::common::TransactionBundle::VERSION,
+            network: network.to_owned(),
+            rpc_url: self.connection_config.rpc_url(),
+            summary: crate::common::transaction_summary(&unsigned_transaction),
+            unsigned_transaction: near_primitives::serialize::to_base64(
+                unsigned_transaction
+                    .try_to_vec()
+                    .expect("Transaction is not expected to fail on serialization"),
+            ),
+            block_height: online_signer_access_key_response.block_height,
+            expires_after_block_height: online_signer_access_key_response.block_height
+                + crate::common::TRANSACTION_VALIDITY_PERIOD_BLOCKS,
+            signed_transaction: None,
+        };
+        println!();
+        bundle.print_summary();
+        bundle.write(&output)
+    }
+}
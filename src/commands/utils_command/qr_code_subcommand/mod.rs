@@ -0,0 +1,43 @@
+use dialoguer::Input;
+
+/// Show a public key, a signature or a serialized transaction as a QR code
+#[derive(Debug, Default, Clone, clap::Clap)]
+pub struct CliQrCode {
+    /// The data to encode, e.g. a base64 serialized transaction
+    pub payload: Option<String>,
+    /// Save the QR code to a .png or .svg file instead of showing it in the terminal
+    #[clap(long)]
+    pub output: Option<std::path::PathBuf>,
+}
+
+impl CliQrCode {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        if let Some(output) = &self.output {
+            args.push_front(output.display().to_string());
+            args.push_front("--output".to_owned());
+        }
+        if let Some(payload) = &self.payload {
+            args.push_front(payload.to_owned());
+        }
+        args
+    }
+
+    fn input_payload() -> String {
+        Input::new()
+            .with_prompt("Enter the data to show as a QR code")
+            .interact_text()
+            .unwrap()
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        let payload = match self.payload {
+            Some(payload) => payload,
+            None => Self::input_payload(),
+        };
+        match &self.output {
+            Some(output) => crate::common::save_qr_code(payload.trim(), output),
+            None => crate::common::print_qr_code(payload.trim()),
+        }
+    }
+}
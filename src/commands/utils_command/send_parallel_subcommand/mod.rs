@@ -0,0 +1,693 @@
+use std::str::FromStr;
+
+use dialoguer::Input;
+
+pub mod operation_mode;
+
+/// Send many transactions from one account in parallel: a pool of access keys is added to the
+/// account (and saved in the keychain), and the transactions are spread across the keys
+#[derive(Debug, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliSendParallel {
+    /// JSON Lines file with a transaction per line: {"receiver_id": "...", "actions": [...]}
+    /// (the actions use the `transaction-to-json` format)
+    #[clap(long)]
+    pub transactions: Option<std::path::PathBuf>,
+    #[clap(long)]
+    pub signer_account_id: Option<near_primitives::types::AccountId>,
+    /// Number of access keys in the pool
+    #[clap(long, default_value = "4")]
+    pub keys: usize,
+    /// The most transactions that are sent at the same time
+    #[clap(long, default_value = "8")]
+    pub max_in_flight: usize,
+    /// Permission of the pool keys: full_access, or function_call limited to the receiver of the transactions
+    #[clap(long, default_value = "function_call")]
+    pub permission: PoolKeyPermission,
+    /// Allowance of function call pool keys
+    #[clap(long)]
+    pub allowance: Option<crate::common::NearBalance>,
+    /// Save the results of the transactions to this JSON file
+    #[clap(long)]
+    pub report: Option<std::path::PathBuf>,
+    /// Add the pool keys without reviewing the transaction first (for scripts and CI)
+    #[clap(long)]
+    pub skip_review: bool,
+    #[clap(subcommand)]
+    pub mode: Option<self::operation_mode::CliMode>,
+}
+
+impl Default for CliSendParallel {
+    fn default() -> Self {
+        Self {
+            transactions: None,
+            signer_account_id: None,
+            keys: 4,
+            max_in_flight: 8,
+            permission: PoolKeyPermission::FunctionCall,
+            allowance: None,
+            report: None,
+            skip_review: false,
+            mode: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SendParallel {
+    transactions: std::path::PathBuf,
+    signer_account_id: near_primitives::types::AccountId,
+    keys: usize,
+    max_in_flight: usize,
+    permission: PoolKeyPermission,
+    allowance: Option<crate::common::NearBalance>,
+    report: Option<std::path::PathBuf>,
+    skip_review: bool,
+    mode: self::operation_mode::Mode,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    strum_macros::IntoStaticStr,
+    strum_macros::EnumString,
+    strum_macros::EnumVariantNames,
+    smart_default::SmartDefault,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum PoolKeyPermission {
+    FullAccess,
+    #[default]
+    FunctionCall,
+}
+
+impl std::fmt::Display for PoolKeyPermission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PoolKeyPermission::FullAccess => write!(f, "full_access"),
+            PoolKeyPermission::FunctionCall => write!(f, "function_call"),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct TransactionTemplate {
+    receiver_id: near_primitives::types::AccountId,
+    actions: Vec<crate::common::ActionJson>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct TransactionReport {
+    /// Line of the transactions file
+    line: usize,
+    receiver_id: near_primitives::types::AccountId,
+    public_key: String,
+    status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transaction_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl TransactionReport {
+    fn new(
+        line: usize,
+        transaction: &near_primitives::transaction::Transaction,
+        public_key: &near_crypto::PublicKey,
+        result: color_eyre::eyre::Result<Option<near_primitives::views::FinalExecutionOutcomeView>>,
+    ) -> Self {
+        let (status, transaction_hash, error) = match result {
+            Ok(Some(transaction_info)) => {
+                let transaction_hash = Some(transaction_info.transaction_outcome.id.to_string());
+                match transaction_info.status {
+                    near_primitives::views::FinalExecutionStatus::SuccessValue(_) => {
+                        ("success", transaction_hash, None)
+                    }
+                    near_primitives::views::FinalExecutionStatus::Failure(tx_execution_error) => (
+                        "failure",
+                        transaction_hash,
+                        Some(format!("{:?}", tx_execution_error)),
+                    ),
+                    near_primitives::views::FinalExecutionStatus::NotStarted
+                    | near_primitives::views::FinalExecutionStatus::Started => {
+                        ("pending", transaction_hash, None)
+                    }
+                }
+            }
+            Ok(None) => (
+                "error",
+                None,
+                Some("The transaction was not accepted by the node".to_owned()),
+            ),
+            Err(err) => ("error", None, Some(err.to_string())),
+        };
+        Self {
+            line,
+            receiver_id: transaction.receiver_id.clone(),
+            public_key: public_key.to_string(),
+            status: status.to_owned(),
+            transaction_hash,
+            error,
+        }
+    }
+}
+
+impl CliSendParallel {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = self
+            .mode
+            .as_ref()
+            .map(|subcommand| subcommand.to_cli_args())
+            .unwrap_or_default();
+        if self.skip_review {
+            args.push_front("--skip-review".to_owned());
+        }
+        if let Some(report) = &self.report {
+            args.push_front(report.display().to_string());
+            args.push_front("--report".to_owned());
+        }
+        if let Some(allowance) = &self.allowance {
+            args.push_front(allowance.to_string());
+            args.push_front("--allowance".to_owned());
+        }
+        args.push_front(self.permission.to_string());
+        args.push_front("--permission".to_owned());
+        args.push_front(self.max_in_flight.to_string());
+        args.push_front("--max-in-flight".to_owned());
+        args.push_front(self.keys.to_string());
+        args.push_front("--keys".to_owned());
+        if let Some(signer_account_id) = &self.signer_account_id {
+            args.push_front(signer_account_id.to_string());
+            args.push_front("--signer-account-id".to_owned());
+        }
+        if let Some(transactions) = &self.transactions {
+            args.push_front(transactions.display().to_string());
+            args.push_front("--transactions".to_owned());
+        }
+        args
+    }
+}
+
+impl From<SendParallel> for CliSendParallel {
+    fn from(send_parallel: SendParallel) -> Self {
+        Self {
+            transactions: Some(send_parallel.transactions),
+            signer_account_id: Some(send_parallel.signer_account_id),
+            keys: send_parallel.keys,
+            max_in_flight: send_parallel.max_in_flight,
+            permission: send_parallel.permission,
+            allowance: send_parallel.allowance,
+            report: send_parallel.report,
+            skip_review: send_parallel.skip_review,
+            mode: Some(send_parallel.mode.into()),
+        }
+    }
+}
+
+impl From<CliSendParallel> for SendParallel {
+    fn from(item: CliSendParallel) -> Self {
+        let signer_account_id = match item.signer_account_id {
+            Some(signer_account_id) => signer_account_id,
+            None => Input::new()
+                .with_prompt("What is the account ID of the sender?")
+                .interact_text()
+                .unwrap(),
+        };
+        let transactions = match item.transactions {
+            Some(transactions) => transactions,
+            None => Input::<String>::new()
+                .with_prompt("Enter the path to the transactions file (JSON Lines)")
+                .interact_text()
+                .unwrap()
+                .into(),
+        };
+        let allowance = match (item.permission, item.allowance) {
+            (PoolKeyPermission::FunctionCall, None) => Some(
+                Input::new()
+                    .with_prompt("What allowance do the function call pool keys get (e.g. 1 NEAR)?")
+                    .interact_text()
+                    .unwrap(),
+            ),
+            (_, allowance) => allowance,
+        };
+        let mode = match item.mode {
+            Some(cli_mode) => self::operation_mode::Mode::from(cli_mode),
+            None => self::operation_mode::Mode::choose_mode(),
+        };
+        Self {
+            transactions,
+            signer_account_id,
+            keys: item.keys,
+            max_in_flight: item.max_in_flight,
+            permission: item.permission,
+            allowance,
+            report: item.report,
+            skip_review: item.skip_review,
+            mode,
+        }
+    }
+}
+
+impl SendParallel {
+    fn read_transactions(
+        path: &std::path::Path,
+        signer_account_id: &near_primitives::types::AccountId,
+    ) -> color_eyre::eyre::Result<Vec<(usize, near_primitives::transaction::Transaction)>> {
+        let data = std::fs::read_to_string(path).map_err(|err| {
+            color_eyre::Report::msg(format!("Failed to read {}: {}", path.display(), err))
+        })?;
+        let mut transactions = vec![];
+        for (index, line) in data.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let template: TransactionTemplate = serde_json::from_str(line).map_err(|err| {
+                color_eyre::Report::msg(format!(
+                    "Failed to parse the transaction on line {}: {}",
+                    index + 1,
+                    err
+                ))
+            })?;
+            let actions = template
+                .actions
+                .iter()
+                .map(crate::common::ActionJson::to_action)
+                .collect::<color_eyre::eyre::Result<Vec<_>>>()
+                .map_err(|err| {
+                    color_eyre::Report::msg(format!(
+                        "Invalid action on line {}: {}",
+                        index + 1,
+                        err
+                    ))
+                })?;
+            transactions.push((
+                index + 1,
+                near_primitives::transaction::Transaction {
+                    signer_id: signer_account_id.clone(),
+                    public_key: near_crypto::PublicKey::empty(near_crypto::KeyType::ED25519),
+                    nonce: 0,
+                    receiver_id: template.receiver_id,
+                    block_hash: Default::default(),
+                    actions,
+                },
+            ));
+        }
+        if transactions.is_empty() {
+            return Err(color_eyre::Report::msg(format!(
+                "There are no transactions in {}",
+                path.display()
+            )));
+        }
+        Ok(transactions)
+    }
+
+    /// The permission the pool keys are added with; function call keys are limited to the
+    /// receiver of the transactions.
+    fn pool_key_permission(
+        &self,
+        transactions: &[(usize, near_primitives::transaction::Transaction)],
+    ) -> near_primitives::views::AccessKeyPermissionView {
+        match self.permission {
+            PoolKeyPermission::FullAccess => {
+                near_primitives::views::AccessKeyPermissionView::FullAccess
+            }
+            PoolKeyPermission::FunctionCall => {
+                near_primitives::views::AccessKeyPermissionView::FunctionCall {
+                    allowance: self
+                        .allowance
+                        .as_ref()
+                        .map(|allowance| allowance.to_yoctonear()),
+                    receiver_id: transactions[0].1.receiver_id.to_string(),
+                    method_names: vec![],
+                }
+            }
+        }
+    }
+
+    async fn prepare_key_pool(
+        &self,
+        connection_config: &crate::common::ConnectionConfig,
+        signer_account_id: &near_primitives::types::AccountId,
+        pool_key_permission: &near_primitives::views::AccessKeyPermissionView,
+    ) -> color_eyre::eyre::Result<Vec<crate::common::KeychainAccessKey>> {
+        let access_key_list_response =
+            near_jsonrpc_client::new_client(connection_config.rpc_url().as_str())
+                .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
+                    block_reference: near_primitives::types::Finality::Final.into(),
+                    request: near_primitives::views::QueryRequest::ViewAccessKeyList {
+                        account_id: signer_account_id.clone(),
+                    },
+                })
+                .await
+                .map_err(|err| {
+                    color_eyre::Report::msg(format!(
+                        "Failed to fetch query for view key list: {:?}",
+                        err
+                    ))
+                })?;
+        let access_keys = match access_key_list_response.kind {
+            near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKeyList(
+                access_key_list,
+            ) => access_key_list.keys,
+            _ => return Err(color_eyre::Report::msg("Error call result")),
+        };
+        let mut full_access_key = None;
+        let mut pool: Vec<crate::common::KeychainAccessKey> = vec![];
+        for (_, keychain_access_key) in crate::common::read_keychain_access_keys(
+            connection_config.dir_name(),
+            signer_account_id,
+        ) {
+            let permission = match access_keys
+                .iter()
+                .find(|access_key| access_key.public_key == keychain_access_key.public_key)
+            {
+                Some(access_key) => &access_key.access_key.permission,
+                None => continue,
+            };
+            if permission == &near_primitives::views::AccessKeyPermissionView::FullAccess
+                && full_access_key.is_none()
+            {
+                full_access_key = Some(keychain_access_key.clone());
+            }
+            if pool.len() < self.keys
+                && is_same_permission(permission, pool_key_permission)
+                && pool
+                    .iter()
+                    .all(|pool_key| pool_key.public_key != keychain_access_key.public_key)
+            {
+                pool.push(keychain_access_key);
+            }
+        }
+        if pool.len() == self.keys {
+            return Ok(pool);
+        }
+        let full_access_key = full_access_key.ok_or_else(|| {
+            color_eyre::Report::msg(format!(
+                "There is no full access key of <{}> in the keychain to add the pool keys with",
+                signer_account_id
+            ))
+        })?;
+        let mut actions = vec![];
+        for _ in pool.len()..self.keys {
+            let key_pair_properties =
+                crate::common::generate_keypair(crate::common::KeyType::Ed25519, "").await?;
+            crate::common::save_access_key_to_keychain(
+                Some(connection_config.clone()),
+                key_pair_properties.clone(),
+                &signer_account_id.to_string(),
+            )
+            .await?;
+            let public_key = near_crypto::PublicKey::from_str(&key_pair_properties.public_key_str)?;
+            actions.push(near_primitives::transaction::Action::AddKey(
+                near_primitives::transaction::AddKeyAction {
+                    public_key: public_key.clone(),
+                    access_key: near_primitives::account::AccessKey {
+                        nonce: 0,
+                        permission: match pool_key_permission {
+                            near_primitives::views::AccessKeyPermissionView::FullAccess => {
+                                near_primitives::account::AccessKeyPermission::FullAccess
+                            }
+                            near_primitives::views::AccessKeyPermissionView::FunctionCall {
+                                allowance,
+                                receiver_id,
+                                method_names,
+                            } => near_primitives::account::AccessKeyPermission::FunctionCall(
+                                near_primitives::account::FunctionCallPermission {
+                                    allowance: allowance.clone(),
+                                    receiver_id: receiver_id.to_string(),
+                                    method_names: method_names.clone(),
+                                },
+                            ),
+                        },
+                    },
+                },
+            ));
+            pool.push(crate::common::KeychainAccessKey {
+                account_id: signer_account_id.clone(),
+                public_key,
+                private_key: near_crypto::SecretKey::from_str(
+                    &key_pair_properties.secret_keypair_str,
+                )?,
+            });
+        }
+        println!("\nAdding {} access keys to the pool ...", actions.len());
+        let transaction_info =
+            crate::commands::construct_transaction_command::sign_transaction::sign_and_submit(
+                near_primitives::transaction::Transaction {
+                    signer_id: signer_account_id.clone(),
+                    public_key: full_access_key.public_key.clone(),
+                    nonce: 0,
+                    receiver_id: signer_account_id.clone(),
+                    block_hash: Default::default(),
+                    actions,
+                },
+                full_access_key.public_key.clone(),
+                Some(connection_config.clone()),
+                &crate::common::SecretKeySigner {
+                    secret_key: full_access_key.private_key,
+                },
+                crate::commands::construct_transaction_command::sign_transaction::SubmitOptions {
+                    submit: Some(
+                        crate::commands::construct_transaction_command::sign_transaction::Submit::Send,
+                    ),
+                    review: !self.skip_review,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        match transaction_info.map(|transaction_info| transaction_info.status) {
+            Some(near_primitives::views::FinalExecutionStatus::SuccessValue(_)) => Ok(pool),
+            _ => Err(color_eyre::Report::msg(
+                "Failed to add the access keys of the pool",
+            )),
+        }
+    }
+
+    /// Sends the transactions from `max_in_flight` threads; every thread takes a free key from the
+    /// pool and returns it once its transaction is done, so a key never signs two transactions
+    /// at the same time.
+    fn send_transactions(
+        &self,
+        connection_config: crate::common::ConnectionConfig,
+        pool: Vec<crate::common::KeychainAccessKey>,
+        transactions: Vec<(usize, near_primitives::transaction::Transaction)>,
+    ) -> Vec<TransactionReport> {
+        let workers_count = std::cmp::min(self.max_in_flight, transactions.len());
+        let queue = std::sync::Arc::new(std::sync::Mutex::new(
+            transactions
+                .into_iter()
+                .collect::<std::collections::VecDeque<_>>(),
+        ));
+        let key_queue = std::sync::Arc::new(KeyQueue::new(pool));
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let workers = (0..workers_count)
+            .map(|_| {
+                let queue = queue.clone();
+                let key_queue = key_queue.clone();
+                let reports = reports.clone();
+                let connection_config = connection_config.clone();
+                std::thread::spawn(move || {
+                    actix::System::new().block_on(async move {
+                        loop {
+                            let (line, transaction) = match queue.lock().unwrap().pop_front() {
+                                Some(job) => job,
+                                None => break,
+                            };
+                            let pool_key = key_queue.take();
+                            let result = crate::commands::construct_transaction_command::sign_transaction::sign_and_submit(
+                                transaction.clone(),
+                                pool_key.public_key.clone(),
+                                Some(connection_config.clone()),
+                                &crate::common::SecretKeySigner {
+                                    secret_key: pool_key.private_key.clone(),
+                                },
+                                crate::commands::construct_transaction_command::sign_transaction::SubmitOptions {
+                                    submit: Some(crate::commands::construct_transaction_command::sign_transaction::Submit::Send),
+                                    ..Default::default()
+                                },
+                            )
+                            .await;
+                            reports.lock().unwrap().push(TransactionReport::new(
+                                line,
+                                &transaction,
+                                &pool_key.public_key,
+                                result,
+                            ));
+                            key_queue.put_back(pool_key);
+                        }
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        for worker in workers {
+            if worker.join().is_err() {
+                println!("A sending thread has panicked");
+            }
+        }
+        let mut reports = std::mem::take(&mut *reports.lock().unwrap());
+        reports.sort_by_key(|report: &TransactionReport| report.line);
+        reports
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        if self.keys == 0 || self.max_in_flight == 0 {
+            return Err(color_eyre::Report::msg(
+                "--keys and --max-in-flight have to be greater than 0",
+            ));
+        }
+        let connection_config = self.mode.connection_config();
+        // The workers sign concurrently, so the policy cannot ask for a typed confirmation
+        crate::common::check_signing_policy_without_prompts(&connection_config)?;
+        let signer_account_id = self.signer_account_id.clone();
+        let transactions = Self::read_transactions(&self.transactions, &signer_account_id)?;
+        let pool_key_permission = self.pool_key_permission(&transactions);
+        for (line, transaction) in transactions.iter() {
+            crate::common::check_access_key_permission(&pool_key_permission, transaction, 0)
+                .map_err(|err| {
+                    color_eyre::Report::msg(format!(
+                        "The transaction on line {} cannot be signed with a {} pool key: {}",
+                        line, self.permission, err
+                    ))
+                })?;
+        }
+        let pool = self
+            .prepare_key_pool(&connection_config, &signer_account_id, &pool_key_permission)
+            .await?;
+        println!(
+            "\nSending {} transactions with {} access keys ({} at a time) ...",
+            transactions.len(),
+            pool.len(),
+            std::cmp::min(self.max_in_flight, transactions.len())
+        );
+        let reports = self.send_transactions(connection_config, pool, transactions);
+
+        println!("\nResults:");
+        for report in reports.iter() {
+            println!(
+                "line {:<6} {:<8} {:<46} {}",
+                report.line,
+                report.status,
+                report.transaction_hash.as_deref().unwrap_or("-"),
+                report.error.as_deref().unwrap_or("")
+            );
+        }
+        println!(
+            "\n{} of {} transactions succeeded",
+            reports
+                .iter()
+                .filter(|report| report.status == "success")
+                .count(),
+            reports.len()
+        );
+        if let Some(report_path) = &self.report {
+            std::fs::write(report_path, serde_json::to_string_pretty(&reports)?)?;
+            println!("The report is saved in a file {}", report_path.display());
+        }
+        Ok(())
+    }
+}
+
+/// The pool keys that are not signing a transaction at the moment.
+struct KeyQueue {
+    free_keys: std::sync::Mutex<std::collections::VecDeque<crate::common::KeychainAccessKey>>,
+    key_returned: std::sync::Condvar,
+}
+
+impl KeyQueue {
+    fn new(pool: Vec<crate::common::KeychainAccessKey>) -> Self {
+        Self {
+            free_keys: std::sync::Mutex::new(pool.into_iter().collect()),
+            key_returned: std::sync::Condvar::new(),
+        }
+    }
+
+    /// Takes a free key, waiting for one to be returned if all the keys are in use.
+    fn take(&self) -> crate::common::KeychainAccessKey {
+        let mut free_keys = self.free_keys.lock().unwrap();
+        loop {
+            match free_keys.pop_front() {
+                Some(key) => return key,
+                None => free_keys = self.key_returned.wait(free_keys).unwrap(),
+            }
+        }
+    }
+
+    fn put_back(&self, key: crate::common::KeychainAccessKey) {
+        self.free_keys.lock().unwrap().push_back(key);
+        self.key_returned.notify_one();
+    }
+}
+
+/// Pool keys are matched by their kind and receiver: the allowance of a function call key
+/// decreases with use.
+fn is_same_permission(
+    permission: &near_primitives::views::AccessKeyPermissionView,
+    pool_key_permission: &near_primitives::views::AccessKeyPermissionView,
+) -> bool {
+    match (permission, pool_key_permission) {
+        (
+            near_primitives::views::AccessKeyPermissionView::FullAccess,
+            near_primitives::views::AccessKeyPermissionView::FullAccess,
+        ) => true,
+        (
+            near_primitives::views::AccessKeyPermissionView::FunctionCall {
+                receiver_id,
+                method_names,
+                ..
+            },
+            near_primitives::views::AccessKeyPermissionView::FunctionCall {
+                receiver_id: pool_receiver_id,
+                method_names: pool_method_names,
+                ..
+            },
+        ) => receiver_id == pool_receiver_id && method_names == pool_method_names,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool_key(seed: &str) -> crate::common::KeychainAccessKey {
+        let private_key = near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, seed);
+        crate::common::KeychainAccessKey {
+            account_id: "alice.testnet".parse().unwrap(),
+            public_key: private_key.public_key(),
+            private_key,
+        }
+    }
+
+    #[test]
+    fn a_pool_key_is_used_by_one_thread_at_a_time() {
+        let key_queue = std::sync::Arc::new(KeyQueue::new(vec![pool_key("a"), pool_key("b")]));
+        let keys_in_use = std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashSet::<
+            near_crypto::PublicKey,
+        >::new()));
+        let workers = (0..8)
+            .map(|_| {
+                let key_queue = key_queue.clone();
+                let keys_in_use = keys_in_use.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..20 {
+                        let key = key_queue.take();
+                        assert!(keys_in_use.lock().unwrap().insert(key.public_key.clone()));
+                        std::thread::sleep(std::time::Duration::from_millis(1));
+                        assert!(keys_in_use.lock().unwrap().remove(&key.public_key));
+                        key_queue.put_back(key);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!(key_queue.free_keys.lock().unwrap().len(), 2);
+    }
+}
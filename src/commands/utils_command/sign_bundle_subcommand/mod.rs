@@ -0,0 +1,205 @@
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
+use near_primitives::borsh::BorshSerialize;
+use strum::{EnumDiscriminants, EnumIter, EnumMessage, IntoEnumIterator};
+
+use crate::common::TransactionSigner;
+
+/// Sign a transaction bundle prepared with `prepare-bundle` (works offline)
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliSignBundle {
+    /// The bundle file to sign
+    pub bundle: Option<std::path::PathBuf>,
+    /// Save the signed bundle to this file instead of updating the bundle in place
+    #[clap(long)]
+    pub output: Option<std::path::PathBuf>,
+    #[clap(subcommand)]
+    pub signer: Option<CliBundleSigner>,
+}
+
+#[derive(Debug, Clone, EnumDiscriminants, clap::Clap)]
+#[strum_discriminants(derive(EnumMessage, EnumIter))]
+pub enum CliBundleSigner {
+    /// Sign the bundle with a private key
+    #[strum_discriminants(strum(message = "Sign the bundle with a private key"))]
+    PrivateKey {
+        #[clap(long)]
+        signer_private_key: Option<near_crypto::SecretKey>,
+    },
+    /// Sign the bundle with a Ledger device
+    #[strum_discriminants(strum(message = "Sign the bundle with a Ledger device"))]
+    Ledger {
+        #[clap(long)]
+        seed_phrase_hd_path: Option<slip10::BIP32Path>,
+    },
+}
+
+impl CliSignBundle {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = self
+            .signer
+            .as_ref()
+            .map(|subcommand| subcommand.to_cli_args())
+            .unwrap_or_default();
+        if let Some(output) = &self.output {
+            args.push_front(output.display().to_string());
+            args.push_front("--output".to_owned());
+        }
+        if let Some(bundle) = &self.bundle {
+            args.push_front(bundle.display().to_string());
+        }
+        args
+    }
+
+    fn input_bundle() -> std::path::PathBuf {
+        let bundle: String = Input::new()
+            .with_prompt("Enter the path to the bundle")
+            .with_initial_text("transaction-bundle.json")
+            .interact_text()
+            .unwrap();
+        bundle.into()
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        let bundle_path = match self.bundle {
+            Some(bundle) => bundle,
+            None => Self::input_bundle(),
+        };
+        let mut bundle = crate::common::TransactionBundle::read(&bundle_path)?;
+        if bundle.signed_transaction.is_some() {
+            return Err(color_eyre::Report::msg(format!(
+                "The bundle {} is already signed",
+                bundle_path.display()
+            )));
+        }
+        let unsigned_transaction = bundle.unsigned_transaction()?;
+        // The summary is produced by the online machine, so it is rebuilt from the transaction itself
+        if bundle.summary != crate::common::transaction_summary(&unsigned_transaction) {
+            return Err(color_eyre::Report::msg(
+                "The summary of the bundle does not match its transaction",
+            ));
+        }
+        println!("\nGoing to sign the transaction:");
+        bundle.print_summary();
+        crate::common::enforce_signing_policy(&unsigned_transaction, &bundle.connection_config())?;
+        let signer = match self.signer {
+            Some(signer) => signer,
+            None => CliBundleSigner::choose_signer(),
+        };
+        let signature = signer.sign(&unsigned_transaction).await?;
+        if !signature.verify(
+            unsigned_transaction.get_hash_and_size().0.as_ref(),
+            &unsigned_transaction.public_key,
+        ) {
+            return Err(color_eyre::Report::msg(format!(
+                "The signature does not match the public key {} of the transaction",
+                unsigned_transaction.public_key
+            )));
+        }
+        let signed_transaction =
+            near_primitives::transaction::SignedTransaction::new(signature, unsigned_transaction);
+        bundle.signed_transaction = Some(near_primitives::serialize::to_base64(
+            signed_transaction
+                .try_to_vec()
+                .expect("Signed transaction is not expected to fail on serialization"),
+        ));
+        bundle.write(self.output.as_ref().unwrap_or(&bundle_path))
+    }
+}
+
+impl CliBundleSigner {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        match self {
+            Self::PrivateKey { signer_private_key } => {
+                if let Some(signer_private_key) = signer_private_key {
+                    args.push_front(signer_private_key.to_string());
+                    args.push_front("--signer-private-key".to_owned());
+                }
+                args.push_front("private-key".to_owned());
+            }
+            Self::Ledger {
+                seed_phrase_hd_path,
+            } => {
+                if let Some(seed_phrase_hd_path) = seed_phrase_hd_path {
+                    args.push_front(seed_phrase_hd_path.to_string());
+                    args.push_front("--seed-phrase-hd-path".to_owned());
+                }
+                args.push_front("ledger".to_owned());
+            }
+        }
+        args
+    }
+
+    fn choose_signer() -> Self {
+        println!();
+        let variants = CliBundleSignerDiscriminants::iter().collect::<Vec<_>>();
+        let signers = variants
+            .iter()
+            .map(|p| p.get_message().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        let selected_signer = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("How do you want to sign the bundle?")
+            .items(&signers)
+            .default(0)
+            .interact()
+            .unwrap();
+        match variants[selected_signer] {
+            CliBundleSignerDiscriminants::PrivateKey => Self::PrivateKey {
+                signer_private_key: None,
+            },
+            CliBundleSignerDiscriminants::Ledger => Self::Ledger {
+                seed_phrase_hd_path: None,
+            },
+        }
+    }
+
+    async fn sign(
+        self,
+        unsigned_transaction: &near_primitives::transaction::Transaction,
+    ) -> color_eyre::eyre::Result<near_crypto::Signature> {
+        match self {
+            Self::PrivateKey { signer_private_key } => {
+                let signer_private_key: near_crypto::SecretKey = match signer_private_key {
+                    Some(signer_private_key) => signer_private_key,
+                    None => Input::new()
+                        .with_prompt("Enter the private key")
+                        .interact_text()
+                        .unwrap(),
+                };
+                if !Confirm::new()
+                    .with_prompt("Do you want to sign this transaction?")
+                    .interact()?
+                {
+                    return Err(color_eyre::Report::msg("The signing is cancelled"));
+                }
+                crate::common::SecretKeySigner {
+                    secret_key: signer_private_key,
+                }
+                .sign(unsigned_transaction)
+                .await
+            }
+            Self::Ledger {
+                seed_phrase_hd_path,
+            } => {
+                let seed_phrase_hd_path: slip10::BIP32Path = match seed_phrase_hd_path {
+                    Some(seed_phrase_hd_path) => seed_phrase_hd_path,
+                    None => Input::new()
+                        .with_prompt(
+                            "Enter seed phrase HD Path (if you not sure leave blank for default)",
+                        )
+                        .with_initial_text("44'/397'/0'/0'/1'")
+                        .interact_text()
+                        .unwrap(),
+                };
+                crate::common::LedgerSigner::new(seed_phrase_hd_path)?
+                    .sign(unsigned_transaction)
+                    .await
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use dialoguer::{theme::ColorfulTheme, Input, Select};
+use strum::{EnumDiscriminants, EnumIter, EnumMessage, IntoEnumIterator};
+
+use crate::common::TransactionSigner;
+
+/// Sign an off-chain message (NEP-413), e.g. to authenticate with a backend
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliSignMessage {
+    /// The account the message is signed on behalf of
+    #[clap(long)]
+    pub account_id: Option<near_primitives::types::AccountId>,
+    #[clap(long)]
+    pub message: Option<String>,
+    /// The recipient of the message, e.g. "example.app" or "alice.near"
+    #[clap(long)]
+    pub recipient: Option<String>,
+    /// 32 bytes encoded in base64 (a random nonce is used if it is not set)
+    #[clap(long)]
+    pub nonce: Option<String>,
+    #[clap(long)]
+    pub callback_url: Option<String>,
+    #[clap(subcommand)]
+    pub signer: Option<CliMessageSigner>,
+}
+
+#[derive(Debug, Clone, EnumDiscriminants, clap::Clap)]
+#[strum_discriminants(derive(EnumMessage, EnumIter))]
+pub enum CliMessageSigner {
+    /// Sign the message with a private key
+    #[strum_discriminants(strum(message = "Sign the message with a private key"))]
+    PrivateKey {
+        #[clap(long)]
+        signer_private_key: Option<near_crypto::SecretKey>,
+    },
+    /// Sign the message with an access key from the keychain
+    #[strum_discriminants(strum(
+        message = "Sign the message with an access key from the keychain"
+    ))]
+    Keychain {
+        /// The network of the keychain: testnet, mainnet, betanet or the URL of a custom RPC server
+        #[clap(long, default_value = "testnet")]
+        network: String,
+        /// The access key to sign with (the key of the account file is used if it is not set)
+        #[clap(long)]
+        public_key: Option<near_crypto::PublicKey>,
+    },
+}
+
+impl CliSignMessage {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = self
+            .signer
+            .as_ref()
+            .map(|subcommand| subcommand.to_cli_args())
+            .unwrap_or_default();
+        if let Some(callback_url) = &self.callback_url {
+            args.push_front(callback_url.to_owned());
+            args.push_front("--callback-url".to_owned());
+        }
+        if let Some(nonce) = &self.nonce {
+            args.push_front(nonce.to_owned());
+            args.push_front("--nonce".to_owned());
+        }
+        if let Some(recipient) = &self.recipient {
+            args.push_front(recipient.to_owned());
+            args.push_front("--recipient".to_owned());
+        }
+        if let Some(message) = &self.message {
+            args.push_front(message.to_owned());
+            args.push_front("--message".to_owned());
+        }
+        if let Some(account_id) = &self.account_id {
+            args.push_front(account_id.to_string());
+            args.push_front("--account-id".to_owned());
+        }
+        args
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        let account_id: near_primitives::types::AccountId = match self.account_id {
+            Some(account_id) => account_id,
+            None => Input::new()
+                .with_prompt("What account do you sign the message for?")
+                .interact_text()
+                .unwrap(),
+        };
+        let message = match self.message {
+            Some(message) => message,
+            None => Input::new()
+                .with_prompt("Enter the message to sign")
+                .interact_text()
+                .unwrap(),
+        };
+        let recipient = match self.recipient {
+            Some(recipient) => recipient,
+            None => Input::new()
+                .with_prompt("Who is the recipient of the message?")
+                .interact_text()
+                .unwrap(),
+        };
+        let nonce = match self.nonce {
+            Some(nonce) => parse_nonce(&nonce)?,
+            None => {
+                let mut nonce = [0u8; 32];
+                rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut nonce);
+                nonce
+            }
+        };
+        let payload = crate::common::Nep413Payload {
+            message,
+            nonce,
+            recipient,
+            callback_url: self.callback_url,
+        };
+        let signer = match self.signer {
+            Some(signer) => signer,
+            None => CliMessageSigner::choose_signer(),
+        }
+        .into_signer(&account_id)?;
+        let public_key = signer.public_key().await?;
+        if public_key.key_type() != near_crypto::KeyType::ED25519 {
+            return Err(color_eyre::Report::msg(
+                "Only ed25519 keys can be used to sign NEP-413 messages",
+            ));
+        }
+        let signature = signer.sign_message(&payload).await?;
+        if !payload.verify(&signature, &public_key) {
+            return Err(color_eyre::Report::msg(format!(
+                "The signature does not match the public key {}",
+                public_key
+            )));
+        }
+        let signature = match &signature {
+            near_crypto::Signature::ED25519(signature) => base64::encode(&signature.to_bytes()[..]),
+            near_crypto::Signature::SECP256K1(_) => {
+                unreachable!("The public key of the signer is checked to be ed25519")
+            }
+        };
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "accountId": account_id,
+                "publicKey": public_key,
+                "signature": signature,
+                "message": payload.message,
+                "recipient": payload.recipient,
+                "nonce": base64::encode(&payload.nonce[..]),
+                "callbackUrl": payload.callback_url,
+            }))
+            .unwrap()
+        );
+        Ok(())
+    }
+}
+
+/// Parses a NEP-413 nonce: 32 bytes encoded in base64.
+pub fn parse_nonce(nonce: &str) -> color_eyre::eyre::Result<[u8; 32]> {
+    let bytes = base64::decode(nonce)
+        .map_err(|err| color_eyre::Report::msg(format!("The nonce is not base64: {}", err)))?;
+    if bytes.len() != 32 {
+        return Err(color_eyre::Report::msg(format!(
+            "The nonce must be 32 bytes long, got {} bytes",
+            bytes.len()
+        )));
+    }
+    let mut nonce = [0u8; 32];
+    nonce.copy_from_slice(&bytes);
+    Ok(nonce)
+}
+
+impl CliMessageSigner {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        match self {
+            Self::PrivateKey { signer_private_key } => {
+                if let Some(signer_private_key) = signer_private_key {
+                    args.push_front(signer_private_key.to_string());
+                    args.push_front("--signer-private-key".to_owned());
+                }
+                args.push_front("private-key".to_owned());
+            }
+            Self::Keychain {
+                network,
+                public_key,
+            } => {
+                if let Some(public_key) = public_key {
+                    args.push_front(public_key.to_string());
+                    args.push_front("--public-key".to_owned());
+                }
+                args.push_front(network.to_owned());
+                args.push_front("--network".to_owned());
+                args.push_front("keychain".to_owned());
+            }
+        }
+        args
+    }
+
+    fn choose_signer() -> Self {
+        println!();
+        let variants = CliMessageSignerDiscriminants::iter().collect::<Vec<_>>();
+        let signers = variants
+            .iter()
+            .map(|p| p.get_message().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        let selected_signer = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("How do you want to sign the message?")
+            .items(&signers)
+            .default(0)
+            .interact()
+            .unwrap();
+        match variants[selected_signer] {
+            CliMessageSignerDiscriminants::PrivateKey => Self::PrivateKey {
+                signer_private_key: None,
+            },
+            CliMessageSignerDiscriminants::Keychain => Self::Keychain {
+                network: "testnet".to_owned(),
+                public_key: None,
+            },
+        }
+    }
+
+    fn into_signer(
+        self,
+        account_id: &near_primitives::types::AccountId,
+    ) -> color_eyre::eyre::Result<Box<dyn TransactionSigner>> {
+        Ok(match self {
+            Self::PrivateKey { signer_private_key } => {
+                let secret_key: near_crypto::SecretKey = match signer_private_key {
+                    Some(signer_private_key) => signer_private_key,
+                    None => Input::new()
+                        .with_prompt("Enter the private key")
+                        .interact_text()
+                        .unwrap(),
+                };
+                Box::new(crate::common::SecretKeySigner { secret_key })
+            }
+            Self::Keychain {
+                network,
+                public_key,
+            } => {
+                let connection_config =
+                    crate::common::ConnectionConfig::from_network_name(&network)?;
+                let dir_name = connection_config.dir_name();
+                let public_key = match public_key {
+                    Some(public_key) => public_key,
+                    None => crate::common::read_keychain_access_keys(dir_name, account_id)
+                        .into_iter()
+                        .map(|(_, keychain_access_key)| keychain_access_key.public_key)
+                        .next()
+                        .ok_or_else(|| {
+                            color_eyre::Report::msg(format!(
+                                "There are no access keys for <{}> in the {} keychain",
+                                account_id, network
+                            ))
+                        })?,
+                };
+                Box::new(crate::common::KeychainSigner {
+                    dir_name: dir_name.to_owned(),
+                    account_id: account_id.clone(),
+                    public_key,
+                })
+            }
+        })
+    }
+}
@@ -1,6 +1,8 @@
 use dialoguer::Input;
 use near_primitives::borsh::BorshSerialize;
 
+use crate::common::TransactionSigner;
+
 /// утилита, позволяющая подписать транзакцию личным ключом
 #[derive(Debug, Default, Clone, clap::Clap)]
 pub struct CliSignTransactionPrivateKey {
@@ -8,17 +10,25 @@ pub struct CliSignTransactionPrivateKey {
     signer_private_key: Option<near_crypto::SecretKey>,
     #[clap(long)]
     unsigned_transaction: Option<crate::common::TransactionAsBase64>,
+    /// The network whose signing policy applies (testnet, mainnet or betanet)
+    #[clap(long)]
+    policy_network: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct SignTransactionPrivateKey {
     pub signer_private_key: near_crypto::SecretKey,
     pub unsigned_transaction: near_primitives::transaction::Transaction,
+    pub policy_network: String,
 }
 
 impl CliSignTransactionPrivateKey {
     pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
         let mut args = std::collections::VecDeque::new();
+        if let Some(policy_network) = &self.policy_network {
+            args.push_front(policy_network.to_owned());
+            args.push_front("--policy-network".to_string());
+        }
         if let Some(unsigned_transaction) = &self.unsigned_transaction {
             let unsigned_transaction_serialized_to_base64 = near_primitives::serialize::to_base64(
                 unsigned_transaction
@@ -44,6 +54,7 @@ impl From<SignTransactionPrivateKey> for CliSignTransactionPrivateKey {
             unsigned_transaction: Some(crate::common::TransactionAsBase64 {
                 inner: sign_transacrion_private_key.unsigned_transaction,
             }),
+            policy_network: Some(sign_transacrion_private_key.policy_network),
         }
     }
 }
@@ -59,9 +70,14 @@ impl From<CliSignTransactionPrivateKey> for SignTransactionPrivateKey {
                 Some(cli_unsigned_transaction) => cli_unsigned_transaction.inner,
                 None => SignTransactionPrivateKey::input_unsigned_transaction(),
             };
+        let policy_network = match item.policy_network {
+            Some(policy_network) => policy_network,
+            None => crate::common::input_signing_policy_network(),
+        };
         SignTransactionPrivateKey {
             signer_private_key,
             unsigned_transaction,
+            policy_network,
         }
     }
 }
@@ -83,9 +99,15 @@ impl SignTransactionPrivateKey {
     }
 
     pub async fn process(self) -> crate::CliResult {
-        let signature = self
-            .signer_private_key
-            .sign(&self.unsigned_transaction.get_hash_and_size().0.as_ref());
+        crate::common::enforce_signing_policy(
+            &self.unsigned_transaction,
+            &crate::common::ConnectionConfig::from_network_name(&self.policy_network)?,
+        )?;
+        let signature = crate::common::SecretKeySigner {
+            secret_key: self.signer_private_key,
+        }
+        .sign(&self.unsigned_transaction)
+        .await?;
         let signed_transaction = near_primitives::transaction::SignedTransaction::new(
             signature,
             self.unsigned_transaction,
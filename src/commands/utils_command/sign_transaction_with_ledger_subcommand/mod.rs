@@ -1,6 +1,8 @@
 use dialoguer::Input;
 use near_primitives::borsh::BorshSerialize;
 
+use crate::common::TransactionSigner;
+
 /// Utility to sign transaction on Ledger
 #[derive(Debug, Default, Clone, clap::Clap)]
 pub struct CliSignTransactionWithLedger {
@@ -8,17 +10,25 @@ pub struct CliSignTransactionWithLedger {
     seed_phrase_hd_path: Option<slip10::BIP32Path>,
     #[clap(long)]
     unsigned_transaction: Option<crate::common::TransactionAsBase64>,
+    /// The network whose signing policy applies (testnet, mainnet or betanet)
+    #[clap(long)]
+    policy_network: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct SignTransactionWithLedger {
     pub seed_phrase_hd_path: slip10::BIP32Path,
     pub unsigned_transaction: near_primitives::transaction::Transaction,
+    pub policy_network: String,
 }
 
 impl CliSignTransactionWithLedger {
     pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
         let mut args = std::collections::VecDeque::new();
+        if let Some(policy_network) = &self.policy_network {
+            args.push_front(policy_network.to_owned());
+            args.push_front("--policy-network".to_string());
+        }
         if let Some(unsigned_transaction) = &self.unsigned_transaction {
             let unsigned_transaction_serialized_to_base64 = near_primitives::serialize::to_base64(
                 unsigned_transaction
@@ -44,6 +54,7 @@ impl From<SignTransactionWithLedger> for CliSignTransactionWithLedger {
             unsigned_transaction: Some(crate::common::TransactionAsBase64 {
                 inner: sign_transaction_with_ledger.unsigned_transaction,
             }),
+            policy_network: Some(sign_transaction_with_ledger.policy_network),
         }
     }
 }
@@ -59,9 +70,14 @@ impl From<CliSignTransactionWithLedger> for SignTransactionWithLedger {
                 Some(cli_unsigned_transaction) => cli_unsigned_transaction.inner,
                 None => SignTransactionWithLedger::input_unsigned_transaction(),
             };
+        let policy_network = match item.policy_network {
+            Some(policy_network) => policy_network,
+            None => crate::common::input_signing_policy_network(),
+        };
         SignTransactionWithLedger {
             seed_phrase_hd_path,
             unsigned_transaction,
+            policy_network,
         }
     }
 }
@@ -86,29 +102,13 @@ impl SignTransactionWithLedger {
     pub async fn process(self) -> crate::CliResult {
         println!("\nGoing to sign transaction:");
         crate::common::print_transaction(self.unsigned_transaction.clone());
-        println!(
-            "Please confirm transaction signing on Ledger Device (HD Path {})",
-            self.seed_phrase_hd_path.to_string()
-        );
-        let signature = match near_ledger::sign_transaction(
-            self.unsigned_transaction
-                .try_to_vec()
-                .expect("Transaction is not expected to fail on serialization"),
-            self.seed_phrase_hd_path,
-        )
-        .await
-        {
-            Ok(signature) => {
-                near_crypto::Signature::from_parts(near_crypto::KeyType::ED25519, &signature)
-                    .expect("Signature is not expected to fail on deserialization")
-            }
-            Err(near_ledger_error) => {
-                return Err(color_eyre::Report::msg(format!(
-                    "Error occurred while signing the transaction: {:?}",
-                    near_ledger_error
-                )));
-            }
-        };
+        crate::common::enforce_signing_policy(
+            &self.unsigned_transaction,
+            &crate::common::ConnectionConfig::from_network_name(&self.policy_network)?,
+        )?;
+        let signature = crate::common::LedgerSigner::new(self.seed_phrase_hd_path)?
+            .sign(&self.unsigned_transaction)
+            .await?;
 
         let signed_transaction = near_primitives::transaction::SignedTransaction::new(
             signature,
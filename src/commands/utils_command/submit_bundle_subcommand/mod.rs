@@ -0,0 +1,116 @@
+use dialoguer::Input;
+use near_primitives::borsh::BorshDeserialize;
+
+/// Verify a bundle signed with `sign-bundle` and send it to the network it was prepared for
+#[derive(Debug, Default, Clone, clap::Clap)]
+pub struct CliSubmitBundle {
+    /// The signed bundle file
+    pub bundle: Option<std::path::PathBuf>,
+    /// Send the transaction even when the pre-flight checks predict that it fails
+    #[clap(long)]
+    pub ignore_preflight_errors: bool,
+}
+
+impl CliSubmitBundle {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        if self.ignore_preflight_errors {
+            args.push_front("--ignore-preflight-errors".to_owned());
+        }
+        if let Some(bundle) = &self.bundle {
+            args.push_front(bundle.display().to_string());
+        }
+        args
+    }
+
+    fn input_bundle() -> std::path::PathBuf {
+        let bundle: String = Input::new()
+            .with_prompt("Enter the path to the signed bundle")
+            .with_initial_text("transaction-bundle.json")
+            .interact_text()
+            .unwrap();
+        bundle.into()
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        let bundle_path = match self.bundle {
+            Some(bundle) => bundle,
+            None => Self::input_bundle(),
+        };
+        let bundle = crate::common::TransactionBundle::read(&bundle_path)?;
+        let unsigned_transaction = bundle.unsigned_transaction()?;
+        let serialize_to_base64 = bundle.signed_transaction.clone().ok_or_else(|| {
+            color_eyre::Report::msg(format!(
+                "The bundle {} is not signed yet, sign it with `sign-bundle`",
+                bundle_path.display()
+            ))
+        })?;
+        let signed_transaction = near_primitives::transaction::SignedTransaction::try_from_slice(
+            &near_primitives::serialize::from_base64(&serialize_to_base64).map_err(|err| {
+                color_eyre::Report::msg(format!(
+                    "base64 signed transaction sequence is invalid: {}",
+                    err
+                ))
+            })?,
+        )
+        .map_err(|err| {
+            color_eyre::Report::msg(format!("signed transaction could not be parsed: {}", err))
+        })?;
+        let transaction_hash = unsigned_transaction.get_hash_and_size().0;
+        if signed_transaction.get_hash() != transaction_hash {
+            return Err(color_eyre::Report::msg(
+                "The signed transaction differs from the unsigned transaction of the bundle",
+            ));
+        }
+        if !signed_transaction.signature.verify(
+            transaction_hash.as_ref(),
+            &signed_transaction.transaction.public_key,
+        ) {
+            return Err(color_eyre::Report::msg(format!(
+                "The signature does not match the public key {} of the transaction",
+                signed_transaction.transaction.public_key
+            )));
+        }
+
+        let connection_config = bundle.connection_config();
+        let json_rcp_client = near_jsonrpc_client::new_client(connection_config.rpc_url().as_str());
+        let status = json_rcp_client.status().await.map_err(|err| {
+            color_eyre::Report::msg(format!("Failed to fetch the network status: {:?}", err))
+        })?;
+        if status.sync_info.latest_block_height > bundle.expires_after_block_height {
+            return Err(color_eyre::Report::msg(format!(
+                "The bundle has expired at block #{} (the latest block is #{}), prepare it again",
+                bundle.expires_after_block_height, status.sync_info.latest_block_height
+            )));
+        }
+
+        bundle.print_summary();
+        // The transaction is signed offline, so a used nonce cannot be replaced by re-signing it
+        let transaction_info =
+            match crate::commands::construct_transaction_command::sign_transaction::Submit::Send
+                .process_online(
+                    connection_config.clone(),
+                    signed_transaction,
+                    serialize_to_base64,
+                    self.ignore_preflight_errors,
+                )
+                .await
+            {
+                Ok(Some(transaction_info)) => transaction_info,
+                Ok(None) => {
+                    return Err(color_eyre::Report::msg(
+                        "The transaction was not accepted by the node",
+                    ))
+                }
+                Err(err) if err.is::<crate::common::InvalidNonceError>() => {
+                    return Err(color_eyre::Report::msg(format!(
+                        "{}. The nonce of the bundle is used already, prepare the bundle again",
+                        err
+                    )))
+                }
+                Err(err) => return Err(err),
+            };
+        crate::common::print_transaction_status(transaction_info, Some(connection_config)).await;
+        Ok(())
+    }
+}
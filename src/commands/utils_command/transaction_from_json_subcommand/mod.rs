@@ -0,0 +1,66 @@
+use dialoguer::Input;
+use near_primitives::borsh::BorshSerialize;
+
+/// Build a serialized transaction from the JSON produced by `transaction-to-json`.
+/// A transaction with a `signature` field is verified and serialized as a signed one.
+#[derive(Debug, Default, Clone, clap::Clap)]
+pub struct CliTransactionFromJson {
+    /// Path to the JSON file ("-" reads from stdin)
+    file: Option<std::path::PathBuf>,
+}
+
+impl CliTransactionFromJson {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        if let Some(file) = &self.file {
+            args.push_front(file.to_string_lossy().into_owned());
+        }
+        args
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        let file = match self.file {
+            Some(file) => file,
+            None => Input::<String>::new()
+                .with_prompt("Enter the path to the transaction JSON file")
+                .interact_text()
+                .unwrap()
+                .into(),
+        };
+        let data = if file.as_os_str() == "-" {
+            let mut data = String::new();
+            std::io::Read::read_to_string(&mut std::io::stdin(), &mut data)?;
+            data
+        } else {
+            std::fs::read_to_string(&file).map_err(|err| {
+                color_eyre::Report::msg(format!("Failed to read {}: {}", file.display(), err))
+            })?
+        };
+        let transaction_json: crate::common::TransactionJson = serde_json::from_str(&data)
+            .map_err(|err| {
+                color_eyre::Report::msg(format!("Failed to parse the transaction JSON: {}", err))
+            })?;
+        let serialized_transaction = match transaction_json.to_signed_transaction()? {
+            Some(signed_transaction) => {
+                println!(
+                    "\nSigned transaction (hash {}):",
+                    signed_transaction.get_hash()
+                );
+                signed_transaction.try_to_vec()?
+            }
+            None => {
+                let transaction = transaction_json.to_transaction()?;
+                println!(
+                    "\nUnsigned transaction (hash {}):",
+                    transaction.get_hash_and_size().0
+                );
+                transaction.try_to_vec()?
+            }
+        };
+        println!(
+            "{}",
+            near_primitives::serialize::to_base64(serialized_transaction)
+        );
+        Ok(())
+    }
+}
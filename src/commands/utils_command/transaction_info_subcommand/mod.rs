@@ -0,0 +1,266 @@
+use std::str::FromStr;
+
+use dialoguer::Input;
+use near_primitives::borsh::BorshSerialize;
+
+pub mod operation_mode;
+
+/// Show the hash, the size and the attached deposit and gas of a serialized transaction
+/// (signed or not); in the network mode also check whether its block hash is still valid
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliTransactionInfo {
+    /// Base64-encoded transaction, signed or unsigned
+    transaction: Option<String>,
+    #[clap(subcommand)]
+    mode: Option<self::operation_mode::CliMode>,
+}
+
+impl CliTransactionInfo {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = self
+            .mode
+            .as_ref()
+            .map(|subcommand| subcommand.to_cli_args())
+            .unwrap_or_default();
+        if let Some(transaction) = &self.transaction {
+            args.push_front(transaction.to_owned());
+        }
+        args
+    }
+
+    /// Returns the transaction and the size of its signed form (estimated for an unsigned one).
+    fn decode_transaction(
+        transaction: &str,
+    ) -> Result<(near_primitives::transaction::Transaction, u64, bool), String> {
+        match crate::common::TransactionAsBase64::from_str(transaction) {
+            Ok(transaction) => {
+                let signature_size =
+                    near_crypto::Signature::empty(transaction.inner.public_key.key_type())
+                        .try_to_vec()
+                        .expect("Signature is not expected to fail on serialization")
+                        .len() as u64;
+                let size = transaction.inner.get_hash_and_size().1;
+                Ok((transaction.inner, size + signature_size, false))
+            }
+            Err(_) => {
+                let signed_transaction =
+                    crate::common::SignedTransactionAsBase64::from_str(transaction)?.inner;
+                let size = signed_transaction.get_size();
+                Ok((signed_transaction.transaction, size, true))
+            }
+        }
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        let (transaction, size, is_signed) = match &self.transaction {
+            Some(transaction) => {
+                Self::decode_transaction(transaction).map_err(color_eyre::Report::msg)?
+            }
+            None => loop {
+                let transaction: String = Input::new()
+                    .with_prompt("Enter the serialized transaction (base64)")
+                    .interact_text()
+                    .unwrap();
+                match Self::decode_transaction(&transaction) {
+                    Ok(decoded_transaction) => break decoded_transaction,
+                    Err(err) => println!("{}", err),
+                }
+            },
+        };
+        let mode = match self.mode {
+            Some(cli_mode) => self::operation_mode::Mode::from(cli_mode),
+            None => self::operation_mode::Mode::choose_mode(),
+        };
+        let (total_deposit, total_stake, total_gas) =
+            crate::common::transaction_totals(&transaction).ok_or_else(|| {
+                color_eyre::Report::msg(
+                    "The deposits, stakes or gas attached to the transaction add up to more than fits in a balance",
+                )
+            })?;
+        println!();
+        crate::common::print_transaction(transaction.clone());
+        println!();
+        println!("{:<16} {}", "hash:", transaction.get_hash_and_size().0);
+        println!(
+            "{:<16} {} bytes{} of {} allowed{}",
+            "size:",
+            size,
+            if is_signed { "" } else { " once signed" },
+            crate::common::MAX_TRANSACTION_SIZE,
+            if size > crate::common::MAX_TRANSACTION_SIZE {
+                " (too large, the network rejects it)"
+            } else {
+                ""
+            }
+        );
+        println!(
+            "{:<16} {}",
+            "total deposit:",
+            crate::common::NearBalance::from_yoctonear(total_deposit)
+        );
+        if total_stake > 0 {
+            println!(
+                "{:<16} {}",
+                "total stake:",
+                crate::common::NearBalance::from_yoctonear(total_stake)
+            );
+        }
+        println!(
+            "{:<16} {}",
+            "total gas:",
+            crate::common::NearGas { inner: total_gas }
+        );
+        if let Some(connection_config) = mode.connection_config() {
+            Self::print_validity(&connection_config, &transaction.block_hash).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the height after which a transaction referring to the block at `block_height`
+    /// expires and the number of blocks it stays valid for, if it has not expired yet.
+    fn validity(
+        block_height: near_primitives::types::BlockHeight,
+        latest_block_height: near_primitives::types::BlockHeight,
+    ) -> (near_primitives::types::BlockHeight, Option<u64>) {
+        let expires_after_block_height =
+            block_height + crate::common::TRANSACTION_VALIDITY_PERIOD_BLOCKS;
+        (
+            expires_after_block_height,
+            expires_after_block_height.checked_sub(latest_block_height),
+        )
+    }
+
+    async fn print_validity(
+        connection_config: &crate::common::ConnectionConfig,
+        block_hash: &near_primitives::hash::CryptoHash,
+    ) -> crate::CliResult {
+        let json_rcp_client = near_jsonrpc_client::new_client(connection_config.rpc_url().as_str());
+        let status = json_rcp_client.status().await.map_err(|err| {
+            color_eyre::Report::msg(format!("Failed to fetch the network status: {:?}", err))
+        })?;
+        let block = match json_rcp_client
+            .block(near_primitives::types::BlockReference::BlockId(
+                near_primitives::types::BlockId::Hash(block_hash.clone()),
+            ))
+            .await
+        {
+            Ok(block) => block,
+            Err(err) => {
+                println!(
+                    "\nThe block {} is unknown to {}: the transaction has expired or was made for another network ({:?})",
+                    block_hash,
+                    connection_config.rpc_url(),
+                    err
+                );
+                return Ok(());
+            }
+        };
+        let latest_block_height = status.sync_info.latest_block_height;
+        match Self::validity(block.header.height, latest_block_height) {
+            (expires_after_block_height, None) => println!(
+                "\nThe transaction has expired at block #{} (the latest block is #{})",
+                expires_after_block_height, latest_block_height
+            ),
+            (expires_after_block_height, Some(remaining_blocks)) => println!(
+                "\nThe transaction is valid for {} more blocks (it expires after block #{})",
+                remaining_blocks, expires_after_block_height
+            ),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_greeting_transaction() -> near_primitives::transaction::Transaction {
+        near_primitives::transaction::Transaction {
+            signer_id: "alice.testnet".parse().unwrap(),
+            public_key: near_crypto::PublicKey::empty(near_crypto::KeyType::ED25519),
+            nonce: 0,
+            receiver_id: "contract.testnet".parse().unwrap(),
+            block_hash: Default::default(),
+            actions: vec![near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: "set_greeting".to_owned(),
+                    args: vec![],
+                    gas: 10u64.pow(12),
+                    deposit: 0,
+                },
+            )],
+        }
+    }
+
+    #[test]
+    fn unsigned_transaction_size_is_estimated_as_signed() {
+        let secret_key = near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, "info");
+        let transaction = near_primitives::transaction::Transaction {
+            public_key: secret_key.public_key(),
+            ..set_greeting_transaction()
+        };
+        let (_, unsigned_size, is_signed) = CliTransactionInfo::decode_transaction(
+            &near_primitives::serialize::to_base64(transaction.try_to_vec().unwrap()),
+        )
+        .unwrap();
+        assert!(!is_signed);
+
+        let signed_transaction = near_primitives::transaction::SignedTransaction::new(
+            secret_key.sign(transaction.get_hash_and_size().0.as_ref()),
+            transaction.clone(),
+        );
+        let (decoded_transaction, signed_size, is_signed) = CliTransactionInfo::decode_transaction(
+            &near_primitives::serialize::to_base64(signed_transaction.try_to_vec().unwrap()),
+        )
+        .unwrap();
+        assert!(is_signed);
+        assert_eq!(decoded_transaction, transaction);
+        assert_eq!(unsigned_size, signed_size);
+        assert_eq!(
+            signed_size,
+            signed_transaction.try_to_vec().unwrap().len() as u64
+        );
+    }
+
+    #[test]
+    fn oversized_transaction_exceeds_the_size_limit() {
+        let mut transaction = set_greeting_transaction();
+        if let near_primitives::transaction::Action::FunctionCall(function_call_action) =
+            &mut transaction.actions[0]
+        {
+            function_call_action.args = vec![0; crate::common::MAX_TRANSACTION_SIZE as usize];
+        }
+        let (_, size, _) = CliTransactionInfo::decode_transaction(
+            &near_primitives::serialize::to_base64(transaction.try_to_vec().unwrap()),
+        )
+        .unwrap();
+        assert!(size > crate::common::MAX_TRANSACTION_SIZE);
+    }
+
+    #[test]
+    fn invalid_transaction_is_rejected() {
+        assert!(CliTransactionInfo::decode_transaction("bm90IGEgdHJhbnNhY3Rpb24=").is_err());
+    }
+
+    #[test]
+    fn transaction_expires_after_the_validity_period() {
+        let validity_period = crate::common::TRANSACTION_VALIDITY_PERIOD_BLOCKS;
+        assert_eq!(
+            CliTransactionInfo::validity(1000, 1010),
+            (1000 + validity_period, Some(validity_period - 10))
+        );
+        assert_eq!(
+            CliTransactionInfo::validity(1000, 1000 + validity_period),
+            (1000 + validity_period, Some(0))
+        );
+        assert_eq!(
+            CliTransactionInfo::validity(1000, 1001 + validity_period),
+            (1000 + validity_period, None)
+        );
+    }
+}
@@ -0,0 +1,24 @@
+/// аргументы, необходимые для проверки транзакции в offline mode
+#[derive(Debug, Default, Clone, clap::Clap)]
+pub struct CliOfflineArgs {}
+
+#[derive(Debug, Clone)]
+pub struct OfflineArgs;
+
+impl CliOfflineArgs {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        std::collections::VecDeque::new()
+    }
+}
+
+impl From<OfflineArgs> for CliOfflineArgs {
+    fn from(_: OfflineArgs) -> Self {
+        Self {}
+    }
+}
+
+impl From<CliOfflineArgs> for OfflineArgs {
+    fn from(_: CliOfflineArgs) -> Self {
+        Self
+    }
+}
@@ -0,0 +1,53 @@
+use std::convert::TryFrom;
+
+use dialoguer::Input;
+use near_primitives::borsh::BorshDeserialize;
+
+/// Print a serialized transaction (signed or not) as stable JSON
+#[derive(Debug, Default, Clone, clap::Clap)]
+pub struct CliTransactionToJson {
+    /// Base64-encoded transaction, signed or unsigned
+    transaction: Option<String>,
+}
+
+impl CliTransactionToJson {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        if let Some(transaction) = &self.transaction {
+            args.push_front(transaction.to_string());
+        }
+        args
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        let transaction = match self.transaction {
+            Some(transaction) => transaction,
+            None => Input::new()
+                .with_prompt("Enter the serialized transaction (base64)")
+                .interact_text()
+                .unwrap(),
+        };
+        let serialize_from_base64 =
+            near_primitives::serialize::from_base64(&transaction).map_err(|err| {
+                color_eyre::Report::msg(format!("Base64 transaction sequence is invalid: {}", err))
+            })?;
+        let transaction_json =
+            match near_primitives::transaction::Transaction::try_from_slice(&serialize_from_base64)
+            {
+                Ok(transaction) => crate::common::TransactionJson::try_from(&transaction)?,
+                Err(_) => crate::common::TransactionJson::try_from(
+                    &near_primitives::transaction::SignedTransaction::try_from_slice(
+                        &serialize_from_base64,
+                    )
+                    .map_err(|err| {
+                        color_eyre::Report::msg(format!(
+                            "The sequence is neither a transaction nor a signed transaction: {}",
+                            err
+                        ))
+                    })?,
+                )?,
+            };
+        println!("{}", serde_json::to_string_pretty(&transaction_json)?);
+        Ok(())
+    }
+}
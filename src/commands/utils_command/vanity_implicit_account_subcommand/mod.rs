@@ -0,0 +1,247 @@
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use dialoguer::{theme::ColorfulTheme, Input, Select};
+
+/// Search for a key pair whose implicit account ID starts and/or ends with the given hex characters
+#[derive(Debug, Clone, clap::Clap)]
+pub struct CliVanityImplicitAccount {
+    #[clap(long)]
+    pub prefix: Option<String>,
+    #[clap(long)]
+    pub suffix: Option<String>,
+    /// Save the found key pair to the keychain instead of printing it
+    #[clap(long)]
+    pub save_to_keychain: bool,
+    /// Network whose keychain the key pair is saved to (testnet, mainnet, betanet or an RPC URL)
+    #[clap(long)]
+    pub network: Option<String>,
+    #[clap(long, default_value = "plaintext")]
+    pub format: crate::common::OutputFormat,
+}
+
+impl Default for CliVanityImplicitAccount {
+    fn default() -> Self {
+        Self {
+            prefix: None,
+            suffix: None,
+            save_to_keychain: false,
+            network: None,
+            format: crate::common::OutputFormat::Plaintext,
+        }
+    }
+}
+
+impl CliVanityImplicitAccount {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        args.push_front(self.format.to_string());
+        args.push_front("--format".to_owned());
+        if let Some(network) = &self.network {
+            args.push_front(network.to_owned());
+            args.push_front("--network".to_owned());
+        }
+        if self.save_to_keychain {
+            args.push_front("--save-to-keychain".to_owned());
+        }
+        if let Some(suffix) = &self.suffix {
+            args.push_front(suffix.to_owned());
+            args.push_front("--suffix".to_owned());
+        }
+        if let Some(prefix) = &self.prefix {
+            args.push_front(prefix.to_owned());
+            args.push_front("--prefix".to_owned());
+        }
+        args
+    }
+
+    fn input_prefix() -> String {
+        Input::new()
+            .with_prompt("Enter the hex characters the implicit account ID should start with")
+            .interact_text()
+            .unwrap()
+    }
+
+    fn choose_network() -> crate::common::ConnectionConfig {
+        println!();
+        let networks = [
+            crate::common::ConnectionConfig::Testnet,
+            crate::common::ConnectionConfig::Mainnet,
+            crate::common::ConnectionConfig::Betanet,
+        ];
+        let selected_network = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select the network whose keychain the key pair is saved to")
+            .items(&["Testnet", "Mainnet", "Betanet"])
+            .default(0)
+            .interact()
+            .unwrap();
+        networks[selected_network].clone()
+    }
+
+    fn check_hex_pattern(pattern: &str) -> crate::CliResult {
+        if pattern.len() > 64
+            || !pattern
+                .as_bytes()
+                .iter()
+                .all(|b| matches!(b, b'a'..=b'f' | b'0'..=b'9'))
+        {
+            return Err(color_eyre::Report::msg(format!(
+                "<{}> cannot be a part of an implicit account ID: only lowercase hex characters (0-9, a-f) are allowed",
+                pattern
+            )));
+        }
+        Ok(())
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        let (prefix, suffix) = match (&self.prefix, &self.suffix) {
+            (None, None) => (Self::input_prefix(), String::new()),
+            (prefix, suffix) => (
+                prefix.clone().unwrap_or_default(),
+                suffix.clone().unwrap_or_default(),
+            ),
+        };
+        Self::check_hex_pattern(&prefix)?;
+        Self::check_hex_pattern(&suffix)?;
+        if prefix.len() + suffix.len() > 64 {
+            return Err(color_eyre::Report::msg(
+                "The prefix and the suffix are longer than an implicit account ID",
+            ));
+        }
+        let connection_config = match (self.save_to_keychain, &self.network) {
+            (false, None) => None,
+            (false, Some(_)) => {
+                return Err(color_eyre::Report::msg(
+                    "--network is used together with --save-to-keychain",
+                ))
+            }
+            (true, Some(network)) => {
+                Some(crate::common::ConnectionConfig::from_network_name(network)?)
+            }
+            (true, None) => Some(Self::choose_network()),
+        };
+
+        // All the candidates are derived from one seed phrase with different account indexes,
+        // so the found key can be restored from the seed phrase like any other generated key.
+        let mnemonic = bip39::Mnemonic::generate(12)?;
+        let master_seed_phrase = mnemonic.word_iter().collect::<Vec<&str>>().join(" ");
+        let master_seed = mnemonic.to_seed("");
+
+        let threads_count = num_cpus::get() as u64;
+        let attempts = std::sync::Arc::new(AtomicU64::new(0));
+        let is_found = std::sync::Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = std::sync::mpsc::channel();
+        for thread_index in 0..threads_count {
+            let attempts = attempts.clone();
+            let is_found = is_found.clone();
+            let sender = sender.clone();
+            let prefix = prefix.clone();
+            let suffix = suffix.clone();
+            std::thread::spawn(move || {
+                let mut account_index = thread_index;
+                while !is_found.load(Ordering::Relaxed) && account_index < 1 << 31 {
+                    let seed_phrase_hd_path =
+                        slip10::BIP32Path::from_str(&format!("m/44'/397'/{}'", account_index))
+                            .unwrap();
+                    if let Ok((Some(implicit_account_id), _, _)) =
+                        crate::common::derive_key_pair_from_seed(
+                            &master_seed,
+                            &seed_phrase_hd_path,
+                            crate::common::KeyType::Ed25519,
+                        )
+                    {
+                        let implicit_account_id = implicit_account_id.to_string();
+                        if implicit_account_id.starts_with(&prefix)
+                            && implicit_account_id.ends_with(&suffix)
+                        {
+                            is_found.store(true, Ordering::Relaxed);
+                            let _ = sender.send(seed_phrase_hd_path);
+                        }
+                    }
+                    attempts.fetch_add(1, Ordering::Relaxed);
+                    account_index += threads_count;
+                }
+            });
+        }
+        drop(sender);
+
+        let expected_attempts = 16f64.powi((prefix.len() + suffix.len()) as i32);
+        let start = std::time::Instant::now();
+        eprintln!(
+            "Searching for the implicit account ID {}...{} on {} threads (about {} attempts expected)",
+            prefix, suffix, threads_count, expected_attempts
+        );
+        let seed_phrase_hd_path = loop {
+            match receiver.recv_timeout(std::time::Duration::from_secs(1)) {
+                Ok(seed_phrase_hd_path) => break seed_phrase_hd_path,
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                    let attempts = attempts.load(Ordering::Relaxed);
+                    let elapsed = start.elapsed().as_secs_f64();
+                    let attempts_per_second = attempts as f64 / elapsed;
+                    eprint!(
+                        "\rattempts: {}, attempts per second: {:.0}, elapsed: {:.0}s, estimated time: {:.0}s   ",
+                        attempts,
+                        attempts_per_second,
+                        elapsed,
+                        expected_attempts / attempts_per_second.max(1.0)
+                    );
+                }
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err(color_eyre::Report::msg(
+                        "The search space is exhausted without a match",
+                    ))
+                }
+            }
+        };
+        eprintln!(
+            "\nFound after {} attempts in {:.1}s",
+            attempts.load(Ordering::Relaxed),
+            start.elapsed().as_secs_f64()
+        );
+
+        let key_pair_properties = crate::common::get_key_pair_properties_from_seed_phrase(
+            seed_phrase_hd_path,
+            master_seed_phrase,
+            "",
+            crate::common::KeyType::Ed25519,
+        )?;
+        let implicit_account_id = key_pair_properties
+            .implicit_account_id
+            .clone()
+            .expect("ed25519 keys always have an implicit account ID");
+        if let Some(connection_config) = connection_config {
+            return crate::common::save_access_key_to_keychain(
+                Some(connection_config),
+                key_pair_properties,
+                &implicit_account_id.to_string(),
+            )
+            .await;
+        }
+        match self.format {
+            crate::common::OutputFormat::Plaintext => {
+                println!(
+                    "Master Seed Phrase: {}\nSeed Phrase HD Path: {}\nImplicit Account ID: {}\nPublic Key: {}\nSECRET KEYPAIR: {}",
+                    key_pair_properties.master_seed_phrase,
+                    key_pair_properties.seed_phrase_hd_path.to_string(),
+                    implicit_account_id,
+                    key_pair_properties.public_key_str,
+                    key_pair_properties.secret_keypair_str,
+                );
+            }
+            crate::common::OutputFormat::Json => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&serde_json::json!({
+                        "master_seed_phrase": key_pair_properties.master_seed_phrase,
+                        "seed_phrase_hd_path": key_pair_properties.seed_phrase_hd_path.to_string(),
+                        "account_id": implicit_account_id,
+                        "public_key": key_pair_properties.public_key_str,
+                        "private_key": key_pair_properties.secret_keypair_str,
+                    }))
+                    .unwrap()
+                );
+            }
+        };
+        Ok(())
+    }
+}
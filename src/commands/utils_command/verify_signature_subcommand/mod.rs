@@ -0,0 +1,170 @@
+use std::str::FromStr;
+
+use dialoguer::Input;
+
+/// Verify the signature of an off-chain message (NEP-413) and, optionally, that the public key
+/// is an access key of the account
+#[derive(Debug, Default, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliVerifySignature {
+    #[clap(long)]
+    pub message: Option<String>,
+    #[clap(long)]
+    pub recipient: Option<String>,
+    /// 32 bytes encoded in base64
+    #[clap(long)]
+    pub nonce: Option<String>,
+    #[clap(long)]
+    pub callback_url: Option<String>,
+    #[clap(long)]
+    pub public_key: Option<near_crypto::PublicKey>,
+    /// The signature encoded in base64 (as in NEP-413) or as "ed25519:<base58>"
+    #[clap(long)]
+    pub signature: Option<String>,
+    /// Check that the public key is an access key of this account (requires --network)
+    #[clap(long)]
+    pub account_id: Option<near_primitives::types::AccountId>,
+    /// The network to check the access key on: testnet, mainnet, betanet or the URL of a custom RPC server
+    #[clap(long)]
+    pub network: Option<String>,
+}
+
+impl CliVerifySignature {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        if let Some(network) = &self.network {
+            args.push_front(network.to_owned());
+            args.push_front("--network".to_owned());
+        }
+        if let Some(account_id) = &self.account_id {
+            args.push_front(account_id.to_string());
+            args.push_front("--account-id".to_owned());
+        }
+        if let Some(signature) = &self.signature {
+            args.push_front(signature.to_owned());
+            args.push_front("--signature".to_owned());
+        }
+        if let Some(public_key) = &self.public_key {
+            args.push_front(public_key.to_string());
+            args.push_front("--public-key".to_owned());
+        }
+        if let Some(callback_url) = &self.callback_url {
+            args.push_front(callback_url.to_owned());
+            args.push_front("--callback-url".to_owned());
+        }
+        if let Some(nonce) = &self.nonce {
+            args.push_front(nonce.to_owned());
+            args.push_front("--nonce".to_owned());
+        }
+        if let Some(recipient) = &self.recipient {
+            args.push_front(recipient.to_owned());
+            args.push_front("--recipient".to_owned());
+        }
+        if let Some(message) = &self.message {
+            args.push_front(message.to_owned());
+            args.push_front("--message".to_owned());
+        }
+        args
+    }
+
+    fn input<T>(prompt: &str) -> T
+    where
+        T: Clone + FromStr + std::fmt::Display,
+        T::Err: std::fmt::Display + std::fmt::Debug,
+    {
+        Input::new().with_prompt(prompt).interact_text().unwrap()
+    }
+
+    fn parse_signature(signature: &str) -> color_eyre::eyre::Result<near_crypto::Signature> {
+        if signature.contains(':') {
+            return near_crypto::Signature::from_str(signature)
+                .map_err(|err| color_eyre::Report::msg(format!("Invalid signature: {}", err)));
+        }
+        let bytes = base64::decode(signature).map_err(|err| {
+            color_eyre::Report::msg(format!("The signature is not base64: {}", err))
+        })?;
+        near_crypto::Signature::from_parts(near_crypto::KeyType::ED25519, &bytes)
+            .map_err(|err| color_eyre::Report::msg(format!("Invalid signature: {}", err)))
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        if self.account_id.is_some() != self.network.is_some() {
+            return Err(color_eyre::Report::msg(
+                "--account-id and --network must be used together",
+            ));
+        }
+        let payload = crate::common::Nep413Payload {
+            message: match self.message {
+                Some(message) => message,
+                None => Self::input("Enter the signed message"),
+            },
+            recipient: match self.recipient {
+                Some(recipient) => recipient,
+                None => Self::input("Enter the recipient of the message"),
+            },
+            nonce: super::sign_message_subcommand::parse_nonce(&match self.nonce {
+                Some(nonce) => nonce,
+                None => Self::input::<String>("Enter the nonce (base64)"),
+            })?,
+            callback_url: self.callback_url,
+        };
+        let public_key: near_crypto::PublicKey = match self.public_key {
+            Some(public_key) => public_key,
+            None => Self::input("Enter the public key"),
+        };
+        let signature = Self::parse_signature(&match self.signature {
+            Some(signature) => signature,
+            None => Self::input::<String>("Enter the signature"),
+        })?;
+        if !payload.verify(&signature, &public_key) {
+            return Err(color_eyre::Report::msg(format!(
+                "The signature is not valid for the public key {}",
+                public_key
+            )));
+        }
+        println!("The signature is valid for the public key {}.", public_key);
+
+        if let (Some(account_id), Some(network)) = (self.account_id, self.network) {
+            let connection_config = crate::common::ConnectionConfig::from_network_name(&network)?;
+            let access_key_view =
+                near_jsonrpc_client::new_client(connection_config.rpc_url().as_str())
+                    .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
+                        block_reference: near_primitives::types::Finality::Final.into(),
+                        request: near_primitives::views::QueryRequest::ViewAccessKey {
+                            account_id: account_id.clone(),
+                            public_key: public_key.clone(),
+                        },
+                    })
+                    .await
+                    .map_err(|err| {
+                        color_eyre::Report::msg(format!(
+                            "The public key {} is not an access key of <{}>: {:?}",
+                            public_key, account_id, err
+                        ))
+                    })?;
+            match access_key_view.kind {
+                near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKey(
+                    access_key,
+                ) => match access_key.permission {
+                    near_primitives::views::AccessKeyPermissionView::FullAccess => println!(
+                        "The public key is a full access key of <{}>.",
+                        account_id
+                    ),
+                    // NEP-413 messages are only valid when signed with a full access key.
+                    near_primitives::views::AccessKeyPermissionView::FunctionCall { .. } => {
+                        return Err(color_eyre::Report::msg(format!(
+                            "The public key is a function call access key of <{}>, but NEP-413 messages must be signed with a full access key",
+                            account_id
+                        )))
+                    }
+                },
+                _ => return Err(color_eyre::Report::msg("Error call result")),
+            }
+        }
+        Ok(())
+    }
+}
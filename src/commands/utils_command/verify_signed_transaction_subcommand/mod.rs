@@ -0,0 +1,43 @@
+use dialoguer::Input;
+
+/// Check the signature of a signed transaction offline and show the transaction hash
+#[derive(Debug, Default, Clone, clap::Clap)]
+pub struct CliVerifySignedTransaction {
+    #[clap(long)]
+    pub signed_transaction: Option<crate::common::SignedTransactionAsBase64>,
+}
+
+impl CliVerifySignedTransaction {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        if let Some(signed_transaction) = &self.signed_transaction {
+            args.push_front(signed_transaction.to_string());
+            args.push_front("--signed-transaction".to_owned());
+        }
+        args
+    }
+
+    pub async fn process(self) -> crate::CliResult {
+        let signed_transaction = match self.signed_transaction {
+            Some(signed_transaction) => signed_transaction,
+            None => Input::<crate::common::SignedTransactionAsBase64>::new()
+                .with_prompt("Enter the signed transaction (base64)")
+                .interact_text()
+                .unwrap(),
+        }
+        .inner;
+        println!("\nSigned transaction:\n");
+        crate::common::print_transaction(signed_transaction.transaction.clone());
+        println!("{:<13} {}", "signature:", signed_transaction.signature);
+        println!("{:<13} {}", "hash:", signed_transaction.get_hash());
+        crate::common::verify_transaction_signature(
+            &signed_transaction.transaction,
+            &signed_transaction.signature,
+        )?;
+        println!(
+            "\nThe signature is valid: the transaction is signed with {}.",
+            signed_transaction.transaction.public_key
+        );
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use dialoguer::{theme::ColorfulTheme, Select};
 use strum::{EnumDiscriminants, EnumIter, EnumMessage, IntoEnumIterator};
 
+mod view_access_key_audit;
 mod view_account;
 mod view_contract_code;
 mod view_contract_state;
@@ -64,6 +65,8 @@ impl ViewQueryRequest {
 pub enum CliQueryRequest {
     /// View properties for an account
     AccountSummary(self::view_account::operation_mode::CliOperationMode),
+    /// Audit access keys of an account
+    AccessKeyAudit(self::view_access_key_audit::operation_mode::CliOperationMode),
     /// View a contract code
     ContractCode(self::view_contract_code::operation_mode::CliOperationMode),
     /// View a contract state
@@ -81,6 +84,8 @@ pub enum CliQueryRequest {
 pub enum QueryRequest {
     #[strum_discriminants(strum(message = "View properties for an account"))]
     AccountSummary(self::view_account::operation_mode::OperationMode),
+    #[strum_discriminants(strum(message = "Audit access keys of an account"))]
+    AccessKeyAudit(self::view_access_key_audit::operation_mode::OperationMode),
     #[strum_discriminants(strum(message = "View a contract code"))]
     ContractCode(self::view_contract_code::operation_mode::OperationMode),
     #[strum_discriminants(strum(message = "View a contract state"))]
@@ -101,6 +106,11 @@ impl CliQueryRequest {
                 args.push_front("account-summary".to_owned());
                 args
             }
+            Self::AccessKeyAudit(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("access-key-audit".to_owned());
+                args
+            }
             Self::ContractCode(subcommand) => {
                 let mut args = subcommand.to_cli_args();
                 args.push_front("contract-code".to_owned());
@@ -136,6 +146,9 @@ impl From<QueryRequest> for CliQueryRequest {
             QueryRequest::AccountSummary(operation_mode) => {
                 Self::AccountSummary(operation_mode.into())
             }
+            QueryRequest::AccessKeyAudit(operation_mode) => {
+                Self::AccessKeyAudit(operation_mode.into())
+            }
             QueryRequest::ContractCode(operation_mode) => Self::ContractCode(operation_mode.into()),
             QueryRequest::ContractState(operation_mode) => {
                 Self::ContractState(operation_mode.into())
@@ -155,6 +168,9 @@ impl From<CliQueryRequest> for QueryRequest {
             CliQueryRequest::AccountSummary(cli_operation_mode) => {
                 QueryRequest::AccountSummary(cli_operation_mode.into())
             }
+            CliQueryRequest::AccessKeyAudit(cli_operation_mode) => {
+                QueryRequest::AccessKeyAudit(cli_operation_mode.into())
+            }
             CliQueryRequest::ContractCode(cli_operation_mode) => {
                 QueryRequest::ContractCode(cli_operation_mode.into())
             }
@@ -192,6 +208,9 @@ impl QueryRequest {
             QueryRequestDiscriminants::AccountSummary => {
                 CliQueryRequest::AccountSummary(Default::default())
             }
+            QueryRequestDiscriminants::AccessKeyAudit => {
+                CliQueryRequest::AccessKeyAudit(Default::default())
+            }
             QueryRequestDiscriminants::ContractCode => {
                 CliQueryRequest::ContractCode(Default::default())
             }
@@ -212,6 +231,7 @@ impl QueryRequest {
     pub async fn process(self) -> crate::CliResult {
         match self {
             QueryRequest::AccountSummary(operation_mode) => operation_mode.process().await,
+            QueryRequest::AccessKeyAudit(operation_mode) => operation_mode.process().await,
             QueryRequest::ContractCode(operation_mode) => operation_mode.process().await,
             QueryRequest::ContractState(operation_mode) => operation_mode.process().await,
             QueryRequest::Transaction(operation_mode) => operation_mode.process().await,
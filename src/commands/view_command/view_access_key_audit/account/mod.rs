@@ -0,0 +1,524 @@
+use dialoguer::Input;
+
+/// Keys added after the nonce rule was introduced start with `(block_height - 1) * ACCESS_KEY_NONCE_RANGE_MULTIPLIER`.
+const ACCESS_KEY_NONCE_RANGE_MULTIPLIER: u64 = 1_000_000;
+/// Number of transactions signed with a key after which it is reported as a high-nonce key.
+const HIGH_NONCE_TRANSACTIONS_COUNT: u64 = 100_000;
+/// Number of blocks (about 30 days) after which a never used key is reported as stale.
+const STALE_KEY_BLOCKS_COUNT: u64 = 2_592_000;
+/// Remaining allowance below which a function call key is reported as almost exhausted.
+const LOW_ALLOWANCE: u128 = 10u128.pow(21);
+
+#[derive(Debug, Clone, clap::Clap)]
+pub enum CliSendTo {
+    /// Specify an account
+    Account(CliAccount),
+}
+
+#[derive(Debug, Clone)]
+pub enum SendTo {
+    Account(Account),
+}
+
+impl CliSendTo {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        match self {
+            Self::Account(subcommand) => {
+                let mut args = subcommand.to_cli_args();
+                args.push_front("account".to_owned());
+                args
+            }
+        }
+    }
+}
+
+impl From<SendTo> for CliSendTo {
+    fn from(send_to: SendTo) -> Self {
+        match send_to {
+            SendTo::Account(account) => Self::Account(account.into()),
+        }
+    }
+}
+
+impl From<CliSendTo> for SendTo {
+    fn from(item: CliSendTo) -> Self {
+        match item {
+            CliSendTo::Account(cli_account) => {
+                let account = Account::from(cli_account);
+                Self::Account(account)
+            }
+        }
+    }
+}
+
+impl SendTo {
+    pub fn send_to() -> Self {
+        Self::from(CliSendTo::Account(Default::default()))
+    }
+
+    pub async fn process(
+        self,
+        network_connection_config: crate::common::ConnectionConfig,
+    ) -> crate::CliResult {
+        match self {
+            SendTo::Account(account) => account.process(network_connection_config).await,
+        }
+    }
+}
+
+/// Specify account to audit the access keys for
+#[derive(Debug, Clone, clap::Clap)]
+#[clap(
+    setting(clap::AppSettings::ColoredHelp),
+    setting(clap::AppSettings::DisableHelpSubcommand),
+    setting(clap::AppSettings::VersionlessSubcommands)
+)]
+pub struct CliAccount {
+    account_id: Option<near_primitives::types::AccountId>,
+    #[clap(long, default_value = "plaintext")]
+    format: crate::common::OutputFormat,
+}
+
+impl Default for CliAccount {
+    fn default() -> Self {
+        Self {
+            account_id: None,
+            format: crate::common::OutputFormat::Plaintext,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Account {
+    account_id: near_primitives::types::AccountId,
+    format: crate::common::OutputFormat,
+}
+
+impl CliAccount {
+    pub fn to_cli_args(&self) -> std::collections::VecDeque<String> {
+        let mut args = std::collections::VecDeque::new();
+        args.push_front(self.format.to_string());
+        args.push_front("--format".to_owned());
+        if let Some(account_id) = &self.account_id {
+            args.push_front(account_id.to_string());
+        };
+        args
+    }
+}
+
+impl From<Account> for CliAccount {
+    fn from(account: Account) -> Self {
+        Self {
+            account_id: Some(account.account_id),
+            format: account.format,
+        }
+    }
+}
+
+impl From<CliAccount> for Account {
+    fn from(item: CliAccount) -> Self {
+        let account_id: near_primitives::types::AccountId = match item.account_id {
+            Some(cli_account_id) => cli_account_id,
+            None => Account::input_account_id(),
+        };
+        Self {
+            account_id,
+            format: item.format,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum FindingSeverity {
+    High,
+    Medium,
+    Low,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct Finding {
+    severity: FindingSeverity,
+    code: &'static str,
+    message: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct AccessKeyReport {
+    public_key: String,
+    permission: String,
+    in_keychain: bool,
+    nonce: u64,
+    /// Number of transactions signed with the key; approximate, since a transaction may skip nonces.
+    approximate_transactions_count: u64,
+    /// Block height at which the key was added, if it can be derived from the nonce.
+    added_at_block_height: Option<u64>,
+    /// Remaining allowance in yoctoNEAR; the initial allowance is not stored on chain.
+    #[serde(serialize_with = "near_primitives::serialize::option_u128_dec_format::serialize")]
+    remaining_allowance: Option<near_primitives::types::Balance>,
+    findings: Vec<Finding>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct AccessKeyAuditReport {
+    account_id: String,
+    block_height: u64,
+    access_keys: Vec<AccessKeyReport>,
+    /// Keys found in the keychain that are not on chain anymore.
+    keychain_only_public_keys: Vec<String>,
+}
+
+impl Account {
+    fn input_account_id() -> near_primitives::types::AccountId {
+        println!();
+        Input::new()
+            .with_prompt("Enter your account ID")
+            .interact_text()
+            .unwrap()
+    }
+
+    fn rpc_client(&self, selected_server_url: &str) -> near_jsonrpc_client::JsonRpcClient {
+        near_jsonrpc_client::new_client(&selected_server_url)
+    }
+
+    fn audit_access_key(
+        access_key: &near_primitives::views::AccessKeyInfoView,
+        in_keychain: bool,
+        block_height: u64,
+    ) -> AccessKeyReport {
+        let mut findings: Vec<Finding> = vec![];
+        let nonce = access_key.access_key.nonce;
+        let (transactions_count, added_at_block_height) =
+            if nonce >= ACCESS_KEY_NONCE_RANGE_MULTIPLIER {
+                (
+                    nonce % ACCESS_KEY_NONCE_RANGE_MULTIPLIER,
+                    Some(nonce / ACCESS_KEY_NONCE_RANGE_MULTIPLIER + 1),
+                )
+            } else {
+                (nonce, None)
+            };
+        let (permission, remaining_allowance) = match &access_key.access_key.permission {
+            near_primitives::views::AccessKeyPermissionView::FullAccess => {
+                if !in_keychain {
+                    findings.push(Finding {
+                        severity: FindingSeverity::High,
+                        code: "full_access_key_not_in_keychain",
+                        message: "full access key is not known to the local keychain".to_owned(),
+                    });
+                }
+                ("full access".to_owned(), None)
+            }
+            near_primitives::views::AccessKeyPermissionView::FunctionCall {
+                allowance,
+                receiver_id,
+                method_names,
+            } => {
+                match allowance {
+                    None => findings.push(Finding {
+                        severity: FindingSeverity::Medium,
+                        code: "unlimited_allowance",
+                        message: "function call key has no allowance limit".to_owned(),
+                    }),
+                    Some(amount) if *amount < LOW_ALLOWANCE => findings.push(Finding {
+                        severity: FindingSeverity::Low,
+                        code: "low_allowance",
+                        message: format!(
+                            "remaining allowance is almost exhausted ({})",
+                            crate::common::NearBalance::from_yoctonear(*amount)
+                        ),
+                    }),
+                    Some(_) => {}
+                };
+                (
+                    format!("function calls {:?} on {}", method_names, receiver_id),
+                    *allowance,
+                )
+            }
+        };
+        match (transactions_count, added_at_block_height) {
+            (0, Some(added_at_block_height))
+                if block_height.saturating_sub(added_at_block_height) > STALE_KEY_BLOCKS_COUNT =>
+            {
+                findings.push(Finding {
+                    severity: FindingSeverity::Low,
+                    code: "stale_key",
+                    message: format!(
+                        "the key was added at block {} and has never been used",
+                        added_at_block_height
+                    ),
+                })
+            }
+            (transactions_count, _) if transactions_count >= HIGH_NONCE_TRANSACTIONS_COUNT => {
+                findings.push(Finding {
+                    severity: FindingSeverity::Low,
+                    code: "high_nonce",
+                    message: format!(
+                        "the key has signed about {} transactions, consider rotating it",
+                        transactions_count
+                    ),
+                })
+            }
+            _ => {}
+        };
+        AccessKeyReport {
+            public_key: access_key.public_key.to_string(),
+            permission,
+            in_keychain,
+            nonce,
+            approximate_transactions_count: transactions_count,
+            added_at_block_height,
+            remaining_allowance,
+            findings,
+        }
+    }
+
+    fn print_report(report: &AccessKeyAuditReport) {
+        println!(
+            "\nAccess key audit for <{}> at block #{}",
+            report.account_id, report.block_height
+        );
+        println!("Number of access keys: {}", report.access_keys.len());
+        for (index, access_key) in report.access_keys.iter().enumerate() {
+            println!(
+                "{: >4}. {} ({}{})",
+                index + 1,
+                access_key.public_key,
+                access_key.permission,
+                if access_key.in_keychain {
+                    ", in the keychain"
+                } else {
+                    ""
+                }
+            );
+            println!("        nonce: {}", access_key.nonce);
+            println!(
+                "        transactions signed: about {}",
+                access_key.approximate_transactions_count
+            );
+            if let Some(added_at_block_height) = access_key.added_at_block_height {
+                println!("        added at block: #{}", added_at_block_height);
+            }
+            if let Some(remaining_allowance) = access_key.remaining_allowance {
+                println!(
+                    "        remaining allowance: {}",
+                    crate::common::NearBalance::from_yoctonear(remaining_allowance)
+                );
+            }
+            for finding in access_key.findings.iter() {
+                println!(
+                    "        [{:?}] {}: {}",
+                    finding.severity, finding.code, finding.message
+                );
+            }
+        }
+        if !report.keychain_only_public_keys.is_empty() {
+            println!("\nKeys in the keychain that are not on chain:");
+            for public_key in report.keychain_only_public_keys.iter() {
+                println!("      {}", public_key);
+            }
+        }
+        let findings_count: usize = report
+            .access_keys
+            .iter()
+            .map(|access_key| access_key.findings.len())
+            .sum();
+        println!("\nFindings: {}", findings_count);
+    }
+
+    pub async fn process(
+        self,
+        network_connection_config: crate::common::ConnectionConfig,
+    ) -> crate::CliResult {
+        let query_view_method_response = self
+            .rpc_client(network_connection_config.rpc_url().as_str())
+            .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
+                block_reference: near_primitives::types::Finality::Final.into(),
+                request: near_primitives::views::QueryRequest::ViewAccessKeyList {
+                    account_id: self.account_id.clone(),
+                },
+            })
+            .await
+            .map_err(|err| {
+                color_eyre::Report::msg(format!(
+                    "Failed to fetch query for view key list: {:?}",
+                    err
+                ))
+            })?;
+        let block_height = query_view_method_response.block_height;
+        let access_key_view =
+            if let near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKeyList(result) =
+                query_view_method_response.kind
+            {
+                result
+            } else {
+                return Err(color_eyre::Report::msg("Error call result"));
+            };
+        let keychain_public_keys: Vec<near_crypto::PublicKey> =
+            crate::common::read_keychain_access_keys(
+                network_connection_config.dir_name(),
+                &self.account_id,
+            )
+            .into_iter()
+            .map(|(_, keychain_access_key)| keychain_access_key.public_key)
+            .collect();
+        let access_keys: Vec<AccessKeyReport> = access_key_view
+            .keys
+            .iter()
+            .map(|access_key| {
+                Self::audit_access_key(
+                    access_key,
+                    keychain_public_keys.contains(&access_key.public_key),
+                    block_height,
+                )
+            })
+            .collect();
+        let mut keychain_only_public_keys: Vec<String> = keychain_public_keys
+            .iter()
+            .filter(|public_key| {
+                !access_key_view
+                    .keys
+                    .iter()
+                    .any(|access_key| &access_key.public_key == *public_key)
+            })
+            .map(|public_key| public_key.to_string())
+            .collect();
+        keychain_only_public_keys.sort();
+        keychain_only_public_keys.dedup();
+        let report = AccessKeyAuditReport {
+            account_id: self.account_id.to_string(),
+            block_height,
+            access_keys,
+            keychain_only_public_keys,
+        };
+        match self.format {
+            crate::common::OutputFormat::Plaintext => Self::print_report(&report),
+            crate::common::OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            }
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn access_key_info(
+        nonce: u64,
+        permission: near_primitives::views::AccessKeyPermissionView,
+    ) -> near_primitives::views::AccessKeyInfoView {
+        near_primitives::views::AccessKeyInfoView {
+            public_key: near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, "audit")
+                .public_key(),
+            access_key: near_primitives::views::AccessKeyView { nonce, permission },
+        }
+    }
+
+    fn function_call_permission(
+        allowance: Option<near_primitives::types::Balance>,
+    ) -> near_primitives::views::AccessKeyPermissionView {
+        near_primitives::views::AccessKeyPermissionView::FunctionCall {
+            allowance,
+            receiver_id: "app.testnet".to_owned(),
+            method_names: vec![],
+        }
+    }
+
+    fn finding_codes(report: &AccessKeyReport) -> Vec<&'static str> {
+        report.findings.iter().map(|finding| finding.code).collect()
+    }
+
+    #[test]
+    fn full_access_key_outside_the_keychain_is_reported() {
+        let access_key = access_key_info(
+            5,
+            near_primitives::views::AccessKeyPermissionView::FullAccess,
+        );
+        let report = Account::audit_access_key(&access_key, false, 100);
+        assert_eq!(
+            finding_codes(&report),
+            vec!["full_access_key_not_in_keychain"]
+        );
+        assert_eq!(report.remaining_allowance, None);
+
+        let report = Account::audit_access_key(&access_key, true, 100);
+        assert!(report.findings.is_empty());
+    }
+
+    #[test]
+    fn remaining_allowance_is_kept_in_yoctonear() {
+        let allowance = 25 * 10u128.pow(23);
+        let report = Account::audit_access_key(
+            &access_key_info(5, function_call_permission(Some(allowance))),
+            true,
+            100,
+        );
+        assert_eq!(report.remaining_allowance, Some(allowance));
+        assert!(report.findings.is_empty());
+        assert_eq!(
+            serde_json::to_value(&report).unwrap()["remaining_allowance"],
+            serde_json::json!("2500000000000000000000000")
+        );
+    }
+
+    #[test]
+    fn unlimited_and_low_allowances_are_reported() {
+        let report = Account::audit_access_key(
+            &access_key_info(5, function_call_permission(None)),
+            true,
+            100,
+        );
+        assert_eq!(finding_codes(&report), vec!["unlimited_allowance"]);
+        assert_eq!(
+            serde_json::to_value(&report).unwrap()["remaining_allowance"],
+            serde_json::Value::Null
+        );
+
+        let report = Account::audit_access_key(
+            &access_key_info(5, function_call_permission(Some(LOW_ALLOWANCE - 1))),
+            true,
+            100,
+        );
+        assert_eq!(finding_codes(&report), vec!["low_allowance"]);
+        assert_eq!(report.remaining_allowance, Some(LOW_ALLOWANCE - 1));
+    }
+
+    #[test]
+    fn nonce_is_split_into_block_height_and_transactions_count() {
+        let report = Account::audit_access_key(
+            &access_key_info(
+                41 * ACCESS_KEY_NONCE_RANGE_MULTIPLIER + 7,
+                near_primitives::views::AccessKeyPermissionView::FullAccess,
+            ),
+            true,
+            100,
+        );
+        assert_eq!(report.added_at_block_height, Some(42));
+        assert_eq!(report.approximate_transactions_count, 7);
+    }
+
+    #[test]
+    fn stale_and_high_nonce_keys_are_reported() {
+        let added_at_block_height = 10;
+        let report = Account::audit_access_key(
+            &access_key_info(
+                (added_at_block_height - 1) * ACCESS_KEY_NONCE_RANGE_MULTIPLIER,
+                near_primitives::views::AccessKeyPermissionView::FullAccess,
+            ),
+            true,
+            added_at_block_height + STALE_KEY_BLOCKS_COUNT + 1,
+        );
+        assert_eq!(finding_codes(&report), vec!["stale_key"]);
+
+        let report = Account::audit_access_key(
+            &access_key_info(
+                HIGH_NONCE_TRANSACTIONS_COUNT,
+                near_primitives::views::AccessKeyPermissionView::FullAccess,
+            ),
+            true,
+            100,
+        );
+        assert_eq!(finding_codes(&report), vec!["high_nonce"]);
+    }
+}
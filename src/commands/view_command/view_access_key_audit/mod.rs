@@ -0,0 +1,2 @@
+mod account;
+pub mod operation_mode;
@@ -1,7 +1,55 @@
 use std::convert::{TryFrom, TryInto};
 use std::io::Write;
+use std::str::FromStr;
 
-use near_primitives::borsh::BorshDeserialize;
+use near_primitives::borsh::{BorshDeserialize, BorshSerialize};
+
+mod access_key_permission;
+mod agent;
+mod bundle;
+mod csv_file;
+mod key_type;
+mod ledger;
+mod nep413;
+mod nonce_store;
+mod preflight;
+mod qr_code;
+mod recover;
+mod review;
+mod shamir;
+mod signer;
+mod signing_policy;
+mod transaction_json;
+
+pub use self::access_key_permission::{check_access_key_permission, AccessKeyPrivilege};
+pub use self::agent::{agent_request, agent_socket_path, AgentRequest, AgentResponse};
+pub use self::bundle::TransactionBundle;
+#[cfg(test)]
+pub use self::csv_file::write_temp_csv;
+pub use self::csv_file::{read_csv_lines, CsvLine};
+pub use self::key_type::{
+    derive_secp256k1_key_from_path, parse_hardened_index, KeyType, HARDENED_INDEX_BOUND,
+};
+pub use self::ledger::{HardwareLedger, LedgerDevice, LedgerSigner};
+pub use self::nep413::Nep413Payload;
+pub use self::nonce_store::{InvalidNonceError, NonceStore};
+pub use self::preflight::{check_transaction_before_sending, preflight_failures, PreflightState};
+pub use self::qr_code::{
+    print_qr_code, qr_code_to_svg, save_qr_code, split_into_qr_code_parts, QR_CODE_MAX_PART_LENGTH,
+};
+pub use self::recover::{
+    recover_access_keys_from_seed_phrase, AccessKeyListSource, RecoveredAccessKey,
+};
+pub use self::review::{
+    review_transaction, transaction_max_gas, transaction_risks, transaction_totals,
+};
+pub use self::shamir::{combine_secret_shares, split_secret, SecretShare};
+pub use self::signer::{KeychainSigner, SecretKeySigner, SignerFuture, TransactionSigner};
+pub use self::signing_policy::{
+    check_signing_policy_without_prompts, enforce_signing_policy, input_signing_policy_network,
+    SigningPolicy,
+};
+pub use self::transaction_json::{AccessKeyPermissionJson, ActionJson, TransactionJson};
 
 #[derive(
     Debug,
@@ -51,6 +99,58 @@ impl std::fmt::Display for TransactionAsBase64 {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct SignedTransactionAsBase64 {
+    pub inner: near_primitives::transaction::SignedTransaction,
+}
+
+impl std::str::FromStr for SignedTransactionAsBase64 {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            inner: near_primitives::transaction::SignedTransaction::try_from_slice(
+                &near_primitives::serialize::from_base64(s)
+                    .map_err(|err| format!("base64 transaction sequence is invalid: {}", err))?,
+            )
+            .map_err(|err| format!("signed transaction could not be parsed: {}", err))?,
+        })
+    }
+}
+
+impl std::fmt::Display for SignedTransactionAsBase64 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            near_primitives::serialize::to_base64(
+                self.inner
+                    .try_to_vec()
+                    .expect("Signed transaction is not expected to fail on serialization")
+            )
+        )
+    }
+}
+
+/// Checks that the signature was made by the key in the `public_key` field of the transaction.
+pub fn verify_transaction_signature(
+    transaction: &near_primitives::transaction::Transaction,
+    signature: &near_crypto::Signature,
+) -> crate::CliResult {
+    if signature.verify(
+        transaction.get_hash_and_size().0.as_ref(),
+        &transaction.public_key,
+    ) {
+        Ok(())
+    } else {
+        Err(color_eyre::Report::msg(format!(
+            "The signature {} does not match the transaction hash {} and the public key {}",
+            signature,
+            transaction.get_hash_and_size().0,
+            transaction.public_key
+        )))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BlockHashAsBase58 {
     pub inner: near_primitives::hash::CryptoHash,
@@ -277,6 +377,23 @@ pub enum ConnectionConfig {
 }
 
 impl ConnectionConfig {
+    /// Accepts "testnet", "mainnet", "betanet" or the URL of a custom RPC server.
+    pub fn from_network_name(network: &str) -> color_eyre::eyre::Result<Self> {
+        Ok(match network {
+            "testnet" => Self::Testnet,
+            "mainnet" => Self::Mainnet,
+            "betanet" => Self::Betanet,
+            url => Self::Custom {
+                url: url.parse().map_err(|err| {
+                    color_eyre::Report::msg(format!(
+                        "<{}> is neither a network name (testnet, mainnet, betanet) nor an RPC server URL: {}",
+                        url, err
+                    ))
+                })?,
+            },
+        })
+    }
+
     pub fn rpc_url(&self) -> url::Url {
         match self {
             Self::Testnet => crate::consts::TESTNET_API_SERVER_URL.parse().unwrap(),
@@ -350,7 +467,7 @@ pub fn check_account_id(
                 {
                     result
                 } else {
-                    return Err(color_eyre::Report::msg(format!("Error call result")));
+                    return Err(color_eyre::Report::msg("Error call result"));
                 };
             Ok(Some(account_view))
         }
@@ -368,67 +485,238 @@ pub fn is_64_len_hex(account_id: impl AsRef<str>) -> bool {
             .all(|b| matches!(b, b'a'..=b'f' | b'0'..=b'9'))
 }
 
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct KeychainAccessKey {
+    pub account_id: near_primitives::types::AccountId,
+    pub public_key: near_crypto::PublicKey,
+    pub private_key: near_crypto::SecretKey,
+}
+
+/// Reads all the access keys saved in the keychain for the account: the account file itself
+/// and the files in the account directory.
+pub fn read_keychain_access_keys(
+    dir_name: &str,
+    account_id: &near_primitives::types::AccountId,
+) -> Vec<(std::path::PathBuf, KeychainAccessKey)> {
+    let home_dir = dirs::home_dir().expect("Impossible to get your home dir!");
+    let mut account_file_path = std::path::PathBuf::from(&home_dir);
+    account_file_path.push(dir_name);
+    account_file_path.push(format!("{}.json", account_id));
+    let mut account_dir_path = std::path::PathBuf::from(&home_dir);
+    account_dir_path.push(dir_name);
+    account_dir_path.push(account_id.to_string());
+    let mut paths = vec![account_file_path];
+    if let Ok(dir) = account_dir_path.read_dir() {
+        paths.extend(dir.filter_map(|entry| entry.ok()).map(|entry| entry.path()));
+    }
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let data = std::fs::read_to_string(&path).ok()?;
+            let keychain_access_key: KeychainAccessKey = serde_json::from_str(&data).ok()?;
+            Some((path, keychain_access_key))
+        })
+        .collect()
+}
+
+/// Returns the first access key of the account in the keychain that has full access on chain.
+pub async fn find_full_access_keychain_key(
+    connection_config: &ConnectionConfig,
+    account_id: &near_primitives::types::AccountId,
+) -> color_eyre::eyre::Result<near_crypto::PublicKey> {
+    let json_rpc_client = near_jsonrpc_client::new_client(connection_config.rpc_url().as_str());
+    for (_, keychain_access_key) in
+        read_keychain_access_keys(connection_config.dir_name(), account_id)
+    {
+        let access_key_response = json_rpc_client
+            .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
+                block_reference: near_primitives::types::Finality::Final.into(),
+                request: near_primitives::views::QueryRequest::ViewAccessKey {
+                    account_id: account_id.clone(),
+                    public_key: keychain_access_key.public_key.clone(),
+                },
+            })
+            .await;
+        if let Ok(near_jsonrpc_primitives::types::query::RpcQueryResponse {
+            kind:
+                near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKey(
+                    near_primitives::views::AccessKeyView {
+                        permission: near_primitives::views::AccessKeyPermissionView::FullAccess,
+                        ..
+                    },
+                ),
+            ..
+        }) = access_key_response
+        {
+            return Ok(keychain_access_key.public_key);
+        }
+    }
+    Err(color_eyre::Report::msg(format!(
+        "There is no full access key of <{}> in the keychain",
+        account_id
+    )))
+}
+
 #[derive(Debug, Clone)]
 pub struct KeyPairProperties {
     pub seed_phrase_hd_path: slip10::BIP32Path,
     pub master_seed_phrase: String,
-    pub implicit_account_id: near_primitives::types::AccountId,
+    /// Implicit accounts are only defined for ed25519 keys.
+    pub implicit_account_id: Option<near_primitives::types::AccountId>,
     pub public_key_str: String,
     pub secret_keypair_str: String,
 }
 
-pub async fn generate_keypair() -> color_eyre::eyre::Result<KeyPairProperties> {
+pub async fn generate_keypair(
+    key_type: KeyType,
+    seed_phrase_passphrase: &str,
+) -> color_eyre::eyre::Result<KeyPairProperties> {
     let generate_keypair: crate::commands::utils_command::generate_keypair_subcommand::CliGenerateKeypair =
         crate::commands::utils_command::generate_keypair_subcommand::CliGenerateKeypair::default();
-    let (master_seed_phrase, master_seed) =
-        if let Some(master_seed_phrase) = generate_keypair.master_seed_phrase.as_deref() {
-            (
-                master_seed_phrase.to_owned(),
-                bip39::Mnemonic::parse(master_seed_phrase)?.to_seed(""),
-            )
-        } else {
-            let mnemonic =
-                bip39::Mnemonic::generate(generate_keypair.new_master_seed_phrase_words_count)?;
-            let master_seed_phrase = mnemonic.word_iter().collect::<Vec<&str>>().join(" ");
-            (master_seed_phrase, mnemonic.to_seed(""))
-        };
-
-    let derived_private_key = slip10::derive_key_from_path(
-        &master_seed,
-        slip10::Curve::Ed25519,
-        &generate_keypair.seed_phrase_hd_path,
+    let mnemonic = bip39::Mnemonic::generate(generate_keypair.new_master_seed_phrase_words_count)?;
+    let master_seed_phrase = mnemonic.word_iter().collect::<Vec<&str>>().join(" ");
+    get_key_pair_properties_from_seed_phrase(
+        generate_keypair.seed_phrase_hd_path,
+        master_seed_phrase,
+        seed_phrase_passphrase,
+        key_type,
     )
-    .map_err(|err| {
-        color_eyre::Report::msg(format!(
-            "Failed to derive a key from the master key: {}",
-            err
-        ))
-    })?;
-
-    let secret_keypair = {
-        let secret = ed25519_dalek::SecretKey::from_bytes(&derived_private_key.key)?;
-        let public = ed25519_dalek::PublicKey::from(&secret);
-        ed25519_dalek::Keypair { secret, public }
-    };
+}
 
-    let implicit_account_id =
-        near_primitives::types::AccountId::try_from(hex::encode(&secret_keypair.public))?;
-    let public_key_str = format!(
-        "ed25519:{}",
-        bs58::encode(&secret_keypair.public).into_string()
-    );
-    let secret_keypair_str = format!(
-        "ed25519:{}",
-        bs58::encode(secret_keypair.to_bytes()).into_string()
-    );
-    let key_pair_properties: KeyPairProperties = KeyPairProperties {
-        seed_phrase_hd_path: generate_keypair.seed_phrase_hd_path,
+pub fn get_key_pair_properties_from_seed_phrase(
+    seed_phrase_hd_path: slip10::BIP32Path,
+    master_seed_phrase: String,
+    seed_phrase_passphrase: &str,
+    key_type: KeyType,
+) -> color_eyre::eyre::Result<KeyPairProperties> {
+    let master_seed = bip39::Mnemonic::parse(&master_seed_phrase)?.to_seed(seed_phrase_passphrase);
+    let (implicit_account_id, public_key_str, secret_keypair_str) =
+        derive_key_pair_from_seed(&master_seed, &seed_phrase_hd_path, key_type)?;
+    Ok(KeyPairProperties {
+        seed_phrase_hd_path,
         master_seed_phrase,
         implicit_account_id,
         public_key_str,
         secret_keypair_str,
-    };
-    Ok(key_pair_properties)
+    })
+}
+
+/// Derives a key pair from the BIP-39 seed.
+/// Returns the implicit account ID (for ed25519 keys only), the public key and the secret key.
+pub fn derive_key_pair_from_seed(
+    master_seed: &[u8],
+    seed_phrase_hd_path: &slip10::BIP32Path,
+    key_type: KeyType,
+) -> color_eyre::eyre::Result<(Option<near_primitives::types::AccountId>, String, String)> {
+    Ok(match key_type {
+        KeyType::Ed25519 => {
+            let derived_private_key = slip10::derive_key_from_path(
+                master_seed,
+                slip10::Curve::Ed25519,
+                seed_phrase_hd_path,
+            )
+            .map_err(|err| {
+                color_eyre::Report::msg(format!(
+                    "Failed to derive a key from the master key: {}",
+                    err
+                ))
+            })?;
+
+            let secret_keypair = {
+                let secret = ed25519_dalek::SecretKey::from_bytes(&derived_private_key.key)?;
+                let public = ed25519_dalek::PublicKey::from(&secret);
+                ed25519_dalek::Keypair { secret, public }
+            };
+
+            let implicit_account_id =
+                near_primitives::types::AccountId::try_from(hex::encode(&secret_keypair.public))?;
+            let public_key_str = format!(
+                "ed25519:{}",
+                bs58::encode(&secret_keypair.public).into_string()
+            );
+            let secret_keypair_str = format!(
+                "ed25519:{}",
+                bs58::encode(secret_keypair.to_bytes()).into_string()
+            );
+            (
+                Some(implicit_account_id),
+                public_key_str,
+                secret_keypair_str,
+            )
+        }
+        KeyType::Secp256k1 => {
+            let derived_private_key =
+                derive_secp256k1_key_from_path(master_seed, seed_phrase_hd_path)?;
+            let secret_key = near_crypto::SecretKey::from_str(&format!(
+                "secp256k1:{}",
+                bs58::encode(&derived_private_key).into_string()
+            ))?;
+            (
+                None,
+                secret_key.public_key().to_string(),
+                secret_key.to_string(),
+            )
+        }
+    })
+}
+
+/// Number of blocks after the referenced block hash during which a transaction can be included.
+pub const TRANSACTION_VALIDITY_PERIOD_BLOCKS: u64 = 86_400;
+
+/// The largest signed transaction (in bytes) accepted by the protocol.
+pub const MAX_TRANSACTION_SIZE: u64 = 4_194_304;
+
+pub fn transaction_summary(transaction: &near_primitives::transaction::Transaction) -> Vec<String> {
+    let mut summary = vec![
+        format!("{:<13} {}", "signer_id:", transaction.signer_id),
+        format!("{:<13} {}", "public_key:", transaction.public_key),
+        format!("{:<13} {}", "nonce:", transaction.nonce),
+        format!("{:<13} {}", "receiver_id:", transaction.receiver_id),
+        format!("{:<13} {}", "block_hash:", transaction.block_hash),
+        "actions:".to_owned(),
+    ];
+    for action in transaction.actions.iter() {
+        summary.push(match action {
+            near_primitives::transaction::Action::CreateAccount(_) => {
+                format!("   -- create account {}", transaction.receiver_id)
+            }
+            near_primitives::transaction::Action::DeployContract(deploy_contract_action) => {
+                format!(
+                    "   -- deploy contract ({} bytes)",
+                    deploy_contract_action.code.len()
+                )
+            }
+            near_primitives::transaction::Action::FunctionCall(function_call_action) => format!(
+                "   -- call {} with {} and deposit {}",
+                function_call_action.method_name,
+                NearGas {
+                    inner: function_call_action.gas
+                },
+                NearBalance::from_yoctonear(function_call_action.deposit)
+            ),
+            near_primitives::transaction::Action::Transfer(transfer_action) => format!(
+                "   -- transfer {}",
+                NearBalance::from_yoctonear(transfer_action.deposit)
+            ),
+            near_primitives::transaction::Action::Stake(stake_action) => format!(
+                "   -- stake {} with {}",
+                NearBalance::from_yoctonear(stake_action.stake),
+                stake_action.public_key
+            ),
+            near_primitives::transaction::Action::AddKey(add_key_action) => format!(
+                "   -- add access key {} ({:?})",
+                add_key_action.public_key, add_key_action.access_key.permission
+            ),
+            near_primitives::transaction::Action::DeleteKey(delete_key_action) => {
+                format!("   -- delete access key {}", delete_key_action.public_key)
+            }
+            near_primitives::transaction::Action::DeleteAccount(delete_account_action) => format!(
+                "   -- delete account, beneficiary {}",
+                delete_account_action.beneficiary_id
+            ),
+        });
+    }
+    summary
 }
 
 pub fn print_transaction(transaction: near_primitives::transaction::Transaction) {
@@ -878,7 +1166,7 @@ pub async fn save_access_key_to_keychain(
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use std::str::FromStr;
 
@@ -1232,4 +1520,57 @@ mod tests {
             Err("Near Gas: invalid digit found in string".to_string())
         );
     }
+
+    pub(crate) const LEDGER_EMULATOR_SEED_PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    /// A Ledger signer backed by the emulator with the keys of `LEDGER_EMULATOR_SEED_PHRASE`.
+    pub(crate) fn emulated_ledger_signer(
+        seed_phrase_hd_path: &str,
+        reject_signing: bool,
+    ) -> LedgerSigner {
+        LedgerSigner::with_device(
+            slip10::BIP32Path::from_str(seed_phrase_hd_path).unwrap(),
+            Box::new(
+                super::ledger::LedgerEmulator::new(LEDGER_EMULATOR_SEED_PHRASE, reject_signing)
+                    .unwrap(),
+            ),
+        )
+    }
+
+    #[test]
+    fn verify_transaction_signature_detects_foreign_key() {
+        let secret_key = near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, "signer");
+        let unsigned_transaction = near_primitives::transaction::Transaction {
+            signer_id: "alice.testnet".parse().unwrap(),
+            public_key: secret_key.public_key(),
+            nonce: 0,
+            receiver_id: "bob.testnet".parse().unwrap(),
+            block_hash: Default::default(),
+            actions: vec![near_primitives::transaction::Action::Transfer(
+                near_primitives::transaction::TransferAction { deposit: 1 },
+            )],
+        };
+        let signature = secret_key.sign(unsigned_transaction.get_hash_and_size().0.as_ref());
+        let signed_transaction = SignedTransactionAsBase64::from_str(
+            &SignedTransactionAsBase64 {
+                inner: near_primitives::transaction::SignedTransaction::new(
+                    signature.clone(),
+                    unsigned_transaction.clone(),
+                ),
+            }
+            .to_string(),
+        )
+        .unwrap();
+        assert!(verify_transaction_signature(
+            &signed_transaction.inner.transaction,
+            &signed_transaction.inner.signature
+        )
+        .is_ok());
+        let foreign_transaction = near_primitives::transaction::Transaction {
+            public_key: near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, "other")
+                .public_key(),
+            ..unsigned_transaction
+        };
+        assert!(verify_transaction_signature(&foreign_transaction, &signature).is_err());
+    }
 }
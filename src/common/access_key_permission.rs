@@ -0,0 +1,196 @@
+use super::NearBalance;
+
+/// How much an access key is allowed to do. Variants are ordered from the least to the most privileged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AccessKeyPrivilege {
+    LimitedFunctionCall,
+    FunctionCall,
+    UnlimitedFunctionCall,
+    FullAccess,
+}
+
+impl std::fmt::Display for AccessKeyPrivilege {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LimitedFunctionCall => write!(
+                f,
+                "function call access limited to a list of methods and an allowance"
+            ),
+            Self::FunctionCall => write!(
+                f,
+                "function call access limited to a list of methods or an allowance"
+            ),
+            Self::UnlimitedFunctionCall => {
+                write!(
+                    f,
+                    "function call access to any method with no allowance limit"
+                )
+            }
+            Self::FullAccess => write!(f, "full access"),
+        }
+    }
+}
+
+/// Checks whether an access key with the given permission can sign the transaction.
+/// On success returns the privilege level of the key, otherwise the reason why the key does not fit.
+pub fn check_access_key_permission(
+    permission: &near_primitives::views::AccessKeyPermissionView,
+    transaction: &near_primitives::transaction::Transaction,
+    gas_price: near_primitives::types::Balance,
+) -> Result<AccessKeyPrivilege, String> {
+    let (allowance, receiver_id, method_names) = match permission {
+        near_primitives::views::AccessKeyPermissionView::FullAccess => {
+            return Ok(AccessKeyPrivilege::FullAccess)
+        }
+        near_primitives::views::AccessKeyPermissionView::FunctionCall {
+            allowance,
+            receiver_id,
+            method_names,
+        } => (allowance, receiver_id, method_names),
+    };
+    let function_call_action = match transaction.actions.as_slice() {
+        [near_primitives::transaction::Action::FunctionCall(function_call_action)] => {
+            function_call_action
+        }
+        _ => {
+            return Err(
+                "the transaction requires a full access key (it has to consist of exactly one function call)"
+                    .to_string(),
+            )
+        }
+    };
+    if function_call_action.deposit > 0 {
+        return Err(format!(
+            "function call access keys cannot attach a deposit ({})",
+            NearBalance::from_yoctonear(function_call_action.deposit)
+        ));
+    }
+    if receiver_id.to_string() != transaction.receiver_id.to_string() {
+        return Err(format!(
+            "the key only allows calls to <{}>, but the transaction receiver is <{}>",
+            receiver_id, transaction.receiver_id
+        ));
+    }
+    if !method_names.is_empty()
+        && !method_names
+            .iter()
+            .any(|method_name| method_name == &function_call_action.method_name)
+    {
+        return Err(format!(
+            "the key only allows the methods {:?}, but the transaction calls \"{}\"",
+            method_names, function_call_action.method_name
+        ));
+    }
+    if let Some(allowance) = allowance {
+        let prepaid_gas_cost = (function_call_action.gas as u128).saturating_mul(gas_price);
+        if *allowance < prepaid_gas_cost {
+            return Err(format!(
+                "the remaining allowance ({}) does not cover the prepaid gas ({})",
+                NearBalance::from_yoctonear(*allowance),
+                NearBalance::from_yoctonear(prepaid_gas_cost)
+            ));
+        }
+    }
+    Ok(match (method_names.is_empty(), allowance.is_none()) {
+        (false, false) => AccessKeyPrivilege::LimitedFunctionCall,
+        (true, true) => AccessKeyPrivilege::UnlimitedFunctionCall,
+        _ => AccessKeyPrivilege::FunctionCall,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function_call(
+        method_name: &str,
+        deposit: near_primitives::types::Balance,
+    ) -> near_primitives::transaction::Transaction {
+        near_primitives::transaction::Transaction {
+            signer_id: "alice.testnet".parse().unwrap(),
+            public_key: near_crypto::PublicKey::empty(near_crypto::KeyType::ED25519),
+            nonce: 0,
+            receiver_id: "contract.testnet".parse().unwrap(),
+            block_hash: Default::default(),
+            actions: vec![near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: method_name.to_owned(),
+                    args: vec![],
+                    gas: 10u64.pow(12),
+                    deposit,
+                },
+            )],
+        }
+    }
+
+    #[test]
+    fn access_key_permission_full_access() {
+        assert_eq!(
+            check_access_key_permission(
+                &near_primitives::views::AccessKeyPermissionView::FullAccess,
+                &function_call("set_greeting", 1),
+                1
+            ),
+            Ok(AccessKeyPrivilege::FullAccess)
+        );
+    }
+
+    #[test]
+    fn access_key_permission_function_call_limited() {
+        let permission = near_primitives::views::AccessKeyPermissionView::FunctionCall {
+            allowance: Some(10u128.pow(24)),
+            receiver_id: "contract.testnet".parse().unwrap(),
+            method_names: vec!["set_greeting".to_string()],
+        };
+        assert_eq!(
+            check_access_key_permission(&permission, &function_call("set_greeting", 0), 1),
+            Ok(AccessKeyPrivilege::LimitedFunctionCall)
+        );
+        assert!(
+            check_access_key_permission(&permission, &function_call("get_greeting", 0), 1).is_err()
+        );
+    }
+
+    #[test]
+    fn access_key_permission_function_call_with_deposit() {
+        let permission = near_primitives::views::AccessKeyPermissionView::FunctionCall {
+            allowance: None,
+            receiver_id: "contract.testnet".parse().unwrap(),
+            method_names: vec![],
+        };
+        assert!(
+            check_access_key_permission(&permission, &function_call("set_greeting", 1), 1).is_err()
+        );
+        assert_eq!(
+            check_access_key_permission(&permission, &function_call("set_greeting", 0), 1),
+            Ok(AccessKeyPrivilege::UnlimitedFunctionCall)
+        );
+    }
+
+    #[test]
+    fn access_key_permission_function_call_not_enough_allowance() {
+        let permission = near_primitives::views::AccessKeyPermissionView::FunctionCall {
+            allowance: Some(1),
+            receiver_id: "contract.testnet".parse().unwrap(),
+            method_names: vec![],
+        };
+        assert!(check_access_key_permission(
+            &permission,
+            &function_call("set_greeting", 0),
+            100_000_000
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn access_key_permission_function_call_receiver_mismatch() {
+        let permission = near_primitives::views::AccessKeyPermissionView::FunctionCall {
+            allowance: None,
+            receiver_id: "another.testnet".parse().unwrap(),
+            method_names: vec![],
+        };
+        assert!(
+            check_access_key_permission(&permission, &function_call("set_greeting", 0), 1).is_err()
+        );
+    }
+}
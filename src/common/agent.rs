@@ -0,0 +1,74 @@
+use super::Nep413Payload;
+
+/// A request to `near-cli agent`, sent as a single line of JSON over its Unix socket.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum AgentRequest {
+    /// List the public keys the agent holds for the account in the keychain directory
+    ListKeys {
+        dir_name: String,
+        account_id: near_primitives::types::AccountId,
+    },
+    /// Sign the base64-encoded unsigned transaction with the key of its `public_key`
+    Sign {
+        dir_name: String,
+        transaction: String,
+    },
+    /// Sign the NEP-413 message with the key of the account
+    SignMessage {
+        dir_name: String,
+        account_id: near_primitives::types::AccountId,
+        public_key: near_crypto::PublicKey,
+        payload: Nep413Payload,
+    },
+}
+
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AgentResponse {
+    #[serde(default)]
+    pub public_keys: Vec<near_crypto::PublicKey>,
+    #[serde(default)]
+    pub signature: Option<near_crypto::Signature>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// The socket of the agent: `$NEAR_CLI_AGENT_SOCK` or `~/.near-credentials/agent.sock`.
+pub fn agent_socket_path() -> std::path::PathBuf {
+    match std::env::var_os(crate::consts::AGENT_SOCKET_ENV_VAR) {
+        Some(socket_path) => std::path::PathBuf::from(socket_path),
+        None => {
+            let mut socket_path = dirs::home_dir().expect("Impossible to get your home dir!");
+            socket_path.push(crate::consts::AGENT_SOCKET_FILE_NAME);
+            socket_path
+        }
+    }
+}
+
+/// Sends the request to the running agent. Returns `None` when there is no agent to talk to.
+#[cfg(unix)]
+pub fn agent_request(request: &AgentRequest) -> color_eyre::eyre::Result<Option<AgentResponse>> {
+    use std::io::Write;
+    let mut stream = match std::os::unix::net::UnixStream::connect(agent_socket_path()) {
+        Ok(stream) => stream,
+        Err(_) => return Ok(None),
+    };
+    serde_json::to_writer(&mut stream, request)?;
+    stream.write_all(b"\n")?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+    let response: AgentResponse = serde_json::from_reader(&stream).map_err(|err| {
+        color_eyre::Report::msg(format!("The agent returned an invalid response: {}", err))
+    })?;
+    if let Some(error) = response.error {
+        return Err(color_eyre::Report::msg(format!(
+            "The agent refused the request: {}",
+            error
+        )));
+    }
+    Ok(Some(response))
+}
+
+#[cfg(not(unix))]
+pub fn agent_request(_request: &AgentRequest) -> color_eyre::eyre::Result<Option<AgentResponse>> {
+    Ok(None)
+}
@@ -0,0 +1,202 @@
+use std::str::FromStr;
+
+use super::{ConnectionConfig, TransactionAsBase64};
+
+/// A self-describing JSON file that carries a transaction between an online and an air-gapped
+/// machine: `prepare-bundle` fills in the nonce and the recent block hash, `sign-bundle` adds
+/// the signed transaction and `submit-bundle` verifies and broadcasts it.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TransactionBundle {
+    pub version: u32,
+    pub network: String,
+    pub rpc_url: url::Url,
+    /// Human-readable description of the transaction to review before signing.
+    pub summary: Vec<String>,
+    /// Base64-encoded unsigned transaction.
+    pub unsigned_transaction: String,
+    /// Height of the block whose hash the transaction refers to.
+    pub block_height: u64,
+    /// The transaction is rejected by the network after this block height.
+    pub expires_after_block_height: u64,
+    /// Base64-encoded signed transaction, set by `sign-bundle`.
+    pub signed_transaction: Option<String>,
+}
+
+impl TransactionBundle {
+    pub const VERSION: u32 = 1;
+
+    pub fn read(path: &std::path::Path) -> color_eyre::eyre::Result<Self> {
+        let data = std::fs::read_to_string(path).map_err(|err| {
+            color_eyre::Report::msg(format!(
+                "Failed to read the bundle {}: {}",
+                path.display(),
+                err
+            ))
+        })?;
+        let bundle: Self = serde_json::from_str(&data).map_err(|err| {
+            color_eyre::Report::msg(format!(
+                "Failed to parse the bundle {}: {}",
+                path.display(),
+                err
+            ))
+        })?;
+        if bundle.version != Self::VERSION {
+            return Err(color_eyre::Report::msg(format!(
+                "Unsupported bundle version {}, expected {}",
+                bundle.version,
+                Self::VERSION
+            )));
+        }
+        Ok(bundle)
+    }
+
+    pub fn write(&self, path: &std::path::Path) -> crate::CliResult {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        println!("The bundle is saved in a file {}", path.display());
+        Ok(())
+    }
+
+    pub fn unsigned_transaction(
+        &self,
+    ) -> color_eyre::eyre::Result<near_primitives::transaction::Transaction> {
+        Ok(TransactionAsBase64::from_str(&self.unsigned_transaction)
+            .map_err(color_eyre::Report::msg)?
+            .inner)
+    }
+
+    pub fn connection_config(&self) -> ConnectionConfig {
+        match self.network.as_str() {
+            "testnet" => ConnectionConfig::Testnet,
+            "mainnet" => ConnectionConfig::Mainnet,
+            "betanet" => ConnectionConfig::Betanet,
+            _ => ConnectionConfig::Custom {
+                url: self.rpc_url.clone(),
+            },
+        }
+    }
+
+    pub fn print_summary(&self) {
+        println!("Network: {} ({})", self.network, self.rpc_url);
+        for line in self.summary.iter() {
+            println!("{}", line);
+        }
+        println!(
+            "The transaction expires after block #{}",
+            self.expires_after_block_height
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_primitives::borsh::BorshSerialize;
+
+    fn set_greeting_transaction() -> near_primitives::transaction::Transaction {
+        near_primitives::transaction::Transaction {
+            signer_id: "alice.testnet".parse().unwrap(),
+            public_key: near_crypto::PublicKey::empty(near_crypto::KeyType::ED25519),
+            nonce: 0,
+            receiver_id: "contract.testnet".parse().unwrap(),
+            block_hash: Default::default(),
+            actions: vec![near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: "set_greeting".to_owned(),
+                    args: vec![],
+                    gas: 10u64.pow(12),
+                    deposit: 0,
+                },
+            )],
+        }
+    }
+
+    fn bundle(network: &str) -> TransactionBundle {
+        let transaction = set_greeting_transaction();
+        TransactionBundle {
+            version: TransactionBundle::VERSION,
+            network: network.to_owned(),
+            rpc_url: "https://rpc.testnet.near.org".parse().unwrap(),
+            summary: crate::common::transaction_summary(&transaction),
+            unsigned_transaction: near_primitives::serialize::to_base64(
+                transaction.try_to_vec().unwrap(),
+            ),
+            block_height: 100,
+            expires_after_block_height: 100 + crate::common::TRANSACTION_VALIDITY_PERIOD_BLOCKS,
+            signed_transaction: None,
+        }
+    }
+
+    fn temp_bundle_path(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("near-cli-bundle-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("bundle.json")
+    }
+
+    #[test]
+    fn bundle_survives_a_write_and_read_round_trip() {
+        let path = temp_bundle_path("round-trip");
+        let bundle = bundle("testnet");
+        bundle.write(&path).unwrap();
+
+        let read_bundle = TransactionBundle::read(&path).unwrap();
+        assert_eq!(read_bundle.network, bundle.network);
+        assert_eq!(read_bundle.rpc_url, bundle.rpc_url);
+        assert_eq!(read_bundle.summary, bundle.summary);
+        assert_eq!(read_bundle.block_height, bundle.block_height);
+        assert_eq!(
+            read_bundle.expires_after_block_height,
+            bundle.expires_after_block_height
+        );
+        assert_eq!(read_bundle.signed_transaction, None);
+        assert_eq!(
+            read_bundle.unsigned_transaction().unwrap(),
+            set_greeting_transaction()
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn bundle_of_another_version_is_rejected() {
+        let path = temp_bundle_path("version");
+        TransactionBundle {
+            version: TransactionBundle::VERSION + 1,
+            ..bundle("testnet")
+        }
+        .write(&path)
+        .unwrap();
+
+        let err = TransactionBundle::read(&path).unwrap_err();
+        assert!(err.to_string().contains("Unsupported bundle version"));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn malformed_bundle_is_rejected() {
+        let path = temp_bundle_path("malformed");
+        std::fs::write(&path, "{\"version\": 1}").unwrap();
+        let err = TransactionBundle::read(&path).unwrap_err();
+        assert!(err.to_string().contains("Failed to parse the bundle"));
+
+        let bundle = TransactionBundle {
+            unsigned_transaction: "not base64".to_owned(),
+            ..bundle("testnet")
+        };
+        assert!(bundle.unsigned_transaction().is_err());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn bundle_network_selects_the_connection_config() {
+        assert!(matches!(
+            bundle("mainnet").connection_config(),
+            ConnectionConfig::Mainnet
+        ));
+        match bundle("localnet").connection_config() {
+            ConnectionConfig::Custom { url } => {
+                assert_eq!(url.as_str(), "https://rpc.testnet.near.org/")
+            }
+            connection_config => panic!("Unexpected connection config {:?}", connection_config),
+        }
+    }
+}
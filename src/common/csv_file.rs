@@ -0,0 +1,89 @@
+/// A data line of a CSV file: its line number (starting from 1) and its trimmed columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvLine {
+    pub line: usize,
+    pub columns: Vec<String>,
+}
+
+/// Reads the data lines of a CSV file. Blank lines and `#` comments are skipped, and so is the
+/// first line left when its second column is `header_column` (a header line like
+/// "receiver,amount").
+pub fn read_csv_lines(
+    csv: &std::path::Path,
+    header_column: &str,
+) -> color_eyre::eyre::Result<Vec<CsvLine>> {
+    let data = std::fs::read_to_string(csv).map_err(|err| {
+        color_eyre::Report::msg(format!("Failed to read {}: {}", csv.display(), err))
+    })?;
+    let mut csv_lines: Vec<CsvLine> = vec![];
+    for (index, line) in data.lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let columns = line
+            .split(',')
+            .map(|column| column.trim().to_owned())
+            .collect::<Vec<_>>();
+        if csv_lines.is_empty()
+            && columns
+                .get(1)
+                .map(|column| column.eq_ignore_ascii_case(header_column))
+                .unwrap_or(false)
+        {
+            continue;
+        }
+        csv_lines.push(CsvLine {
+            line: index + 1,
+            columns,
+        });
+    }
+    Ok(csv_lines)
+}
+
+/// Writes a file with the data to a directory of the test process and returns its path.
+#[cfg(test)]
+pub fn write_temp_csv(name: &str, data: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("near-cli-csv-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, data).unwrap();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_comments_and_blank_lines_are_skipped() {
+        let csv = write_temp_csv(
+            "lines.csv",
+            "# transfers of May\n\nreceiver, Amount\nalice.testnet, 1 NEAR\n# bob\nbob.testnet,2 NEAR,extra\n",
+        );
+        assert_eq!(
+            read_csv_lines(&csv, "amount").unwrap(),
+            vec![
+                CsvLine {
+                    line: 4,
+                    columns: vec!["alice.testnet".to_owned(), "1 NEAR".to_owned()],
+                },
+                CsvLine {
+                    line: 6,
+                    columns: vec![
+                        "bob.testnet".to_owned(),
+                        "2 NEAR".to_owned(),
+                        "extra".to_owned()
+                    ],
+                },
+            ]
+        );
+        std::fs::remove_file(csv).unwrap();
+    }
+
+    #[test]
+    fn only_the_first_data_line_can_be_a_header() {
+        let csv = write_temp_csv("late-header.csv", "alice.testnet,1 NEAR\nreceiver,amount\n");
+        assert_eq!(read_csv_lines(&csv, "amount").unwrap().len(), 2);
+        std::fs::remove_file(csv).unwrap();
+    }
+}
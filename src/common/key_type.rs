@@ -0,0 +1,162 @@
+use std::str::FromStr;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    strum_macros::IntoStaticStr,
+    strum_macros::EnumString,
+    strum_macros::EnumVariantNames,
+    smart_default::SmartDefault,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum KeyType {
+    #[default]
+    Ed25519,
+    Secp256k1,
+}
+
+impl std::fmt::Display for KeyType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyType::Ed25519 => write!(f, "ed25519"),
+            KeyType::Secp256k1 => write!(f, "secp256k1"),
+        }
+    }
+}
+
+/// Account indexes of hardened paths are below this bound; the bit itself marks the path hardened.
+pub const HARDENED_INDEX_BOUND: u32 = 1 << 31;
+
+const SECP256K1_CURVE_ORDER: [u8; 32] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+    0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+];
+
+fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+    use hmac::{Mac, NewMac};
+    let mut mac =
+        hmac::Hmac::<sha2::Sha512>::new_varkey(key).expect("HMAC can take a key of any size");
+    mac.update(data);
+    let mut result = [0u8; 64];
+    result.copy_from_slice(&mac.finalize().into_bytes());
+    result
+}
+
+/// Adds two numbers below the secp256k1 curve order modulo the order.
+fn add_mod_secp256k1_curve_order(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let mut result = [0u8; 32];
+    let mut carry = 0u16;
+    for i in (0..32).rev() {
+        let sum = a[i] as u16 + b[i] as u16 + carry;
+        result[i] = sum as u8;
+        carry = sum >> 8;
+    }
+    if carry == 1 || result >= SECP256K1_CURVE_ORDER {
+        let mut borrow = 0i16;
+        for i in (0..32).rev() {
+            let difference = result[i] as i16 - SECP256K1_CURVE_ORDER[i] as i16 - borrow;
+            result[i] = difference.rem_euclid(256) as u8;
+            borrow = if difference < 0 { 1 } else { 0 };
+        }
+    }
+    result
+}
+
+fn secp256k1_compressed_public_key(private_key: &[u8; 32]) -> color_eyre::eyre::Result<Vec<u8>> {
+    let secret_key = near_crypto::SecretKey::from_str(&format!(
+        "secp256k1:{}",
+        bs58::encode(private_key).into_string()
+    ))?;
+    let public_key_str = secret_key.public_key().to_string();
+    let uncompressed = bs58::decode(public_key_str.trim_start_matches("secp256k1:")).into_vec()?;
+    let mut compressed = vec![0x02 | (uncompressed[63] & 1)];
+    compressed.extend_from_slice(&uncompressed[..32]);
+    Ok(compressed)
+}
+
+/// Parses the account index N of a hardened `.../N'` path; N must be below 2^31, otherwise
+/// setting the hardened bit would alias it to a lower index.
+pub fn parse_hardened_index(value: &str) -> Result<u32, String> {
+    let index = u32::from_str(value).map_err(|err| err.to_string())?;
+    if index >= HARDENED_INDEX_BOUND {
+        return Err(format!(
+            "The account index {} is not below 2^31 = {}",
+            index, HARDENED_INDEX_BOUND
+        ));
+    }
+    Ok(index)
+}
+
+/// Derives a secp256k1 private key from the seed following SLIP-0010 (same as BIP-0032 for this curve).
+pub fn derive_secp256k1_key_from_path(
+    seed: &[u8],
+    seed_phrase_hd_path: &slip10::BIP32Path,
+) -> color_eyre::eyre::Result<[u8; 32]> {
+    let master = hmac_sha512(b"Bitcoin seed", seed);
+    let mut private_key = [0u8; 32];
+    let mut chain_code = [0u8; 32];
+    private_key.copy_from_slice(&master[..32]);
+    chain_code.copy_from_slice(&master[32..]);
+    for depth in 0..seed_phrase_hd_path.depth() {
+        let index = *seed_phrase_hd_path.index(depth).unwrap();
+        let mut data = if index & 0x8000_0000 != 0 {
+            let mut data = vec![0u8];
+            data.extend_from_slice(&private_key);
+            data
+        } else {
+            secp256k1_compressed_public_key(&private_key)?
+        };
+        data.extend_from_slice(&index.to_be_bytes());
+        let child = hmac_sha512(&chain_code, &data);
+        let mut tweak = [0u8; 32];
+        tweak.copy_from_slice(&child[..32]);
+        if tweak >= SECP256K1_CURVE_ORDER {
+            return Err(color_eyre::Report::msg(format!(
+                "Failed to derive a key for the index {}, try another HD path",
+                index
+            )));
+        }
+        private_key = add_mod_secp256k1_curve_order(&private_key, &tweak);
+        chain_code.copy_from_slice(&child[32..]);
+    }
+    if private_key == [0u8; 32] {
+        return Err(color_eyre::Report::msg(
+            "Failed to derive a key from the master key, try another HD path",
+        ));
+    }
+    Ok(private_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn derive_secp256k1_key_from_path_test_vector_1() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let cases = [
+            (
+                "m/0'",
+                "edb2e14f9ee77d26dd93b4ecede8d16ed408ce149b6cd80b0715a2d911a0afea",
+            ),
+            (
+                "m/0'/1",
+                "3c6cb8d0f6a264c91ea8b5030fadaa8e538b020f0a387421a12de9319dc93368",
+            ),
+            (
+                "m/0'/1/2'",
+                "cbce0d719ecf7431d88e6a89fa1483e02e35092af60c042b1df2ff59fa424dca",
+            ),
+        ];
+        for (path, expected_private_key) in cases.iter() {
+            let seed_phrase_hd_path = slip10::BIP32Path::from_str(path).unwrap();
+            assert_eq!(
+                hex::encode(derive_secp256k1_key_from_path(&seed, &seed_phrase_hd_path).unwrap()),
+                *expected_private_key
+            );
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use near_primitives::borsh::BorshSerialize;
+
+use super::{SignerFuture, TransactionSigner};
+
+#[cfg(any(test, feature = "ledger-emulator"))]
+mod emulator;
+
+#[cfg(any(test, feature = "ledger-emulator"))]
+pub use self::emulator::LedgerEmulator;
+
+/// A Ledger device with the NEAR app: either the connected hardware device or a `LedgerEmulator`.
+pub trait LedgerDevice {
+    fn get_public_key<'a>(
+        &'a self,
+        seed_phrase_hd_path: &'a slip10::BIP32Path,
+    ) -> SignerFuture<'a, ed25519_dalek::PublicKey>;
+
+    /// Signs the borsh-serialized transaction and returns the ed25519 signature bytes.
+    fn sign_transaction<'a>(
+        &'a self,
+        transaction: Vec<u8>,
+        seed_phrase_hd_path: &'a slip10::BIP32Path,
+    ) -> SignerFuture<'a, Vec<u8>>;
+}
+
+pub struct HardwareLedger;
+
+impl LedgerDevice for HardwareLedger {
+    fn get_public_key<'a>(
+        &'a self,
+        seed_phrase_hd_path: &'a slip10::BIP32Path,
+    ) -> SignerFuture<'a, ed25519_dalek::PublicKey> {
+        Box::pin(async move {
+            near_ledger::get_public_key(seed_phrase_hd_path.clone())
+                .await
+                .map_err(|near_ledger_error| {
+                    color_eyre::Report::msg(format!(
+                        "An error occurred while trying to get PublicKey from Ledger device: {:?}",
+                        near_ledger_error
+                    ))
+                })
+        })
+    }
+
+    fn sign_transaction<'a>(
+        &'a self,
+        transaction: Vec<u8>,
+        seed_phrase_hd_path: &'a slip10::BIP32Path,
+    ) -> SignerFuture<'a, Vec<u8>> {
+        Box::pin(async move {
+            near_ledger::sign_transaction(transaction, seed_phrase_hd_path.clone())
+                .await
+                .map_err(|near_ledger_error| {
+                    color_eyre::Report::msg(format!(
+                        "Error occurred while signing the transaction: {:?}",
+                        near_ledger_error
+                    ))
+                })
+        })
+    }
+}
+
+pub struct LedgerSigner {
+    pub seed_phrase_hd_path: slip10::BIP32Path,
+    device: Box<dyn LedgerDevice>,
+}
+
+impl LedgerSigner {
+    /// Uses the connected device, or `LedgerEmulator` when near-cli is built with the
+    /// `ledger-emulator` feature and the emulator is configured in the environment.
+    pub fn new(seed_phrase_hd_path: slip10::BIP32Path) -> color_eyre::eyre::Result<Self> {
+        #[cfg(feature = "ledger-emulator")]
+        if let Some(ledger_emulator) = LedgerEmulator::from_env()? {
+            return Ok(Self::with_device(
+                seed_phrase_hd_path,
+                Box::new(ledger_emulator),
+            ));
+        }
+        Ok(Self::with_device(
+            seed_phrase_hd_path,
+            Box::new(HardwareLedger),
+        ))
+    }
+
+    pub fn with_device(
+        seed_phrase_hd_path: slip10::BIP32Path,
+        device: Box<dyn LedgerDevice>,
+    ) -> Self {
+        Self {
+            seed_phrase_hd_path,
+            device,
+        }
+    }
+}
+
+impl TransactionSigner for LedgerSigner {
+    fn public_key<'a>(&'a self) -> SignerFuture<'a, near_crypto::PublicKey> {
+        Box::pin(async move {
+            println!(
+                "Please allow getting the PublicKey on Ledger device (HD Path: {})",
+                self.seed_phrase_hd_path
+            );
+            let public_key = self
+                .device
+                .get_public_key(&self.seed_phrase_hd_path)
+                .await?;
+            Ok(near_crypto::PublicKey::ED25519(
+                near_crypto::ED25519PublicKey::from(public_key.to_bytes()),
+            ))
+        })
+    }
+
+    fn sign<'a>(
+        &'a self,
+        unsigned_transaction: &'a near_primitives::transaction::Transaction,
+    ) -> SignerFuture<'a, near_crypto::Signature> {
+        Box::pin(async move {
+            println!(
+                "Confirm transaction signing on your Ledger device (HD Path: {})",
+                self.seed_phrase_hd_path
+            );
+            let signature = self
+                .device
+                .sign_transaction(
+                    unsigned_transaction
+                        .try_to_vec()
+                        .expect("Transaction is not expected to fail on serialization"),
+                    &self.seed_phrase_hd_path,
+                )
+                .await?;
+            Ok(
+                near_crypto::Signature::from_parts(near_crypto::KeyType::ED25519, &signature)
+                    .expect("Signature is not expected to fail on deserialization"),
+            )
+        })
+    }
+}
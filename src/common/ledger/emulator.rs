@@ -0,0 +1,294 @@
+use std::str::FromStr;
+
+use super::{LedgerDevice, SignerFuture};
+
+// APDU protocol of the NEAR Ledger app
+const LEDGER_CLA: u8 = 0x80;
+const LEDGER_INS_GET_PUBLIC_KEY: u8 = 4;
+const LEDGER_INS_SIGN_TRANSACTION: u8 = 2;
+const LEDGER_P1_GET_PUBLIC_KEY_DISPLAY: u8 = 0;
+const LEDGER_P1_SIGN_NORMAL: u8 = 0;
+const LEDGER_P1_SIGN_NORMAL_LAST_CHUNK: u8 = 0x80;
+const LEDGER_NETWORK_ID: u8 = b'W';
+const LEDGER_CHUNK_SIZE: usize = 128;
+/// The app expects the HD path of the signing key to be 5 levels deep (44'/397'/0'/0'/1').
+const LEDGER_SIGN_HD_PATH_DEPTH: usize = 5;
+const LEDGER_SW_OK: u16 = 0x9000;
+const LEDGER_SW_USER_REJECTED: u16 = 0x6985;
+const LEDGER_SW_WRONG_DATA: u16 = 0x6a80;
+const LEDGER_SW_INS_NOT_SUPPORTED: u16 = 0x6d00;
+
+fn ledger_apdu_command(ins: u8, p1: u8, data: &[u8]) -> Vec<u8> {
+    let mut command = vec![LEDGER_CLA, ins, p1, LEDGER_NETWORK_ID, data.len() as u8];
+    command.extend_from_slice(data);
+    command
+}
+
+fn ledger_hd_path_to_bytes(seed_phrase_hd_path: &slip10::BIP32Path) -> Vec<u8> {
+    let mut hd_path_bytes = vec![];
+    for level in 0..seed_phrase_hd_path.depth() {
+        hd_path_bytes.extend_from_slice(
+            &seed_phrase_hd_path
+                .index(level)
+                .expect("The level is within the depth of the HD path")
+                .to_be_bytes(),
+        );
+    }
+    hd_path_bytes
+}
+
+/// Splits the APDU response into the data and the status word.
+fn ledger_apdu_response(response: Vec<u8>) -> color_eyre::eyre::Result<Vec<u8>> {
+    if response.len() < 2 {
+        return Err(color_eyre::Report::msg(
+            "The Ledger device returned a truncated response",
+        ));
+    }
+    let (data, status_word) = response.split_at(response.len() - 2);
+    match u16::from_be_bytes([status_word[0], status_word[1]]) {
+        LEDGER_SW_OK => Ok(data.to_vec()),
+        LEDGER_SW_USER_REJECTED => Err(color_eyre::Report::msg(
+            "The request was rejected on the Ledger device",
+        )),
+        status_word => Err(color_eyre::Report::msg(format!(
+            "The Ledger device returned the error code {:#06x}",
+            status_word
+        ))),
+    }
+}
+
+/// A software Ledger device with the NEAR app. It derives the keys from a seed phrase and
+/// handles the same APDU commands as the hardware device, so the Ledger flows can run
+/// without one. In builds with the `ledger-emulator` feature, set
+/// `NEAR_CLI_LEDGER_EMULATOR_SEED_PHRASE` to use it instead of the device (and
+/// `NEAR_CLI_LEDGER_EMULATOR_REJECT` to reject every signing request).
+pub struct LedgerEmulator {
+    master_seed: Vec<u8>,
+    reject_signing: bool,
+    sign_buffer: std::cell::RefCell<Vec<u8>>,
+}
+
+impl LedgerEmulator {
+    pub fn new(master_seed_phrase: &str, reject_signing: bool) -> color_eyre::eyre::Result<Self> {
+        Ok(Self {
+            master_seed: bip39::Mnemonic::parse(master_seed_phrase)?
+                .to_seed("")
+                .to_vec(),
+            reject_signing,
+            sign_buffer: Default::default(),
+        })
+    }
+
+    #[cfg(feature = "ledger-emulator")]
+    pub fn from_env() -> color_eyre::eyre::Result<Option<Self>> {
+        match std::env::var(crate::consts::LEDGER_EMULATOR_SEED_PHRASE_ENV_VAR) {
+            Ok(master_seed_phrase) => Ok(Some(Self::new(
+                &master_seed_phrase,
+                std::env::var_os(crate::consts::LEDGER_EMULATOR_REJECT_ENV_VAR).is_some(),
+            )?)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Handles a single APDU command and returns the response data followed by the status word.
+    pub fn exchange(&self, command: &[u8]) -> Vec<u8> {
+        let (mut response, status_word) = match self.handle_command(command) {
+            Ok(data) => (data, LEDGER_SW_OK),
+            Err(status_word) => (vec![], status_word),
+        };
+        response.extend_from_slice(&status_word.to_be_bytes());
+        response
+    }
+
+    fn handle_command(&self, command: &[u8]) -> Result<Vec<u8>, u16> {
+        if command.len() < 5
+            || command[0] != LEDGER_CLA
+            || command[3] != LEDGER_NETWORK_ID
+            || command.len() != 5 + command[4] as usize
+        {
+            return Err(LEDGER_SW_WRONG_DATA);
+        }
+        let data = &command[5..];
+        match command[1] {
+            LEDGER_INS_GET_PUBLIC_KEY => Ok(self.derive_keypair(data)?.public.to_bytes().to_vec()),
+            LEDGER_INS_SIGN_TRANSACTION => {
+                let mut sign_buffer = self.sign_buffer.borrow_mut();
+                sign_buffer.extend_from_slice(data);
+                if command[2] != LEDGER_P1_SIGN_NORMAL_LAST_CHUNK {
+                    return Ok(vec![]);
+                }
+                let sign_data = std::mem::take(&mut *sign_buffer);
+                if sign_data.len() < LEDGER_SIGN_HD_PATH_DEPTH * 4 {
+                    return Err(LEDGER_SW_WRONG_DATA);
+                }
+                let (hd_path_bytes, payload) = sign_data.split_at(LEDGER_SIGN_HD_PATH_DEPTH * 4);
+                let keypair = self.derive_keypair(hd_path_bytes)?;
+                if self.reject_signing {
+                    return Err(LEDGER_SW_USER_REJECTED);
+                }
+                use ed25519_dalek::Signer;
+                Ok(keypair
+                    .sign(near_primitives::hash::hash(payload).as_ref())
+                    .to_bytes()
+                    .to_vec())
+            }
+            _ => Err(LEDGER_SW_INS_NOT_SUPPORTED),
+        }
+    }
+
+    /// Sends the HD path followed by the data to sign in chunks, the way the host does it.
+    fn exchange_signing(
+        &self,
+        ins: u8,
+        data: Vec<u8>,
+        seed_phrase_hd_path: &slip10::BIP32Path,
+    ) -> color_eyre::eyre::Result<Vec<u8>> {
+        let mut sign_data = ledger_hd_path_to_bytes(seed_phrase_hd_path);
+        sign_data.extend(data);
+        let chunks = sign_data.chunks(LEDGER_CHUNK_SIZE).collect::<Vec<_>>();
+        let mut signature = vec![];
+        for (chunk_index, chunk) in chunks.iter().enumerate() {
+            let p1 = if chunk_index + 1 == chunks.len() {
+                LEDGER_P1_SIGN_NORMAL_LAST_CHUNK
+            } else {
+                LEDGER_P1_SIGN_NORMAL
+            };
+            signature = ledger_apdu_response(self.exchange(&ledger_apdu_command(ins, p1, chunk)))?;
+        }
+        Ok(signature)
+    }
+
+    fn derive_keypair(&self, hd_path_bytes: &[u8]) -> Result<ed25519_dalek::Keypair, u16> {
+        if hd_path_bytes.is_empty() || hd_path_bytes.len() % 4 != 0 {
+            return Err(LEDGER_SW_WRONG_DATA);
+        }
+        let hd_path_levels = hd_path_bytes
+            .chunks(4)
+            .map(|level| {
+                let index = u32::from_be_bytes([level[0], level[1], level[2], level[3]]);
+                if index >= 0x8000_0000 {
+                    format!("{}'", index - 0x8000_0000)
+                } else {
+                    index.to_string()
+                }
+            })
+            .collect::<Vec<_>>();
+        let seed_phrase_hd_path =
+            slip10::BIP32Path::from_str(&format!("m/{}", hd_path_levels.join("/")))
+                .map_err(|_| LEDGER_SW_WRONG_DATA)?;
+        let derived_private_key = slip10::derive_key_from_path(
+            &self.master_seed,
+            slip10::Curve::Ed25519,
+            &seed_phrase_hd_path,
+        )
+        .map_err(|_| LEDGER_SW_WRONG_DATA)?;
+        let secret = ed25519_dalek::SecretKey::from_bytes(&derived_private_key.key)
+            .map_err(|_| LEDGER_SW_WRONG_DATA)?;
+        let public = ed25519_dalek::PublicKey::from(&secret);
+        Ok(ed25519_dalek::Keypair { secret, public })
+    }
+}
+
+impl LedgerDevice for LedgerEmulator {
+    fn get_public_key<'a>(
+        &'a self,
+        seed_phrase_hd_path: &'a slip10::BIP32Path,
+    ) -> SignerFuture<'a, ed25519_dalek::PublicKey> {
+        Box::pin(async move {
+            let public_key = ledger_apdu_response(self.exchange(&ledger_apdu_command(
+                LEDGER_INS_GET_PUBLIC_KEY,
+                LEDGER_P1_GET_PUBLIC_KEY_DISPLAY,
+                &ledger_hd_path_to_bytes(seed_phrase_hd_path),
+            )))?;
+            Ok(ed25519_dalek::PublicKey::from_bytes(&public_key)?)
+        })
+    }
+
+    fn sign_transaction<'a>(
+        &'a self,
+        transaction: Vec<u8>,
+        seed_phrase_hd_path: &'a slip10::BIP32Path,
+    ) -> SignerFuture<'a, Vec<u8>> {
+        Box::pin(async move {
+            self.exchange_signing(
+                LEDGER_INS_SIGN_TRANSACTION,
+                transaction,
+                seed_phrase_hd_path,
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tests::{emulated_ledger_signer, LEDGER_EMULATOR_SEED_PHRASE};
+    use crate::common::{get_key_pair_properties_from_seed_phrase, KeyType, TransactionSigner};
+
+    fn transaction_calling(method_name: &str) -> near_primitives::transaction::Transaction {
+        near_primitives::transaction::Transaction {
+            signer_id: "alice.testnet".parse().unwrap(),
+            public_key: near_crypto::PublicKey::empty(near_crypto::KeyType::ED25519),
+            nonce: 0,
+            receiver_id: "contract.testnet".parse().unwrap(),
+            block_hash: Default::default(),
+            actions: vec![near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: method_name.to_owned(),
+                    args: vec![],
+                    gas: 10u64.pow(12),
+                    deposit: 0,
+                },
+            )],
+        }
+    }
+
+    #[test]
+    fn ledger_emulator_provides_public_key_for_hd_path() {
+        for seed_phrase_hd_path in &["44'/397'/0'/0'/1'", "44'/397'/0'/0'/2'"] {
+            let ledger_signer = emulated_ledger_signer(seed_phrase_hd_path, false);
+            let public_key = actix::System::new()
+                .block_on(ledger_signer.public_key())
+                .unwrap();
+            let key_pair_properties = get_key_pair_properties_from_seed_phrase(
+                ledger_signer.seed_phrase_hd_path.clone(),
+                LEDGER_EMULATOR_SEED_PHRASE.to_owned(),
+                "",
+                KeyType::Ed25519,
+            )
+            .unwrap();
+            assert_eq!(public_key.to_string(), key_pair_properties.public_key_str);
+        }
+    }
+
+    #[test]
+    fn ledger_emulator_signs_transaction_in_chunks() {
+        let ledger_signer = emulated_ledger_signer("44'/397'/0'/0'/1'", false);
+        let public_key = actix::System::new()
+            .block_on(ledger_signer.public_key())
+            .unwrap();
+        let unsigned_transaction = near_primitives::transaction::Transaction {
+            public_key: public_key.clone(),
+            ..transaction_calling(&"a".repeat(3 * LEDGER_CHUNK_SIZE))
+        };
+        let signature = actix::System::new()
+            .block_on(ledger_signer.sign(&unsigned_transaction))
+            .unwrap();
+        assert!(signature.verify(
+            unsigned_transaction.get_hash_and_size().0.as_ref(),
+            &public_key
+        ));
+    }
+
+    #[test]
+    fn ledger_emulator_rejects_signing() {
+        let ledger_signer = emulated_ledger_signer("44'/397'/0'/0'/1'", true);
+        let error = actix::System::new()
+            .block_on(ledger_signer.sign(&transaction_calling("set_greeting")))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The request was rejected on the Ledger device"
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use near_primitives::borsh::BorshSerialize;
+
+/// `2^31 + 413`, prepended to NEP-413 payloads so they can never be a valid transaction.
+pub const NEP413_TAG: u32 = (1 << 31) + 413;
+
+/// An off-chain message signed according to NEP-413.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Nep413Payload {
+    pub message: String,
+    pub nonce: [u8; 32],
+    pub recipient: String,
+    pub callback_url: Option<String>,
+}
+
+impl Nep413Payload {
+    /// The borsh-serialized payload (without the tag).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        (
+            self.message.clone(),
+            self.nonce,
+            self.recipient.clone(),
+            self.callback_url.clone(),
+        )
+            .try_to_vec()
+            .expect("NEP-413 payload is not expected to fail on serialization")
+    }
+
+    /// The hash that is signed: sha256 of the borsh-serialized tag followed by the payload.
+    pub fn hash(&self) -> near_primitives::hash::CryptoHash {
+        let mut bytes = NEP413_TAG
+            .try_to_vec()
+            .expect("NEP-413 tag is not expected to fail on serialization");
+        bytes.extend(self.to_bytes());
+        near_primitives::hash::hash(&bytes)
+    }
+
+    pub fn verify(
+        &self,
+        signature: &near_crypto::Signature,
+        public_key: &near_crypto::PublicKey,
+    ) -> bool {
+        signature.verify(self.hash().as_ref(), public_key)
+    }
+}
@@ -0,0 +1,192 @@
+use super::ConnectionConfig;
+
+const NONCE_STORE_LOCK_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+const NONCE_STORE_LOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct ReservedNonce {
+    nonce: u64,
+}
+
+/// Hands out increasing nonces of one access key to concurrent near-cli invocations.
+/// The last reserved nonce is kept next to the keychain files and every reservation is made
+/// under an advisory lock of a lock file, so two processes never sign with the same nonce.
+/// The lock is released by the OS when its holder exits, even if it is killed.
+#[derive(Debug, Clone)]
+pub struct NonceStore {
+    path: std::path::PathBuf,
+}
+
+struct NonceStoreLock {
+    file: std::fs::File,
+}
+
+impl Drop for NonceStoreLock {
+    fn drop(&mut self) {
+        let _ = fs2::FileExt::unlock(&self.file);
+    }
+}
+
+impl NonceStore {
+    pub fn new(
+        connection_config: &ConnectionConfig,
+        account_id: &near_primitives::types::AccountId,
+        public_key: &near_crypto::PublicKey,
+    ) -> Self {
+        let mut path = dirs::home_dir().expect("Impossible to get your home dir!");
+        path.push(connection_config.dir_name());
+        path.push(crate::consts::NONCE_STORE_DIR_NAME);
+        path.push(account_id.to_string());
+        path.push(format!("{}.json", public_key.to_string().replace(":", "_")));
+        Self { path }
+    }
+
+    pub fn with_path(path: std::path::PathBuf) -> Self {
+        Self { path }
+    }
+
+    async fn lock(&self) -> color_eyre::eyre::Result<NonceStoreLock> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let lock_path = self.path.with_extension("lock");
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .open(&lock_path)
+            .map_err(|err| {
+                color_eyre::Report::msg(format!(
+                    "Failed to open the nonce store lock {}: {}",
+                    lock_path.display(),
+                    err
+                ))
+            })?;
+        let started_at = std::time::Instant::now();
+        loop {
+            match fs2::FileExt::try_lock_exclusive(&file) {
+                Ok(()) => return Ok(NonceStoreLock { file }),
+                Err(err) if err.kind() == fs2::lock_contended_error().kind() => {
+                    if started_at.elapsed() > NONCE_STORE_LOCK_TIMEOUT {
+                        return Err(color_eyre::Report::msg(format!(
+                            "Timed out waiting for the nonce store lock {}",
+                            lock_path.display()
+                        )));
+                    }
+                    actix::clock::sleep(NONCE_STORE_LOCK_RETRY_INTERVAL).await;
+                }
+                Err(err) => {
+                    return Err(color_eyre::Report::msg(format!(
+                        "Failed to lock the nonce store lock {}: {}",
+                        lock_path.display(),
+                        err
+                    )))
+                }
+            }
+        }
+    }
+
+    /// Reserves the next nonce: it is greater than both the nonce of the access key on chain and
+    /// every nonce reserved before.
+    pub async fn reserve(&self, access_key_nonce: u64) -> color_eyre::eyre::Result<u64> {
+        let _lock = self.lock().await?;
+        let reserved_nonce = match std::fs::read_to_string(&self.path) {
+            Ok(data) => serde_json::from_str::<ReservedNonce>(&data)
+                .map(|reserved_nonce| reserved_nonce.nonce)
+                .unwrap_or_default(),
+            Err(_) => 0,
+        };
+        let nonce = std::cmp::max(reserved_nonce, access_key_nonce) + 1;
+        std::fs::write(&self.path, serde_json::to_string(&ReservedNonce { nonce })?).map_err(
+            |err| {
+                color_eyre::Report::msg(format!(
+                    "Failed to update the nonce store {}: {}",
+                    self.path.display(),
+                    err
+                ))
+            },
+        )?;
+        Ok(nonce)
+    }
+}
+
+/// The node rejected the transaction because its nonce was already used.
+#[derive(Debug)]
+pub struct InvalidNonceError {
+    pub message: String,
+}
+
+impl std::fmt::Display for InvalidNonceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The transaction nonce is invalid: {}", self.message)
+    }
+}
+
+impl std::error::Error for InvalidNonceError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nonce_store_hands_out_unique_nonces_to_concurrent_reservations() {
+        let path = std::env::temp_dir().join(format!(
+            "near-cli-nonce-store-{}/ed25519_test.json",
+            std::process::id()
+        ));
+        let nonce_store = NonceStore::with_path(path.clone());
+        let reserve = |nonce_store: &NonceStore, access_key_nonce| {
+            actix::System::new()
+                .block_on(nonce_store.reserve(access_key_nonce))
+                .unwrap()
+        };
+        assert_eq!(reserve(&nonce_store, 10), 11);
+        assert_eq!(reserve(&nonce_store, 10), 12);
+        assert_eq!(reserve(&nonce_store, 20), 21);
+        let threads = (0..8)
+            .map(|_| {
+                let nonce_store = nonce_store.clone();
+                std::thread::spawn(move || {
+                    (0..5)
+                        .map(|_| reserve(&nonce_store, 20))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut nonces = threads
+            .into_iter()
+            .flat_map(|thread| thread.join().unwrap())
+            .collect::<Vec<_>>();
+        nonces.sort();
+        assert_eq!(nonces, (22..62).collect::<Vec<_>>());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn nonce_store_waits_for_the_lock_holder_only() {
+        let path = std::env::temp_dir().join(format!(
+            "near-cli-nonce-store-lock-{}/ed25519_test.json",
+            std::process::id()
+        ));
+        let nonce_store = NonceStore::with_path(path.clone());
+        let lock = actix::System::new().block_on(nonce_store.lock()).unwrap();
+        let waiting_nonce_store = nonce_store.clone();
+        let waiting_thread = std::thread::spawn(move || {
+            actix::System::new()
+                .block_on(waiting_nonce_store.reserve(5))
+                .unwrap()
+        });
+        std::thread::sleep(4 * NONCE_STORE_LOCK_RETRY_INTERVAL);
+        assert!(!path.exists());
+        drop(lock);
+        assert_eq!(waiting_thread.join().unwrap(), 6);
+        // The lock file stays behind, but without a holder it does not block anyone.
+        assert!(path.with_extension("lock").exists());
+        assert_eq!(
+            actix::System::new()
+                .block_on(nonce_store.reserve(5))
+                .unwrap(),
+            7
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
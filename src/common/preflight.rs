@@ -0,0 +1,467 @@
+use super::review::{fetch_account_view, fetch_runtime_config, is_unknown_account_or_access_key};
+use super::{transaction_max_gas, transaction_totals, NearBalance};
+
+/// The chain state and the protocol config a transaction is checked against before it is sent.
+#[derive(Debug, Clone)]
+pub struct PreflightState {
+    pub gas_price: near_primitives::types::Balance,
+    pub signer_account: Option<near_primitives::views::AccountView>,
+    pub access_key: Option<near_primitives::views::AccessKeyView>,
+    pub receiver_account: Option<near_primitives::views::AccountView>,
+    /// Size of the contract currently deployed on the receiver account
+    pub receiver_code_size: u64,
+    pub runtime_config: near_primitives::runtime::config::RuntimeConfig,
+}
+
+impl PreflightState {
+    pub async fn fetch(
+        json_rpc_client: &near_jsonrpc_client::JsonRpcClient,
+        transaction: &near_primitives::transaction::Transaction,
+    ) -> color_eyre::eyre::Result<Self> {
+        let runtime_config = fetch_runtime_config(json_rpc_client).await?;
+        let gas_price = json_rpc_client
+            .gas_price(None)
+            .await
+            .map_err(|err| {
+                color_eyre::Report::msg(format!("Failed to fetch the gas price: {:?}", err))
+            })?
+            .gas_price;
+        let signer_account = fetch_account_view(json_rpc_client, &transaction.signer_id).await?;
+        let access_key = match json_rpc_client
+            .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
+                block_reference: near_primitives::types::Finality::Final.into(),
+                request: near_primitives::views::QueryRequest::ViewAccessKey {
+                    account_id: transaction.signer_id.clone(),
+                    public_key: transaction.public_key.clone(),
+                },
+            })
+            .await
+        {
+            Ok(near_jsonrpc_primitives::types::query::RpcQueryResponse {
+                kind:
+                    near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKey(access_key),
+                ..
+            }) => Some(access_key),
+            Err(err) if is_unknown_account_or_access_key(&err) => None,
+            Ok(_) => {
+                return Err(color_eyre::Report::msg(
+                    "Failed to fetch the access key: unexpected response",
+                ))
+            }
+            Err(err) => {
+                return Err(color_eyre::Report::msg(format!(
+                    "Failed to fetch the access key: {:?}",
+                    err
+                )))
+            }
+        };
+        let receiver_account = if transaction.receiver_id == transaction.signer_id {
+            signer_account.clone()
+        } else {
+            fetch_account_view(json_rpc_client, &transaction.receiver_id).await?
+        };
+        let is_deploy = transaction.actions.iter().any(|action| {
+            matches!(
+                action,
+                near_primitives::transaction::Action::DeployContract(_)
+            )
+        });
+        let has_code = receiver_account
+            .as_ref()
+            .map(|account_view| account_view.code_hash != Default::default())
+            .unwrap_or(false);
+        let receiver_code_size = if is_deploy && has_code {
+            match json_rpc_client
+                .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
+                    block_reference: near_primitives::types::Finality::Final.into(),
+                    request: near_primitives::views::QueryRequest::ViewCode {
+                        account_id: transaction.receiver_id.clone(),
+                    },
+                })
+                .await
+            {
+                Ok(near_jsonrpc_primitives::types::query::RpcQueryResponse {
+                    kind: near_jsonrpc_primitives::types::query::QueryResponseKind::ViewCode(code),
+                    ..
+                }) => code.code.len() as u64,
+                Ok(_) => {
+                    return Err(color_eyre::Report::msg(
+                        "Failed to fetch the contract code: unexpected response",
+                    ))
+                }
+                Err(err) => {
+                    return Err(color_eyre::Report::msg(format!(
+                        "Failed to fetch the contract code: {:?}",
+                        err
+                    )))
+                }
+            }
+        } else {
+            0
+        };
+        Ok(Self {
+            gas_price,
+            signer_account,
+            access_key,
+            receiver_account,
+            receiver_code_size,
+            runtime_config,
+        })
+    }
+}
+
+/// Returns the total deposit, the total stake and the most the gas can cost, or `None` if any of
+/// them overflows.
+fn transaction_costs(
+    transaction: &near_primitives::transaction::Transaction,
+    state: &PreflightState,
+) -> Option<(
+    near_primitives::types::Balance,
+    near_primitives::types::Balance,
+    near_primitives::types::Balance,
+)> {
+    let (total_deposit, total_stake, _) = transaction_totals(transaction)?;
+    let max_gas = transaction_max_gas(transaction, &state.runtime_config.transaction_costs)?;
+    Some((
+        total_deposit,
+        total_stake,
+        (max_gas as u128).checked_mul(state.gas_price)?,
+    ))
+}
+
+/// Predicts the errors the network would reject or fail the transaction with, named after the
+/// protocol errors, from the chain state and the protocol config.
+pub fn preflight_failures(
+    transaction: &near_primitives::transaction::Transaction,
+    state: &PreflightState,
+) -> Vec<String> {
+    let mut failures = vec![];
+    let (total_deposit, total_stake, max_gas_cost) = match transaction_costs(transaction, state) {
+        Some(costs) => costs,
+        None => {
+            failures.push(
+                "CostOverflow: the deposits, stakes or gas of the transaction overflow".to_owned(),
+            );
+            return failures;
+        }
+    };
+    let storage_amount_per_byte = state.runtime_config.storage_amount_per_byte;
+
+    for (index, action) in transaction.actions.iter().enumerate() {
+        match action {
+            near_primitives::transaction::Action::DeleteAccount(_)
+                if index + 1 != transaction.actions.len() =>
+            {
+                failures.push(
+                    "DeleteActionMustBeFinal: DeleteAccount has to be the last action of the transaction"
+                        .to_owned(),
+                );
+            }
+            near_primitives::transaction::Action::DeployContract(deploy_contract_action)
+                if deploy_contract_action.code.len() as u64
+                    > state
+                        .runtime_config
+                        .wasm_config
+                        .limit_config
+                        .max_contract_size =>
+            {
+                failures.push(format!(
+                    "ContractSizeExceeded: the contract is {} bytes, the limit is {} bytes",
+                    deploy_contract_action.code.len(),
+                    state
+                        .runtime_config
+                        .wasm_config
+                        .limit_config
+                        .max_contract_size
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    let signer_account = match &state.signer_account {
+        Some(signer_account) => signer_account,
+        None => {
+            failures.push(format!(
+                "SignerDoesNotExist: the account <{}> does not exist",
+                transaction.signer_id
+            ));
+            return failures;
+        }
+    };
+    match &state.access_key {
+        None => failures.push(format!(
+            "AccessKeyNotFound: <{}> has no access key {}",
+            transaction.signer_id, transaction.public_key
+        )),
+        Some(access_key) => {
+            if let near_primitives::views::AccessKeyPermissionView::FunctionCall {
+                allowance,
+                receiver_id,
+                method_names,
+            } = &access_key.permission
+            {
+                match transaction.actions.as_slice() {
+                    [near_primitives::transaction::Action::FunctionCall(function_call_action)] => {
+                        if transaction.receiver_id.to_string() != *receiver_id {
+                            failures.push(format!(
+                                "ReceiverMismatch: the access key only allows calls to <{}>, not <{}>",
+                                receiver_id, transaction.receiver_id
+                            ));
+                        }
+                        if !method_names.is_empty()
+                            && !method_names.contains(&function_call_action.method_name)
+                        {
+                            failures.push(format!(
+                                "MethodNameMismatch: the access key does not allow calling {} (allowed: {})",
+                                function_call_action.method_name,
+                                method_names.join(", ")
+                            ));
+                        }
+                        if function_call_action.deposit > 0 {
+                            failures.push(
+                                "DepositWithFunctionCall: a function call access key cannot attach a deposit"
+                                    .to_owned(),
+                            );
+                        }
+                    }
+                    _ => failures.push(
+                        "RequiresFullAccess: a function call access key can only sign a single function call"
+                            .to_owned(),
+                    ),
+                }
+                if let Some(allowance) = allowance {
+                    if *allowance < max_gas_cost {
+                        failures.push(format!(
+                            "NotEnoughAllowance: the access key has {} of allowance left and the gas may cost up to {}",
+                            NearBalance::from_yoctonear(*allowance),
+                            NearBalance::from_yoctonear(max_gas_cost)
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    let total_cost = match total_deposit.checked_add(max_gas_cost) {
+        Some(total_cost) => total_cost,
+        None => {
+            failures.push(
+                "CostOverflow: the deposits and the gas of the transaction overflow".to_owned(),
+            );
+            return failures;
+        }
+    };
+    let signer_balance = match signer_account.amount.checked_sub(total_cost) {
+        Some(signer_balance) => signer_balance,
+        None => {
+            failures.push(format!(
+                "NotEnoughBalance: <{}> has {} and the transaction costs up to {}",
+                transaction.signer_id,
+                NearBalance::from_yoctonear(signer_account.amount),
+                NearBalance::from_yoctonear(total_cost)
+            ));
+            return failures;
+        }
+    };
+    if total_stake > signer_balance + signer_account.locked {
+        failures.push(format!(
+            "TriesToStake: <{}> cannot stake {} with {} available",
+            transaction.signer_id,
+            NearBalance::from_yoctonear(total_stake),
+            NearBalance::from_yoctonear(signer_balance + signer_account.locked)
+        ));
+    }
+
+    let new_code_size = transaction
+        .actions
+        .iter()
+        .filter_map(|action| match action {
+            near_primitives::transaction::Action::DeployContract(deploy_contract_action) => {
+                Some(deploy_contract_action.code.len() as u64)
+            }
+            _ => None,
+        })
+        .last();
+    let storage_after_deploy = |storage_usage: u64| match new_code_size {
+        Some(new_code_size) => {
+            storage_usage.saturating_sub(state.receiver_code_size) + new_code_size
+        }
+        None => storage_usage,
+    };
+    let is_receiver_signer = transaction.receiver_id == transaction.signer_id;
+    let signer_storage_usage = if is_receiver_signer {
+        storage_after_deploy(signer_account.storage_usage)
+    } else {
+        signer_account.storage_usage
+    };
+    let signer_state_cost = signer_storage_usage as u128 * storage_amount_per_byte;
+    if signer_balance + signer_account.locked < signer_state_cost {
+        failures.push(format!(
+            "LackBalanceForState: <{}> would keep {} but needs {} to cover its storage of {} bytes",
+            transaction.signer_id,
+            NearBalance::from_yoctonear(signer_balance + signer_account.locked),
+            NearBalance::from_yoctonear(signer_state_cost),
+            signer_storage_usage
+        ));
+    }
+    if !is_receiver_signer && new_code_size.is_some() {
+        let transfers: near_primitives::types::Balance = transaction
+            .actions
+            .iter()
+            .filter_map(|action| match action {
+                near_primitives::transaction::Action::Transfer(transfer_action) => {
+                    Some(transfer_action.deposit)
+                }
+                _ => None,
+            })
+            .sum();
+        let (receiver_balance, receiver_storage_usage) = match &state.receiver_account {
+            Some(receiver_account) => (
+                receiver_account.amount + receiver_account.locked + transfers,
+                storage_after_deploy(receiver_account.storage_usage),
+            ),
+            None => (
+                transfers,
+                storage_after_deploy(
+                    state
+                        .runtime_config
+                        .transaction_costs
+                        .storage_usage_config
+                        .num_bytes_account,
+                ),
+            ),
+        };
+        let receiver_state_cost = receiver_storage_usage as u128 * storage_amount_per_byte;
+        if receiver_balance < receiver_state_cost {
+            failures.push(format!(
+                "LackBalanceForState: <{}> would have {} but needs {} to cover its storage of {} bytes with the contract",
+                transaction.receiver_id,
+                NearBalance::from_yoctonear(receiver_balance),
+                NearBalance::from_yoctonear(receiver_state_cost),
+                receiver_storage_usage
+            ));
+        }
+    }
+    failures
+}
+
+/// Runs the pre-flight checks of the signed transaction against the current chain state and
+/// refuses to send it when a failure is predicted or the chain state cannot be fetched, unless
+/// `ignore_preflight_errors` is set.
+pub async fn check_transaction_before_sending(
+    json_rpc_client: &near_jsonrpc_client::JsonRpcClient,
+    transaction: &near_primitives::transaction::Transaction,
+    ignore_preflight_errors: bool,
+) -> crate::CliResult {
+    let state = match PreflightState::fetch(json_rpc_client, transaction).await {
+        Ok(state) => state,
+        Err(err) if ignore_preflight_errors => {
+            println!(
+                "The pre-flight checks are skipped (--ignore-preflight-errors): {}",
+                err
+            );
+            return Ok(());
+        }
+        Err(err) => {
+            return Err(color_eyre::Report::msg(format!(
+                "The transaction is not sent: the pre-flight checks failed to fetch the chain state: {}. Use --ignore-preflight-errors to send it anyway",
+                err
+            )))
+        }
+    };
+    let failures = preflight_failures(transaction, &state);
+    if failures.is_empty() {
+        return Ok(());
+    }
+    println!("\nThe pre-flight checks predict that the transaction fails:");
+    for failure in failures.iter() {
+        println!(" - {}", failure);
+    }
+    if ignore_preflight_errors {
+        println!("The transaction is sent anyway (--ignore-preflight-errors).");
+        return Ok(());
+    }
+    Err(color_eyre::Report::msg(format!(
+        "The transaction is not sent: {} predicted failure(s). Use --ignore-preflight-errors to send it anyway",
+        failures.len()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call_transaction(
+        method_name: &str,
+        deposit: near_primitives::types::Balance,
+    ) -> near_primitives::transaction::Transaction {
+        near_primitives::transaction::Transaction {
+            signer_id: "alice.testnet".parse().unwrap(),
+            public_key: near_crypto::PublicKey::empty(near_crypto::KeyType::ED25519),
+            nonce: 0,
+            receiver_id: "contract.testnet".parse().unwrap(),
+            block_hash: Default::default(),
+            actions: vec![near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: method_name.to_owned(),
+                    args: vec![],
+                    gas: 10u64.pow(12),
+                    deposit,
+                },
+            )],
+        }
+    }
+
+    #[test]
+    fn preflight_failures_are_all_reported() {
+        let mut state = PreflightState {
+            gas_price: 100_000_000,
+            signer_account: Some(near_primitives::views::AccountView {
+                amount: 10u128.pow(24),
+                locked: 0,
+                code_hash: Default::default(),
+                storage_usage: 182,
+                storage_paid_at: 0,
+            }),
+            access_key: Some(near_primitives::views::AccessKeyView {
+                nonce: 0,
+                permission: near_primitives::views::AccessKeyPermissionView::FunctionCall {
+                    allowance: None,
+                    receiver_id: "contract.testnet".to_string(),
+                    method_names: vec!["set_greeting".to_string()],
+                },
+            }),
+            receiver_account: None,
+            receiver_code_size: 0,
+            runtime_config: near_primitives::runtime::config::RuntimeConfig::default(),
+        };
+        assert!(preflight_failures(&call_transaction("set_greeting", 0), &state).is_empty());
+        let failures = preflight_failures(&call_transaction("get_greeting", 0), &state);
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].starts_with("MethodNameMismatch"));
+
+        let mut transaction = call_transaction("set_greeting", 2 * 10u128.pow(24));
+        transaction.actions.insert(
+            0,
+            near_primitives::transaction::Action::DeleteAccount(
+                near_primitives::transaction::DeleteAccountAction {
+                    beneficiary_id: "bob.testnet".parse().unwrap(),
+                },
+            ),
+        );
+        let failures = preflight_failures(&transaction, &state);
+        assert!(failures[0].starts_with("DeleteActionMustBeFinal"));
+        assert!(failures[1].starts_with("RequiresFullAccess"));
+        assert!(failures[2].starts_with("NotEnoughBalance"));
+
+        state.access_key = None;
+        let failures = preflight_failures(&call_transaction("set_greeting", 0), &state);
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].starts_with("AccessKeyNotFound"));
+
+        let failures = preflight_failures(&call_transaction("set_greeting", u128::MAX), &state);
+        assert_eq!(failures.len(), 2);
+        assert!(failures[1].starts_with("CostOverflow"));
+    }
+}
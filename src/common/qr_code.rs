@@ -0,0 +1,111 @@
+/// Maximum number of payload characters in one QR code; longer payloads are split into parts
+/// so that every code stays readable by a phone camera.
+pub const QR_CODE_MAX_PART_LENGTH: usize = 400;
+
+/// Splits the payload into QR code contents. A payload that does not fit into one code is encoded
+/// as numbered parts `NEARQR:<part>/<total>:<chunk>`, where `<part>` starts with 1.
+pub fn split_into_qr_code_parts(payload: &str, max_part_length: usize) -> Vec<String> {
+    if payload.len() <= max_part_length {
+        return vec![payload.to_owned()];
+    }
+    let chunks: Vec<String> = payload
+        .chars()
+        .collect::<Vec<char>>()
+        .chunks(max_part_length)
+        .map(|chunk| chunk.iter().collect())
+        .collect();
+    let total = chunks.len();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, chunk)| format!("NEARQR:{}/{}:{}", index + 1, total, chunk))
+        .collect()
+}
+
+/// Renders the payload as QR codes in the terminal.
+pub fn print_qr_code(payload: &str) -> crate::CliResult {
+    let parts = split_into_qr_code_parts(payload, QR_CODE_MAX_PART_LENGTH);
+    for (index, part) in parts.iter().enumerate() {
+        if parts.len() > 1 {
+            println!("\nQR code {} of {}:", index + 1, parts.len());
+        }
+        let qr_code = qrcode::QrCode::new(part.as_bytes())?;
+        println!(
+            "{}",
+            qr_code
+                .render::<qrcode::render::unicode::Dense1x2>()
+                .dark_color(qrcode::render::unicode::Dense1x2::Light)
+                .light_color(qrcode::render::unicode::Dense1x2::Dark)
+                .build()
+        );
+    }
+    Ok(())
+}
+
+/// Renders the payload as an SVG document with a QR code.
+pub fn qr_code_to_svg(payload: &str) -> color_eyre::eyre::Result<String> {
+    Ok(qrcode::QrCode::new(payload.as_bytes())?
+        .render::<qrcode::render::svg::Color>()
+        .min_dimensions(256, 256)
+        .build())
+}
+
+/// Saves the payload as QR codes to a PNG or SVG file, depending on the file extension.
+/// Parts of a multi-part payload are saved next to it as `<name>-<part>.<extension>`.
+pub fn save_qr_code(payload: &str, path: &std::path::Path) -> crate::CliResult {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_lowercase())
+        .unwrap_or_default();
+    if extension != "png" && extension != "svg" {
+        return Err(color_eyre::Report::msg(format!(
+            "Only .png and .svg QR code files are supported, got {}",
+            path.display()
+        )));
+    }
+    let parts = split_into_qr_code_parts(payload, QR_CODE_MAX_PART_LENGTH);
+    for (index, part) in parts.iter().enumerate() {
+        let part_path = if parts.len() > 1 {
+            path.with_file_name(format!(
+                "{}-{}.{}",
+                path.file_stem()
+                    .and_then(|file_stem| file_stem.to_str())
+                    .unwrap_or_default(),
+                index + 1,
+                extension
+            ))
+        } else {
+            path.to_path_buf()
+        };
+        if extension == "svg" {
+            std::fs::write(&part_path, qr_code_to_svg(part)?)?;
+        } else {
+            qrcode::QrCode::new(part.as_bytes())?
+                .render::<image::Luma<u8>>()
+                .min_dimensions(256, 256)
+                .build()
+                .save(&part_path)?;
+        }
+        println!("The QR code is saved in a file {}", part_path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_into_qr_code_parts_numbers_long_payloads() {
+        assert_eq!(split_into_qr_code_parts("abc", 3), vec!["abc".to_owned()]);
+        assert_eq!(
+            split_into_qr_code_parts("abcdefg", 3),
+            vec![
+                "NEARQR:1/3:abc".to_owned(),
+                "NEARQR:2/3:def".to_owned(),
+                "NEARQR:3/3:g".to_owned(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use std::str::FromStr;
+
+use super::{derive_key_pair_from_seed, KeyPairProperties, KeyType, HARDENED_INDEX_BOUND};
+
+/// Provides the public keys of an account's access keys, so the account recovery can run
+/// against the RPC server or a mock.
+pub trait AccessKeyListSource {
+    fn view_access_key_list<'a>(
+        &'a self,
+        account_id: &'a near_primitives::types::AccountId,
+    ) -> std::pin::Pin<
+        Box<
+            dyn std::future::Future<Output = color_eyre::eyre::Result<Vec<near_crypto::PublicKey>>>
+                + 'a,
+        >,
+    >;
+}
+
+impl AccessKeyListSource for near_jsonrpc_client::JsonRpcClient {
+    fn view_access_key_list<'a>(
+        &'a self,
+        account_id: &'a near_primitives::types::AccountId,
+    ) -> std::pin::Pin<
+        Box<
+            dyn std::future::Future<Output = color_eyre::eyre::Result<Vec<near_crypto::PublicKey>>>
+                + 'a,
+        >,
+    > {
+        Box::pin(async move {
+            let query_view_method_response = self
+                .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
+                    block_reference: near_primitives::types::Finality::Final.into(),
+                    request: near_primitives::views::QueryRequest::ViewAccessKeyList {
+                        account_id: account_id.clone(),
+                    },
+                })
+                .await
+                .map_err(|err| {
+                    color_eyre::Report::msg(format!(
+                        "Failed to fetch query for view key list: {:?}",
+                        err
+                    ))
+                })?;
+            if let near_jsonrpc_primitives::types::query::QueryResponseKind::AccessKeyList(
+                access_key_view,
+            ) = query_view_method_response.kind
+            {
+                Ok(access_key_view
+                    .keys
+                    .into_iter()
+                    .map(|access_key| access_key.public_key)
+                    .collect())
+            } else {
+                Err(color_eyre::Report::msg("Error call result"))
+            }
+        })
+    }
+}
+
+/// A key derived from the seed phrase that is an access key of the account.
+#[derive(Debug, Clone)]
+pub struct RecoveredAccessKey {
+    pub seed_phrase_passphrase: String,
+    pub key_pair_properties: KeyPairProperties,
+}
+
+/// Derives the keys at `m/44'/397'/N'` for every N in `account_indexes` and every passphrase
+/// candidate, and returns those that are access keys of the account. Every N must be below 2^31.
+pub async fn recover_access_keys_from_seed_phrase(
+    access_key_list_source: &impl AccessKeyListSource,
+    account_id: &near_primitives::types::AccountId,
+    master_seed_phrase: &str,
+    seed_phrase_passphrases: &[String],
+    account_indexes: std::ops::Range<u32>,
+    key_type: KeyType,
+) -> color_eyre::eyre::Result<Vec<RecoveredAccessKey>> {
+    if account_indexes.end > HARDENED_INDEX_BOUND {
+        return Err(color_eyre::Report::msg(format!(
+            "The account indexes {}..{} go beyond 2^31 = {}",
+            account_indexes.start, account_indexes.end, HARDENED_INDEX_BOUND
+        )));
+    }
+    let access_keys = access_key_list_source
+        .view_access_key_list(account_id)
+        .await?;
+    let mnemonic = bip39::Mnemonic::parse(master_seed_phrase)?;
+    let mut recovered_access_keys = vec![];
+    for seed_phrase_passphrase in seed_phrase_passphrases {
+        let master_seed = mnemonic.to_seed(seed_phrase_passphrase);
+        for account_index in account_indexes.clone() {
+            let seed_phrase_hd_path =
+                slip10::BIP32Path::from_str(&format!("m/44'/397'/{}'", account_index))
+                    .map_err(|err| color_eyre::Report::msg(format!("{:?}", err)))?;
+            let (implicit_account_id, public_key_str, secret_keypair_str) =
+                derive_key_pair_from_seed(&master_seed, &seed_phrase_hd_path, key_type)?;
+            let public_key = near_crypto::PublicKey::from_str(&public_key_str)?;
+            if access_keys.contains(&public_key) {
+                recovered_access_keys.push(RecoveredAccessKey {
+                    seed_phrase_passphrase: seed_phrase_passphrase.clone(),
+                    key_pair_properties: KeyPairProperties {
+                        seed_phrase_hd_path,
+                        master_seed_phrase: master_seed_phrase.to_owned(),
+                        implicit_account_id,
+                        public_key_str,
+                        secret_keypair_str,
+                    },
+                });
+            }
+        }
+    }
+    Ok(recovered_access_keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::get_key_pair_properties_from_seed_phrase;
+    use std::str::FromStr;
+
+    struct MockAccessKeyListSource {
+        access_keys: Vec<near_crypto::PublicKey>,
+    }
+
+    impl AccessKeyListSource for MockAccessKeyListSource {
+        fn view_access_key_list<'a>(
+            &'a self,
+            _account_id: &'a near_primitives::types::AccountId,
+        ) -> std::pin::Pin<
+            Box<
+                dyn std::future::Future<
+                        Output = color_eyre::eyre::Result<Vec<near_crypto::PublicKey>>,
+                    > + 'a,
+            >,
+        > {
+            Box::pin(async move { Ok(self.access_keys.clone()) })
+        }
+    }
+
+    #[test]
+    fn recover_access_keys_from_seed_phrase_finds_path_and_passphrase() {
+        let master_seed_phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let account_key = get_key_pair_properties_from_seed_phrase(
+            slip10::BIP32Path::from_str("m/44'/397'/3'").unwrap(),
+            master_seed_phrase.to_owned(),
+            "second",
+            KeyType::Ed25519,
+        )
+        .unwrap();
+        let unrelated_key = get_key_pair_properties_from_seed_phrase(
+            slip10::BIP32Path::from_str("m/44'/397'/3'").unwrap(),
+            master_seed_phrase.to_owned(),
+            "unrelated",
+            KeyType::Ed25519,
+        )
+        .unwrap();
+        let access_key_list_source = MockAccessKeyListSource {
+            access_keys: vec![
+                near_crypto::PublicKey::from_str(&unrelated_key.public_key_str).unwrap(),
+                near_crypto::PublicKey::from_str(&account_key.public_key_str).unwrap(),
+            ],
+        };
+        let recovered_access_keys = actix::System::new()
+            .block_on(recover_access_keys_from_seed_phrase(
+                &access_key_list_source,
+                &near_primitives::types::AccountId::from_str("test.near").unwrap(),
+                master_seed_phrase,
+                &["".to_owned(), "first".to_owned(), "second".to_owned()],
+                0..10,
+                KeyType::Ed25519,
+            ))
+            .unwrap();
+        assert_eq!(recovered_access_keys.len(), 1);
+        assert_eq!(recovered_access_keys[0].seed_phrase_passphrase, "second");
+        assert_eq!(
+            recovered_access_keys[0]
+                .key_pair_properties
+                .seed_phrase_hd_path
+                .to_string(),
+            account_key.seed_phrase_hd_path.to_string()
+        );
+        assert_eq!(
+            recovered_access_keys[0]
+                .key_pair_properties
+                .secret_keypair_str,
+            account_key.secret_keypair_str
+        );
+    }
+
+    #[test]
+    fn recover_access_keys_from_seed_phrase_finds_secp256k1_keys() {
+        let master_seed_phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let account_key = get_key_pair_properties_from_seed_phrase(
+            slip10::BIP32Path::from_str("m/44'/397'/1'").unwrap(),
+            master_seed_phrase.to_owned(),
+            "",
+            KeyType::Secp256k1,
+        )
+        .unwrap();
+        let access_key_list_source = MockAccessKeyListSource {
+            access_keys: vec![
+                near_crypto::PublicKey::from_str(&account_key.public_key_str).unwrap(),
+            ],
+        };
+        let recovered_access_keys = actix::System::new()
+            .block_on(recover_access_keys_from_seed_phrase(
+                &access_key_list_source,
+                &near_primitives::types::AccountId::from_str("test.near").unwrap(),
+                master_seed_phrase,
+                &["".to_owned()],
+                0..3,
+                KeyType::Secp256k1,
+            ))
+            .unwrap();
+        assert_eq!(recovered_access_keys.len(), 1);
+        assert_eq!(
+            recovered_access_keys[0].key_pair_properties.public_key_str,
+            account_key.public_key_str
+        );
+        assert!(recovered_access_keys[0]
+            .key_pair_properties
+            .implicit_account_id
+            .is_none());
+    }
+}
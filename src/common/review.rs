@@ -0,0 +1,495 @@
+use super::{ConnectionConfig, NearBalance, NearGas};
+
+/// Returns the total deposit, stake and prepaid gas attached to the actions of the transaction,
+/// or `None` if any of them overflows.
+pub fn transaction_totals(
+    transaction: &near_primitives::transaction::Transaction,
+) -> Option<(
+    near_primitives::types::Balance,
+    near_primitives::types::Balance,
+    near_primitives::types::Gas,
+)> {
+    transaction
+        .actions
+        .iter()
+        .try_fold(
+            (0u128, 0u128, 0u64),
+            |(deposit, stake, gas), action| match action {
+                near_primitives::transaction::Action::FunctionCall(function_call_action) => Some((
+                    deposit.checked_add(function_call_action.deposit)?,
+                    stake,
+                    gas.checked_add(function_call_action.gas)?,
+                )),
+                near_primitives::transaction::Action::Transfer(transfer_action) => {
+                    Some((deposit.checked_add(transfer_action.deposit)?, stake, gas))
+                }
+                near_primitives::transaction::Action::Stake(stake_action) => {
+                    Some((deposit, stake.checked_add(stake_action.stake)?, gas))
+                }
+                _ => Some((deposit, stake, gas)),
+            },
+        )
+}
+
+/// The error of the transactions whose deposits, stakes or gas do not fit in their types.
+fn transaction_totals_overflow_error() -> color_eyre::Report {
+    color_eyre::Report::msg(
+        "The deposits, stakes or gas attached to the transaction add up to more than fits in a balance",
+    )
+}
+
+/// Returns the most gas the transaction can burn: the prepaid gas plus the send and execution
+/// fees of the receipt and of every action (as if the receiver were another account), or `None`
+/// if it overflows.
+pub fn transaction_max_gas(
+    transaction: &near_primitives::transaction::Transaction,
+    fees: &near_primitives::runtime::fees::RuntimeFeesConfig,
+) -> Option<near_primitives::types::Gas> {
+    let fee = |fee: &near_primitives::runtime::fees::Fee, count: u64| {
+        fee.send_not_sir
+            .checked_add(fee.execution)?
+            .checked_mul(count)
+    };
+    let action_fees = &fees.action_creation_config;
+    transaction.actions.iter().try_fold(
+        fee(&fees.action_receipt_creation_config, 1)?,
+        |gas, action| {
+            gas.checked_add(match action {
+                near_primitives::transaction::Action::CreateAccount(_) => {
+                    fee(&action_fees.create_account_cost, 1)?
+                }
+                near_primitives::transaction::Action::DeployContract(deploy_contract_action) => {
+                    fee(&action_fees.deploy_contract_cost, 1)?.checked_add(fee(
+                        &action_fees.deploy_contract_cost_per_byte,
+                        deploy_contract_action.code.len() as u64,
+                    )?)?
+                }
+                near_primitives::transaction::Action::FunctionCall(function_call_action) => {
+                    fee(&action_fees.function_call_cost, 1)?
+                        .checked_add(fee(
+                            &action_fees.function_call_cost_per_byte,
+                            (function_call_action.method_name.len()
+                                + function_call_action.args.len())
+                                as u64,
+                        )?)?
+                        .checked_add(function_call_action.gas)?
+                }
+                near_primitives::transaction::Action::Transfer(_) => {
+                    fee(&action_fees.transfer_cost, 1)?
+                }
+                near_primitives::transaction::Action::Stake(_) => fee(&action_fees.stake_cost, 1)?,
+                near_primitives::transaction::Action::AddKey(add_key_action) => {
+                    match &add_key_action.access_key.permission {
+                        near_primitives::account::AccessKeyPermission::FullAccess => {
+                            fee(&action_fees.add_key_cost.full_access_cost, 1)?
+                        }
+                        near_primitives::account::AccessKeyPermission::FunctionCall(
+                            function_call_permission,
+                        ) => fee(&action_fees.add_key_cost.function_call_cost, 1)?.checked_add(
+                            fee(
+                                &action_fees.add_key_cost.function_call_cost_per_byte,
+                                function_call_permission
+                                    .method_names
+                                    .iter()
+                                    .map(|method_name| method_name.len() as u64 + 1)
+                                    .sum(),
+                            )?,
+                        )?,
+                    }
+                }
+                near_primitives::transaction::Action::DeleteKey(_) => {
+                    fee(&action_fees.delete_key_cost, 1)?
+                }
+                near_primitives::transaction::Action::DeleteAccount(_) => {
+                    fee(&action_fees.delete_account_cost, 1)?
+                }
+            })
+        },
+    )
+}
+
+/// Describes in plain language the actions of the transaction that are dangerous or hard to
+/// undo. `receiver_code_hash` is the code hash of the receiver account when it is known.
+pub fn transaction_risks(
+    transaction: &near_primitives::transaction::Transaction,
+    receiver_code_hash: Option<&near_primitives::hash::CryptoHash>,
+) -> Vec<String> {
+    let mut risks = vec![];
+    for action in transaction.actions.iter() {
+        match action {
+            near_primitives::transaction::Action::AddKey(add_key_action) => {
+                if let near_primitives::account::AccessKeyPermission::FullAccess =
+                    add_key_action.access_key.permission
+                {
+                    risks.push(format!(
+                        "A full access key {} is added to <{}>: whoever holds its private key gets complete control over the account and its funds.",
+                        add_key_action.public_key, transaction.receiver_id
+                    ));
+                }
+            }
+            near_primitives::transaction::Action::DeleteAccount(delete_account_action) => {
+                risks.push(format!(
+                    "The account <{}> is deleted and its whole remaining balance goes to <{}>. This cannot be undone; if <{}> does not exist, the balance is lost.",
+                    transaction.receiver_id,
+                    delete_account_action.beneficiary_id,
+                    delete_account_action.beneficiary_id
+                ));
+            }
+            near_primitives::transaction::Action::DeployContract(deploy_contract_action) => {
+                let new_code_hash = near_primitives::hash::hash(&deploy_contract_action.code);
+                match receiver_code_hash {
+                    Some(code_hash) if *code_hash == Default::default() => {}
+                    Some(code_hash) => risks.push(format!(
+                        "The contract deployed on <{}> (code hash {}) is replaced with a new one (code hash {}); the state of the old contract is kept as is.",
+                        transaction.receiver_id, code_hash, new_code_hash
+                    )),
+                    None => risks.push(format!(
+                        "A contract (code hash {}) is deployed on <{}>, replacing any contract already deployed there.",
+                        new_code_hash, transaction.receiver_id
+                    )),
+                }
+            }
+            near_primitives::transaction::Action::Stake(stake_action) => {
+                if stake_action.stake == 0 {
+                    risks.push(format!(
+                        "All the NEAR staked by <{}> is unstaked; it is unlocked only after several epochs.",
+                        transaction.receiver_id
+                    ));
+                } else {
+                    risks.push(format!(
+                        "{} of <{}> is staked with the validator key {}: it stays locked while staked and for several epochs after unstaking.",
+                        NearBalance::from_yoctonear(stake_action.stake),
+                        transaction.receiver_id,
+                        stake_action.public_key
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    risks
+}
+
+/// Whether the query failed because the account or the access key does not exist, rather than
+/// because the node could not be reached or could not answer.
+pub(super) fn is_unknown_account_or_access_key(
+    err: &near_jsonrpc_primitives::errors::RpcError,
+) -> bool {
+    let err = format!("{:?}", err);
+    [
+        "UNKNOWN_ACCOUNT",
+        "UNKNOWN_ACCESS_KEY",
+        "does not exist while viewing",
+    ]
+    .iter()
+    .any(|unknown| err.contains(unknown))
+}
+
+/// The account, or `None` when it does not exist; the other RPC errors are returned.
+pub(super) async fn fetch_account_view(
+    json_rpc_client: &near_jsonrpc_client::JsonRpcClient,
+    account_id: &near_primitives::types::AccountId,
+) -> color_eyre::eyre::Result<Option<near_primitives::views::AccountView>> {
+    let query_view_method_response = match json_rpc_client
+        .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
+            block_reference: near_primitives::types::Finality::Final.into(),
+            request: near_primitives::views::QueryRequest::ViewAccount {
+                account_id: account_id.clone(),
+            },
+        })
+        .await
+    {
+        Ok(query_view_method_response) => query_view_method_response,
+        Err(err) if is_unknown_account_or_access_key(&err) => return Ok(None),
+        Err(err) => {
+            return Err(color_eyre::Report::msg(format!(
+                "Failed to fetch the account <{}>: {:?}",
+                account_id, err
+            )))
+        }
+    };
+    match query_view_method_response.kind {
+        near_jsonrpc_primitives::types::query::QueryResponseKind::ViewAccount(account_view) => {
+            Ok(Some(account_view))
+        }
+        _ => Err(color_eyre::Report::msg(format!(
+            "Failed to fetch the account <{}>: unexpected response",
+            account_id
+        ))),
+    }
+}
+
+pub(super) async fn fetch_runtime_config(
+    json_rpc_client: &near_jsonrpc_client::JsonRpcClient,
+) -> color_eyre::eyre::Result<near_primitives::runtime::config::RuntimeConfig> {
+    Ok(json_rpc_client
+        .EXPERIMENTAL_protocol_config(
+            near_jsonrpc_primitives::types::config::RpcProtocolConfigRequest {
+                block_reference: near_primitives::types::Finality::Final.into(),
+            },
+        )
+        .await
+        .map_err(|err| {
+            color_eyre::Report::msg(format!("Failed to fetch the protocol config: {:?}", err))
+        })?
+        .config_view
+        .runtime_config)
+}
+
+/// Shows what signing the transaction will cost and flags its risky actions, then asks to
+/// confirm. With a network connection the gas price, the fees, the balance of the signer and the
+/// contract of the receiver are fetched; offline only the attached amounts and the risks are shown
+/// and the gas is estimated with the default fees.
+pub async fn review_transaction(
+    transaction: &near_primitives::transaction::Transaction,
+    connection_config: Option<&ConnectionConfig>,
+) -> crate::CliResult {
+    let (total_deposit, total_stake, _) =
+        transaction_totals(transaction).ok_or_else(transaction_totals_overflow_error)?;
+    println!("\nTransaction review:\n");
+    println!(
+        "{:<24} {}",
+        "total deposit:",
+        NearBalance::from_yoctonear(total_deposit)
+    );
+    if total_stake > 0 {
+        println!(
+            "{:<24} {}",
+            "total stake:",
+            NearBalance::from_yoctonear(total_stake)
+        );
+    }
+    let receiver_code_hash = match connection_config {
+        None => {
+            let max_gas = transaction_max_gas(
+                transaction,
+                &near_primitives::runtime::fees::RuntimeFeesConfig::default(),
+            )
+            .ok_or_else(transaction_totals_overflow_error)?;
+            println!(
+                "{:<24} {} (the gas price is unknown offline)",
+                "max gas:",
+                NearGas { inner: max_gas }
+            );
+            None
+        }
+        Some(connection_config) => {
+            let json_rpc_client =
+                near_jsonrpc_client::new_client(connection_config.rpc_url().as_str());
+            let max_gas = transaction_max_gas(
+                transaction,
+                &fetch_runtime_config(&json_rpc_client)
+                    .await?
+                    .transaction_costs,
+            )
+            .ok_or_else(transaction_totals_overflow_error)?;
+            let gas_price = json_rpc_client
+                .gas_price(None)
+                .await
+                .map_err(|err| {
+                    color_eyre::Report::msg(format!("Failed to fetch the gas price: {:?}", err))
+                })?
+                .gas_price;
+            let max_gas_cost = (max_gas as u128)
+                .checked_mul(gas_price)
+                .ok_or_else(transaction_totals_overflow_error)?;
+            println!(
+                "{:<24} {} ({} at {} yoctoNEAR per gas)",
+                "max gas cost:",
+                NearBalance::from_yoctonear(max_gas_cost),
+                NearGas { inner: max_gas },
+                gas_price
+            );
+            let signer_account_view =
+                fetch_account_view(&json_rpc_client, &transaction.signer_id).await?;
+            match &signer_account_view {
+                Some(signer_account_view) => {
+                    let spent = total_deposit
+                        .checked_add(max_gas_cost)
+                        .and_then(|spent| {
+                            spent
+                                .checked_add(total_stake.saturating_sub(signer_account_view.locked))
+                        })
+                        .ok_or_else(transaction_totals_overflow_error)?;
+                    match signer_account_view.amount.checked_sub(spent) {
+                        Some(remaining_balance) => println!(
+                            "{:<24} {} (of {} now)",
+                            "balance after (at least):",
+                            NearBalance::from_yoctonear(remaining_balance),
+                            NearBalance::from_yoctonear(signer_account_view.amount)
+                        ),
+                        None => println!(
+                            "{:<24} not enough: <{}> has {} and the transaction may need up to {}",
+                            "balance after:",
+                            transaction.signer_id,
+                            NearBalance::from_yoctonear(signer_account_view.amount),
+                            NearBalance::from_yoctonear(spent)
+                        ),
+                    }
+                }
+                None => println!(
+                    "{:<24} unknown: the account <{}> is not found",
+                    "balance after:", transaction.signer_id
+                ),
+            }
+            let is_deploy = transaction.actions.iter().any(|action| {
+                matches!(
+                    action,
+                    near_primitives::transaction::Action::DeployContract(_)
+                )
+            });
+            if !is_deploy {
+                None
+            } else if transaction.receiver_id == transaction.signer_id {
+                signer_account_view.map(|account_view| account_view.code_hash)
+            } else {
+                fetch_account_view(&json_rpc_client, &transaction.receiver_id)
+                    .await?
+                    .map(|account_view| account_view.code_hash)
+            }
+        }
+    };
+    let risks = transaction_risks(transaction, receiver_code_hash.as_ref());
+    if risks.is_empty() {
+        println!("\nNo risky actions found.");
+    } else {
+        println!("\nWarning! Please read carefully:");
+        for risk in risks {
+            println!(" - {}", risk);
+        }
+    }
+    println!();
+    if !dialoguer::Confirm::new()
+        .with_prompt("Do you want to sign this transaction?")
+        .default(false)
+        .interact()?
+    {
+        return Err(color_eyre::Report::msg("The signing is canceled"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call_set_greeting() -> near_primitives::transaction::Transaction {
+        near_primitives::transaction::Transaction {
+            signer_id: "alice.testnet".parse().unwrap(),
+            public_key: near_crypto::PublicKey::empty(near_crypto::KeyType::ED25519),
+            nonce: 0,
+            receiver_id: "contract.testnet".parse().unwrap(),
+            block_hash: Default::default(),
+            actions: vec![near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: "set_greeting".to_owned(),
+                    args: vec![],
+                    gas: 10u64.pow(12),
+                    deposit: 0,
+                },
+            )],
+        }
+    }
+
+    #[test]
+    fn transaction_risks_flag_dangerous_actions() {
+        let mut transaction = call_set_greeting();
+        assert!(transaction_risks(&transaction, None).is_empty());
+        let code = vec![0u8; 8];
+        let existing_code_hash = near_primitives::hash::hash(b"existing contract");
+        transaction.actions = vec![
+            near_primitives::transaction::Action::AddKey(
+                near_primitives::transaction::AddKeyAction {
+                    public_key: near_crypto::PublicKey::empty(near_crypto::KeyType::ED25519),
+                    access_key: near_primitives::account::AccessKey {
+                        nonce: 0,
+                        permission: near_primitives::account::AccessKeyPermission::FullAccess,
+                    },
+                },
+            ),
+            near_primitives::transaction::Action::DeployContract(
+                near_primitives::transaction::DeployContractAction { code: code.clone() },
+            ),
+            near_primitives::transaction::Action::Stake(
+                near_primitives::transaction::StakeAction {
+                    stake: 10u128.pow(24),
+                    public_key: near_crypto::PublicKey::empty(near_crypto::KeyType::ED25519),
+                },
+            ),
+            near_primitives::transaction::Action::DeleteAccount(
+                near_primitives::transaction::DeleteAccountAction {
+                    beneficiary_id: "bob.testnet".parse().unwrap(),
+                },
+            ),
+        ];
+        let risks = transaction_risks(&transaction, Some(&existing_code_hash));
+        assert_eq!(risks.len(), 4);
+        assert!(risks[0].contains("full access key"));
+        assert!(risks[1].contains(&existing_code_hash.to_string()));
+        assert!(risks[2].contains("staked"));
+        assert!(risks[3].contains("<bob.testnet>"));
+        assert_eq!(
+            transaction_risks(&transaction, Some(&Default::default())).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn transaction_max_gas_includes_prepaid_gas_and_fees() {
+        let transaction = call_set_greeting();
+        let fees = near_primitives::runtime::fees::RuntimeFeesConfig::default();
+        let max_gas = transaction_max_gas(&transaction, &fees).unwrap();
+        assert!(max_gas > 10u64.pow(12));
+        assert_eq!(
+            transaction_totals(&transaction),
+            Some((0, 0, 10u64.pow(12)))
+        );
+    }
+
+    #[test]
+    fn overflowing_totals_are_none() {
+        let transfer = |deposit| {
+            near_primitives::transaction::Action::Transfer(
+                near_primitives::transaction::TransferAction { deposit },
+            )
+        };
+        let transaction = near_primitives::transaction::Transaction {
+            actions: vec![transfer(u128::MAX), transfer(1)],
+            ..call_set_greeting()
+        };
+        assert_eq!(transaction_totals(&transaction), None);
+        let transaction = near_primitives::transaction::Transaction {
+            actions: vec![near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: "set_greeting".to_owned(),
+                    args: vec![],
+                    gas: u64::MAX,
+                    deposit: 0,
+                },
+            )],
+            ..call_set_greeting()
+        };
+        assert_eq!(
+            transaction_max_gas(
+                &transaction,
+                &near_primitives::runtime::fees::RuntimeFeesConfig::default()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn unknown_accounts_are_told_apart_from_rpc_failures() {
+        let rpc_error = |data: &str| {
+            near_jsonrpc_primitives::errors::RpcError::new(
+                -32_000,
+                "Server error".to_owned(),
+                Some(serde_json::Value::String(data.to_owned())),
+            )
+        };
+        assert!(is_unknown_account_or_access_key(&rpc_error(
+            "account bob.testnet does not exist while viewing"
+        )));
+        assert!(!is_unknown_account_or_access_key(&rpc_error("Timeout")));
+    }
+}
@@ -0,0 +1,216 @@
+/// Prefix and version of the encoded secret shares.
+///
+/// A share is encoded as `near-sss-1:<threshold>:<index>:<hex>`, where `<threshold>` is the
+/// number of shares required to reconstruct the secret, `<index>` is the x coordinate (1..=255)
+/// and `<hex>` holds the values of the polynomials over GF(2^8) (AES polynomial `0x11b`)
+/// at `<index>`, one byte per byte of the UTF-8 encoded secret.
+const SECRET_SHARE_PREFIX: &str = "near-sss-1";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecretShare {
+    pub threshold: u8,
+    pub index: u8,
+    pub value: Vec<u8>,
+}
+
+impl std::fmt::Display for SecretShare {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            SECRET_SHARE_PREFIX,
+            self.threshold,
+            self.index,
+            hex::encode(&self.value)
+        )
+    }
+}
+
+impl std::str::FromStr for SecretShare {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split(':').collect();
+        if parts.len() != 4 || parts[0] != SECRET_SHARE_PREFIX {
+            return Err(format!(
+                "The share is expected in the format {}:<threshold>:<index>:<hex>",
+                SECRET_SHARE_PREFIX
+            ));
+        }
+        let threshold: u8 = parts[1]
+            .parse()
+            .map_err(|err| format!("Invalid threshold of the share: {}", err))?;
+        let index: u8 = parts[2]
+            .parse()
+            .map_err(|err| format!("Invalid index of the share: {}", err))?;
+        if threshold == 0 || index == 0 {
+            return Err("The threshold and the index of the share must be positive".to_owned());
+        }
+        let value =
+            hex::decode(parts[3]).map_err(|err| format!("Invalid value of the share: {}", err))?;
+        Ok(Self {
+            threshold,
+            index,
+            value,
+        })
+    }
+}
+
+fn gf256_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+fn gf256_inv(a: u8) -> u8 {
+    // a^254 = a^-1 in GF(2^8)
+    let mut result = 1u8;
+    for _ in 0..254 {
+        result = gf256_mul(result, a);
+    }
+    result
+}
+
+/// Splits the secret into `shares` shares so that any `threshold` of them reconstruct it.
+pub fn split_secret(
+    secret: &[u8],
+    threshold: u8,
+    shares: u8,
+) -> color_eyre::eyre::Result<Vec<SecretShare>> {
+    if threshold == 0 || threshold > shares {
+        return Err(color_eyre::Report::msg(format!(
+            "The threshold must be between 1 and the number of shares ({}), got {}",
+            shares, threshold
+        )));
+    }
+    let mut coefficients = vec![0u8; secret.len() * (threshold as usize - 1)];
+    rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut coefficients);
+    Ok((1..=shares)
+        .map(|index| SecretShare {
+            threshold,
+            index,
+            value: secret
+                .iter()
+                .enumerate()
+                .map(|(byte_index, secret_byte)| {
+                    let polynomial = &coefficients[byte_index * (threshold as usize - 1)
+                        ..(byte_index + 1) * (threshold as usize - 1)];
+                    // Horner's method from the highest coefficient down to the secret byte
+                    polynomial
+                        .iter()
+                        .rev()
+                        .chain(std::iter::once(secret_byte))
+                        .fold(0u8, |value, coefficient| {
+                            gf256_mul(value, index) ^ coefficient
+                        })
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+/// Reconstructs the secret from at least `threshold` shares produced by `split_secret`.
+pub fn combine_secret_shares(shares: &[SecretShare]) -> color_eyre::eyre::Result<Vec<u8>> {
+    let first_share = shares
+        .first()
+        .ok_or_else(|| color_eyre::Report::msg("No shares are provided"))?;
+    for share in shares.iter() {
+        if share.threshold != first_share.threshold || share.value.len() != first_share.value.len()
+        {
+            return Err(color_eyre::Report::msg(
+                "The shares belong to different secrets",
+            ));
+        }
+    }
+    let mut indexes: Vec<u8> = shares.iter().map(|share| share.index).collect();
+    indexes.sort_unstable();
+    indexes.dedup();
+    if indexes.len() != shares.len() {
+        return Err(color_eyre::Report::msg("The same share is provided twice"));
+    }
+    if shares.len() < first_share.threshold as usize {
+        return Err(color_eyre::Report::msg(format!(
+            "{} shares are required to reconstruct the secret, {} provided",
+            first_share.threshold,
+            shares.len()
+        )));
+    }
+    let shares = &shares[..first_share.threshold as usize];
+    // Lagrange interpolation at x = 0
+    let lagrange_coefficients: Vec<u8> = shares
+        .iter()
+        .map(|share| {
+            shares
+                .iter()
+                .filter(|other_share| other_share.index != share.index)
+                .fold(1u8, |coefficient, other_share| {
+                    gf256_mul(
+                        coefficient,
+                        gf256_mul(
+                            other_share.index,
+                            gf256_inv(other_share.index ^ share.index),
+                        ),
+                    )
+                })
+        })
+        .collect();
+    Ok((0..first_share.value.len())
+        .map(|byte_index| {
+            shares.iter().zip(lagrange_coefficients.iter()).fold(
+                0u8,
+                |secret_byte, (share, coefficient)| {
+                    secret_byte ^ gf256_mul(share.value[byte_index], *coefficient)
+                },
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn split_and_combine_secret_with_any_threshold_shares() {
+        let secret = "ed25519:3D4YudUahN1nawWogh8pAKSj92sUNMdbZGjn7kERKzYoTy8tnFQuwoGUC51DowKqorvkr2pytJSnwuSbsNVfqygr";
+        let shares = split_secret(secret.as_bytes(), 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        for selected_shares in [[0, 1, 2], [4, 2, 0], [1, 3, 4]].iter() {
+            let selected_shares: Vec<SecretShare> = selected_shares
+                .iter()
+                .map(|index| SecretShare::from_str(&shares[*index].to_string()).unwrap())
+                .collect();
+            assert_eq!(
+                combine_secret_shares(&selected_shares).unwrap(),
+                secret.as_bytes()
+            );
+        }
+        assert!(combine_secret_shares(&shares[..2]).is_err());
+    }
+
+    #[test]
+    fn secret_share_from_str_rejects_invalid_encoding() {
+        assert!(SecretShare::from_str("near-sss-1:3:0:00ff").is_err());
+        assert!(SecretShare::from_str("near-sss-2:3:1:00ff").is_err());
+        assert!(SecretShare::from_str("near-sss-1:3:1:xyz").is_err());
+        assert_eq!(
+            SecretShare::from_str("near-sss-1:3:1:00ff").unwrap(),
+            SecretShare {
+                threshold: 3,
+                index: 1,
+                value: vec![0, 255],
+            }
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use near_primitives::borsh::BorshSerialize;
+
+use super::{agent_request, read_keychain_access_keys, AgentRequest, Nep413Payload};
+
+pub type SignerFuture<'a, T> =
+    std::pin::Pin<Box<dyn std::future::Future<Output = color_eyre::eyre::Result<T>> + 'a>>;
+
+/// Signs transactions on behalf of the signer account, so every signing flow (private key,
+/// keychain, Ledger device or its emulator) goes through the same code path.
+pub trait TransactionSigner {
+    fn public_key<'a>(&'a self) -> SignerFuture<'a, near_crypto::PublicKey>;
+
+    fn sign<'a>(
+        &'a self,
+        unsigned_transaction: &'a near_primitives::transaction::Transaction,
+    ) -> SignerFuture<'a, near_crypto::Signature>;
+
+    /// Signs a NEP-413 off-chain message.
+    fn sign_message<'a>(
+        &'a self,
+        _payload: &'a Nep413Payload,
+    ) -> SignerFuture<'a, near_crypto::Signature> {
+        Box::pin(async move {
+            Err(color_eyre::Report::msg(
+                "This signer cannot sign off-chain messages",
+            ))
+        })
+    }
+}
+
+pub struct SecretKeySigner {
+    pub secret_key: near_crypto::SecretKey,
+}
+
+impl TransactionSigner for SecretKeySigner {
+    fn public_key<'a>(&'a self) -> SignerFuture<'a, near_crypto::PublicKey> {
+        Box::pin(async move { Ok(self.secret_key.public_key()) })
+    }
+
+    fn sign<'a>(
+        &'a self,
+        unsigned_transaction: &'a near_primitives::transaction::Transaction,
+    ) -> SignerFuture<'a, near_crypto::Signature> {
+        Box::pin(async move {
+            Ok(self
+                .secret_key
+                .sign(unsigned_transaction.get_hash_and_size().0.as_ref()))
+        })
+    }
+
+    fn sign_message<'a>(
+        &'a self,
+        payload: &'a Nep413Payload,
+    ) -> SignerFuture<'a, near_crypto::Signature> {
+        Box::pin(async move { Ok(self.secret_key.sign(payload.hash().as_ref())) })
+    }
+}
+
+/// Signs with the private key of the access key from the keychain: the running agent is asked
+/// first, then the key files are read.
+pub struct KeychainSigner {
+    pub dir_name: String,
+    pub account_id: near_primitives::types::AccountId,
+    pub public_key: near_crypto::PublicKey,
+}
+
+impl KeychainSigner {
+    fn read_secret_key(&self) -> color_eyre::eyre::Result<near_crypto::SecretKey> {
+        Ok(read_keychain_access_keys(&self.dir_name, &self.account_id)
+            .into_iter()
+            .map(|(_, keychain_access_key)| keychain_access_key)
+            .find(|keychain_access_key| keychain_access_key.public_key == self.public_key)
+            .ok_or_else(|| {
+                color_eyre::Report::msg(format!(
+                    "The private key for {} is not found in the keychain",
+                    self.public_key
+                ))
+            })?
+            .private_key)
+    }
+
+    /// Asks the running agent to sign if it holds the key. The returned signature is checked
+    /// against the hash the request is about.
+    fn sign_with_agent(
+        &self,
+        request: AgentRequest,
+        hash: &near_primitives::hash::CryptoHash,
+    ) -> color_eyre::eyre::Result<Option<near_crypto::Signature>> {
+        let agent_public_keys = match agent_request(&AgentRequest::ListKeys {
+            dir_name: self.dir_name.clone(),
+            account_id: self.account_id.clone(),
+        })? {
+            Some(response) => response.public_keys,
+            None => return Ok(None),
+        };
+        if !agent_public_keys.contains(&self.public_key) {
+            return Ok(None);
+        }
+        println!("Waiting for the agent to sign ...");
+        let response = agent_request(&request)?
+            .ok_or_else(|| color_eyre::Report::msg("The agent is not running anymore"))?;
+        let signature = response
+            .signature
+            .ok_or_else(|| color_eyre::Report::msg("The agent returned no signature"))?;
+        if !signature.verify(hash.as_ref(), &self.public_key) {
+            return Err(color_eyre::Report::msg(format!(
+                "The agent returned a signature that does not match the public key {}",
+                self.public_key
+            )));
+        }
+        Ok(Some(signature))
+    }
+}
+
+impl TransactionSigner for KeychainSigner {
+    fn public_key<'a>(&'a self) -> SignerFuture<'a, near_crypto::PublicKey> {
+        Box::pin(async move { Ok(self.public_key.clone()) })
+    }
+
+    fn sign<'a>(
+        &'a self,
+        unsigned_transaction: &'a near_primitives::transaction::Transaction,
+    ) -> SignerFuture<'a, near_crypto::Signature> {
+        Box::pin(async move {
+            let request = AgentRequest::Sign {
+                dir_name: self.dir_name.clone(),
+                transaction: near_primitives::serialize::to_base64(
+                    unsigned_transaction
+                        .try_to_vec()
+                        .expect("Transaction is not expected to fail on serialization"),
+                ),
+            };
+            if let Some(signature) =
+                self.sign_with_agent(request, &unsigned_transaction.get_hash_and_size().0)?
+            {
+                return Ok(signature);
+            }
+            let secret_key = self.read_secret_key()?;
+            Ok(secret_key.sign(unsigned_transaction.get_hash_and_size().0.as_ref()))
+        })
+    }
+
+    fn sign_message<'a>(
+        &'a self,
+        payload: &'a Nep413Payload,
+    ) -> SignerFuture<'a, near_crypto::Signature> {
+        Box::pin(async move {
+            let request = AgentRequest::SignMessage {
+                dir_name: self.dir_name.clone(),
+                account_id: self.account_id.clone(),
+                public_key: self.public_key.clone(),
+                payload: payload.clone(),
+            };
+            if let Some(signature) = self.sign_with_agent(request, &payload.hash())? {
+                return Ok(signature);
+            }
+            let secret_key = self.read_secret_key()?;
+            Ok(secret_key.sign(payload.hash().as_ref()))
+        })
+    }
+}
@@ -0,0 +1,260 @@
+use std::str::FromStr;
+
+use super::{transaction_totals, ConnectionConfig, NearBalance};
+
+/// Rules from the signing policy file of a network profile
+/// (`~/.near-credentials/<network>/signing-policy.json`) that every transaction has to pass
+/// before it is signed.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SigningPolicy {
+    /// The largest amount (e.g. "100 NEAR") attached to a single transaction, transfers and
+    /// function call deposits together
+    pub max_transfer_per_transaction: Option<String>,
+    /// When not empty, only these accounts may receive transactions and deleted account balances
+    pub allowed_receivers: Vec<near_primitives::types::AccountId>,
+    pub denied_receivers: Vec<near_primitives::types::AccountId>,
+    pub forbid_full_access_keys: bool,
+    pub forbid_delete_account: bool,
+    /// The signer account ID has to be typed in to confirm every transaction
+    pub require_typed_confirmation: bool,
+}
+
+impl SigningPolicy {
+    /// The policy file of the network profile. Offline there is no fallback profile: the
+    /// network has to be named, so that its rules apply on air-gapped signers as well.
+    pub fn path(connection_config: &ConnectionConfig) -> std::path::PathBuf {
+        let mut path = dirs::home_dir().expect("Impossible to get your home dir!");
+        path.push(connection_config.dir_name());
+        path.push(crate::consts::SIGNING_POLICY_FILE_NAME);
+        path
+    }
+
+    /// Reads the policy file; there is no policy when the file does not exist.
+    pub fn load(path: &std::path::Path) -> color_eyre::eyre::Result<Option<Self>> {
+        let data = match std::fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(color_eyre::Report::msg(format!(
+                    "Failed to read the signing policy {}: {}",
+                    path.display(),
+                    err
+                )))
+            }
+        };
+        let policy: Self = serde_json::from_str(&data).map_err(|err| {
+            color_eyre::Report::msg(format!(
+                "The signing policy {} is invalid: {}",
+                path.display(),
+                err
+            ))
+        })?;
+        if let Some(max_transfer) = &policy.max_transfer_per_transaction {
+            NearBalance::from_str(max_transfer).map_err(|err| {
+                color_eyre::Report::msg(format!(
+                    "The signing policy {} is invalid: max_transfer_per_transaction: {}",
+                    path.display(),
+                    err
+                ))
+            })?;
+        }
+        Ok(Some(policy))
+    }
+
+    /// Returns a plain-language explanation for every rule the transaction breaks.
+    pub fn violations(
+        &self,
+        transaction: &near_primitives::transaction::Transaction,
+    ) -> Vec<String> {
+        let mut violations = vec![];
+        let mut receivers = vec![&transaction.receiver_id];
+        for action in transaction.actions.iter() {
+            match action {
+                near_primitives::transaction::Action::AddKey(add_key_action)
+                    if self.forbid_full_access_keys =>
+                {
+                    if let near_primitives::account::AccessKeyPermission::FullAccess =
+                        add_key_action.access_key.permission
+                    {
+                        violations.push(format!(
+                            "adding the full access key {} to <{}> is forbidden",
+                            add_key_action.public_key, transaction.receiver_id
+                        ));
+                    }
+                }
+                near_primitives::transaction::Action::DeleteAccount(delete_account_action) => {
+                    if self.forbid_delete_account {
+                        violations.push(format!(
+                            "deleting the account <{}> is forbidden",
+                            transaction.receiver_id
+                        ));
+                    }
+                    receivers.push(&delete_account_action.beneficiary_id);
+                }
+                _ => {}
+            }
+        }
+        for receiver_id in receivers {
+            if self.denied_receivers.contains(receiver_id) {
+                violations.push(format!("<{}> is a denied receiver", receiver_id));
+            } else if !self.allowed_receivers.is_empty()
+                && !self.allowed_receivers.contains(receiver_id)
+            {
+                violations.push(format!(
+                    "<{}> is not one of the allowed receivers ({})",
+                    receiver_id,
+                    self.allowed_receivers
+                        .iter()
+                        .map(|account_id| account_id.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        }
+        if let Some(max_transfer) = self
+            .max_transfer_per_transaction
+            .as_ref()
+            .and_then(|max_transfer| NearBalance::from_str(max_transfer).ok())
+        {
+            match transaction_totals(transaction) {
+                Some((total_deposit, _, _)) if total_deposit > max_transfer.to_yoctonear() => {
+                    violations.push(format!(
+                        "the transaction sends {}, more than the {} allowed per transaction",
+                        NearBalance::from_yoctonear(total_deposit),
+                        max_transfer
+                    ))
+                }
+                Some(_) => {}
+                None => violations.push(
+                    "the deposits of the transaction add up to more than fits in a balance"
+                        .to_owned(),
+                ),
+            }
+        }
+        violations
+    }
+}
+
+/// Asks for the network of a transaction signed offline, whose signing policy it has to pass.
+pub fn input_signing_policy_network() -> String {
+    dialoguer::Input::new()
+        .with_prompt(
+            "What network is the transaction for (testnet, mainnet or betanet)? Its signing policy is applied",
+        )
+        .interact_text()
+        .unwrap()
+}
+
+/// Refuses the transaction if it breaks the signing policy of the network profile, and asks to
+/// type the signer account ID when the policy requires it.
+pub fn enforce_signing_policy(
+    transaction: &near_primitives::transaction::Transaction,
+    connection_config: &ConnectionConfig,
+) -> crate::CliResult {
+    let path = SigningPolicy::path(connection_config);
+    let policy = match SigningPolicy::load(&path)? {
+        Some(policy) => policy,
+        None => return Ok(()),
+    };
+    let violations = policy.violations(transaction);
+    if !violations.is_empty() {
+        return Err(color_eyre::Report::msg(format!(
+            "The transaction is refused by the signing policy {}:\n - {}",
+            path.display(),
+            violations.join("\n - ")
+        )));
+    }
+    if policy.require_typed_confirmation {
+        let typed_account_id: String = dialoguer::Input::new()
+            .with_prompt(format!(
+                "The signing policy requires a confirmation. Type the signer account ID <{}> to sign",
+                transaction.signer_id
+            ))
+            .allow_empty(true)
+            .interact_text()?;
+        if typed_account_id.trim() != transaction.signer_id.to_string() {
+            return Err(color_eyre::Report::msg(format!(
+                "The transaction is refused by the signing policy {}: the typed account ID does not match <{}>",
+                path.display(),
+                transaction.signer_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Fails when the signing policy of the network profile requires a typed confirmation, which
+/// the batch commands that sign without prompts cannot ask for.
+pub fn check_signing_policy_without_prompts(
+    connection_config: &ConnectionConfig,
+) -> crate::CliResult {
+    let path = SigningPolicy::path(connection_config);
+    match SigningPolicy::load(&path)? {
+        Some(policy) if policy.require_typed_confirmation => Err(color_eyre::Report::msg(format!(
+            "The signing policy {} requires a typed confirmation of every transaction, \
+                which cannot be asked for while the transactions are signed without prompts",
+            path.display()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction_with_deposit(
+        deposit: near_primitives::types::Balance,
+    ) -> near_primitives::transaction::Transaction {
+        near_primitives::transaction::Transaction {
+            signer_id: "alice.testnet".parse().unwrap(),
+            public_key: near_crypto::PublicKey::empty(near_crypto::KeyType::ED25519),
+            nonce: 0,
+            receiver_id: "contract.testnet".parse().unwrap(),
+            block_hash: Default::default(),
+            actions: vec![near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: "set_greeting".to_owned(),
+                    args: vec![],
+                    gas: 10u64.pow(12),
+                    deposit,
+                },
+            )],
+        }
+    }
+
+    #[test]
+    fn signing_policy_reports_every_violation() {
+        let policy: SigningPolicy = serde_json::from_str(
+            r#"{
+                "max_transfer_per_transaction": "1 NEAR",
+                "denied_receivers": ["contract.testnet"],
+                "forbid_full_access_keys": true
+            }"#,
+        )
+        .unwrap();
+        assert!(policy
+            .violations(&transaction_with_deposit(0))
+            .iter()
+            .any(|violation| violation.contains("denied receiver")));
+        let policy = SigningPolicy {
+            allowed_receivers: vec!["contract.testnet".parse().unwrap()],
+            denied_receivers: vec![],
+            forbid_delete_account: true,
+            ..policy
+        };
+        assert!(policy
+            .violations(&transaction_with_deposit(10u128.pow(24)))
+            .is_empty());
+        let mut transaction = transaction_with_deposit(2 * 10u128.pow(24));
+        transaction
+            .actions
+            .push(near_primitives::transaction::Action::DeleteAccount(
+                near_primitives::transaction::DeleteAccountAction {
+                    beneficiary_id: "bob.testnet".parse().unwrap(),
+                },
+            ));
+        assert_eq!(policy.violations(&transaction).len(), 3);
+    }
+}
@@ -0,0 +1,440 @@
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use super::{verify_transaction_signature, BlockHashAsBase58};
+
+/// Stable JSON representation of a transaction (signed or not) meant to be reviewed and diffed.
+/// Amounts are yoctoNEAR strings; function call arguments are kept as JSON when they are
+/// canonical JSON and as base64 otherwise, so the conversion back to Borsh is lossless.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TransactionJson {
+    pub signer_id: near_primitives::types::AccountId,
+    pub public_key: String,
+    pub nonce: u64,
+    pub receiver_id: near_primitives::types::AccountId,
+    pub block_hash: String,
+    pub actions: Vec<ActionJson>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Informational; a mismatch with the rebuilt transaction, e.g. after editing, is only reported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ActionJson {
+    CreateAccount,
+    DeployContract {
+        code_base64: String,
+        code_hash: String,
+    },
+    FunctionCall {
+        method_name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        args_json: Option<serde_json::Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        args_base64: Option<String>,
+        gas: u64,
+        deposit: String,
+    },
+    Transfer {
+        deposit: String,
+    },
+    Stake {
+        stake: String,
+        public_key: String,
+    },
+    AddKey {
+        public_key: String,
+        nonce: u64,
+        permission: AccessKeyPermissionJson,
+    },
+    DeleteKey {
+        public_key: String,
+    },
+    DeleteAccount {
+        beneficiary_id: near_primitives::types::AccountId,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AccessKeyPermissionJson {
+    FullAccess,
+    FunctionCall {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        allowance: Option<String>,
+        receiver_id: near_primitives::types::AccountId,
+        method_names: Vec<String>,
+    },
+}
+
+fn parse_json_field<T: std::str::FromStr>(name: &str, value: &str) -> color_eyre::eyre::Result<T>
+where
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|err| color_eyre::Report::msg(format!("Invalid {} <{}>: {}", name, value, err)))
+}
+
+impl TryFrom<&near_primitives::transaction::Action> for ActionJson {
+    type Error = color_eyre::Report;
+
+    fn try_from(action: &near_primitives::transaction::Action) -> Result<Self, Self::Error> {
+        Ok(match action {
+            near_primitives::transaction::Action::CreateAccount(_) => Self::CreateAccount,
+            near_primitives::transaction::Action::DeployContract(deploy_contract_action) => {
+                Self::DeployContract {
+                    code_base64: near_primitives::serialize::to_base64(
+                        &deploy_contract_action.code,
+                    ),
+                    code_hash: near_primitives::hash::hash(&deploy_contract_action.code)
+                        .to_string(),
+                }
+            }
+            near_primitives::transaction::Action::FunctionCall(function_call_action) => {
+                let args_json =
+                    serde_json::from_slice::<serde_json::Value>(&function_call_action.args)
+                        .ok()
+                        .filter(|args| {
+                            serde_json::to_vec(args).ok().as_ref()
+                                == Some(&function_call_action.args)
+                        });
+                Self::FunctionCall {
+                    method_name: function_call_action.method_name.clone(),
+                    args_base64: match args_json {
+                        Some(_) => None,
+                        None => Some(near_primitives::serialize::to_base64(
+                            &function_call_action.args,
+                        )),
+                    },
+                    args_json,
+                    gas: function_call_action.gas,
+                    deposit: function_call_action.deposit.to_string(),
+                }
+            }
+            near_primitives::transaction::Action::Transfer(transfer_action) => Self::Transfer {
+                deposit: transfer_action.deposit.to_string(),
+            },
+            near_primitives::transaction::Action::Stake(stake_action) => Self::Stake {
+                stake: stake_action.stake.to_string(),
+                public_key: stake_action.public_key.to_string(),
+            },
+            near_primitives::transaction::Action::AddKey(add_key_action) => Self::AddKey {
+                public_key: add_key_action.public_key.to_string(),
+                nonce: add_key_action.access_key.nonce,
+                permission: match &add_key_action.access_key.permission {
+                    near_primitives::account::AccessKeyPermission::FullAccess => {
+                        AccessKeyPermissionJson::FullAccess
+                    }
+                    near_primitives::account::AccessKeyPermission::FunctionCall(permission) => {
+                        AccessKeyPermissionJson::FunctionCall {
+                            allowance: permission.allowance.map(|allowance| allowance.to_string()),
+                            receiver_id: parse_json_field("receiver id", &permission.receiver_id)?,
+                            method_names: permission.method_names.clone(),
+                        }
+                    }
+                },
+            },
+            near_primitives::transaction::Action::DeleteKey(delete_key_action) => Self::DeleteKey {
+                public_key: delete_key_action.public_key.to_string(),
+            },
+            near_primitives::transaction::Action::DeleteAccount(delete_account_action) => {
+                Self::DeleteAccount {
+                    beneficiary_id: delete_account_action.beneficiary_id.clone(),
+                }
+            }
+        })
+    }
+}
+
+impl ActionJson {
+    pub fn to_action(&self) -> color_eyre::eyre::Result<near_primitives::transaction::Action> {
+        Ok(match self {
+            Self::CreateAccount => near_primitives::transaction::Action::CreateAccount(
+                near_primitives::transaction::CreateAccountAction {},
+            ),
+            Self::DeployContract {
+                code_base64,
+                code_hash,
+            } => {
+                let code = near_primitives::serialize::from_base64(code_base64).map_err(|err| {
+                    color_eyre::Report::msg(format!("Invalid contract code: {}", err))
+                })?;
+                let actual_code_hash = near_primitives::hash::hash(&code).to_string();
+                if &actual_code_hash != code_hash {
+                    return Err(color_eyre::Report::msg(format!(
+                        "The contract code hash is {}, but {} is specified",
+                        actual_code_hash, code_hash
+                    )));
+                }
+                near_primitives::transaction::Action::DeployContract(
+                    near_primitives::transaction::DeployContractAction { code },
+                )
+            }
+            Self::FunctionCall {
+                method_name,
+                args_json,
+                args_base64,
+                gas,
+                deposit,
+            } => near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: method_name.clone(),
+                    args: match (args_json, args_base64) {
+                        (Some(args_json), None) => serde_json::to_vec(args_json)?,
+                        (None, Some(args_base64)) => {
+                            near_primitives::serialize::from_base64(args_base64).map_err(|err| {
+                                color_eyre::Report::msg(format!("Invalid args_base64: {}", err))
+                            })?
+                        }
+                        (None, None) => vec![],
+                        (Some(_), Some(_)) => {
+                            return Err(color_eyre::Report::msg(format!(
+                                "The function call <{}> has both args_json and args_base64",
+                                method_name
+                            )))
+                        }
+                    },
+                    gas: *gas,
+                    deposit: parse_json_field("deposit", deposit)?,
+                },
+            ),
+            Self::Transfer { deposit } => near_primitives::transaction::Action::Transfer(
+                near_primitives::transaction::TransferAction {
+                    deposit: parse_json_field("deposit", deposit)?,
+                },
+            ),
+            Self::Stake { stake, public_key } => near_primitives::transaction::Action::Stake(
+                near_primitives::transaction::StakeAction {
+                    stake: parse_json_field("stake", stake)?,
+                    public_key: parse_json_field("public key", public_key)?,
+                },
+            ),
+            Self::AddKey {
+                public_key,
+                nonce,
+                permission,
+            } => near_primitives::transaction::Action::AddKey(
+                near_primitives::transaction::AddKeyAction {
+                    public_key: parse_json_field("public key", public_key)?,
+                    access_key: near_primitives::account::AccessKey {
+                        nonce: *nonce,
+                        permission: match permission {
+                            AccessKeyPermissionJson::FullAccess => {
+                                near_primitives::account::AccessKeyPermission::FullAccess
+                            }
+                            AccessKeyPermissionJson::FunctionCall {
+                                allowance,
+                                receiver_id,
+                                method_names,
+                            } => near_primitives::account::AccessKeyPermission::FunctionCall(
+                                near_primitives::account::FunctionCallPermission {
+                                    allowance: match allowance {
+                                        Some(allowance) => {
+                                            Some(parse_json_field("allowance", allowance)?)
+                                        }
+                                        None => None,
+                                    },
+                                    receiver_id: receiver_id.to_string(),
+                                    method_names: method_names.clone(),
+                                },
+                            ),
+                        },
+                    },
+                },
+            ),
+            Self::DeleteKey { public_key } => near_primitives::transaction::Action::DeleteKey(
+                near_primitives::transaction::DeleteKeyAction {
+                    public_key: parse_json_field("public key", public_key)?,
+                },
+            ),
+            Self::DeleteAccount { beneficiary_id } => {
+                near_primitives::transaction::Action::DeleteAccount(
+                    near_primitives::transaction::DeleteAccountAction {
+                        beneficiary_id: beneficiary_id.clone(),
+                    },
+                )
+            }
+        })
+    }
+}
+
+impl TryFrom<&near_primitives::transaction::Transaction> for TransactionJson {
+    type Error = color_eyre::Report;
+
+    fn try_from(
+        transaction: &near_primitives::transaction::Transaction,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            signer_id: transaction.signer_id.clone(),
+            public_key: transaction.public_key.to_string(),
+            nonce: transaction.nonce,
+            receiver_id: transaction.receiver_id.clone(),
+            block_hash: transaction.block_hash.to_string(),
+            actions: transaction
+                .actions
+                .iter()
+                .map(ActionJson::try_from)
+                .collect::<Result<_, _>>()?,
+            signature: None,
+            hash: Some(transaction.get_hash_and_size().0.to_string()),
+        })
+    }
+}
+
+impl TryFrom<&near_primitives::transaction::SignedTransaction> for TransactionJson {
+    type Error = color_eyre::Report;
+
+    fn try_from(
+        signed_transaction: &near_primitives::transaction::SignedTransaction,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            signature: Some(signed_transaction.signature.to_string()),
+            ..Self::try_from(&signed_transaction.transaction)?
+        })
+    }
+}
+
+impl TransactionJson {
+    pub fn to_transaction(
+        &self,
+    ) -> color_eyre::eyre::Result<near_primitives::transaction::Transaction> {
+        let transaction = near_primitives::transaction::Transaction {
+            signer_id: self.signer_id.clone(),
+            public_key: parse_json_field("public key", &self.public_key)?,
+            nonce: self.nonce,
+            receiver_id: self.receiver_id.clone(),
+            block_hash: BlockHashAsBase58::from_str(&self.block_hash)
+                .map_err(color_eyre::Report::msg)?
+                .inner,
+            actions: self
+                .actions
+                .iter()
+                .map(ActionJson::to_action)
+                .collect::<color_eyre::eyre::Result<_>>()?,
+        };
+        if let Some(hash) = &self.hash {
+            let actual_hash = transaction.get_hash_and_size().0.to_string();
+            if &actual_hash != hash {
+                eprintln!(
+                    "Warning: the transaction was edited, its hash is {} instead of {}",
+                    actual_hash, hash
+                );
+            }
+        }
+        Ok(transaction)
+    }
+
+    /// Returns the signed transaction when the JSON carries a signature.
+    pub fn to_signed_transaction(
+        &self,
+    ) -> color_eyre::eyre::Result<Option<near_primitives::transaction::SignedTransaction>> {
+        let transaction = self.to_transaction()?;
+        match &self.signature {
+            Some(signature) => {
+                let signature: near_crypto::Signature = parse_json_field("signature", signature)?;
+                verify_transaction_signature(&transaction, &signature)?;
+                Ok(Some(near_primitives::transaction::SignedTransaction::new(
+                    signature,
+                    transaction,
+                )))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_primitives::borsh::BorshSerialize;
+
+    #[test]
+    fn transaction_json_round_trip_is_lossless() {
+        let mut transaction = near_primitives::transaction::Transaction {
+            signer_id: "alice.testnet".parse().unwrap(),
+            public_key: near_crypto::PublicKey::empty(near_crypto::KeyType::ED25519),
+            nonce: 0,
+            receiver_id: "contract.testnet".parse().unwrap(),
+            block_hash: Default::default(),
+            actions: vec![near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: "set_greeting".to_owned(),
+                    args: vec![],
+                    gas: 10u64.pow(12),
+                    deposit: 10u128.pow(24),
+                },
+            )],
+        };
+        transaction.actions.extend(vec![
+            near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: "set_greeting".to_owned(),
+                    args: br#"{"greeting":"hi"}"#.to_vec(),
+                    gas: 10u64.pow(12),
+                    deposit: 0,
+                },
+            ),
+            near_primitives::transaction::Action::FunctionCall(
+                near_primitives::transaction::FunctionCallAction {
+                    method_name: "set_greeting".to_owned(),
+                    args: br#"{ "greeting": "hi" }"#.to_vec(),
+                    gas: 10u64.pow(12),
+                    deposit: 0,
+                },
+            ),
+            near_primitives::transaction::Action::AddKey(
+                near_primitives::transaction::AddKeyAction {
+                    public_key: near_crypto::PublicKey::empty(near_crypto::KeyType::ED25519),
+                    access_key: near_primitives::account::AccessKey {
+                        nonce: 0,
+                        permission: near_primitives::account::AccessKeyPermission::FunctionCall(
+                            near_primitives::account::FunctionCallPermission {
+                                allowance: Some(250000000000000000000000),
+                                receiver_id: "contract.testnet".to_owned(),
+                                method_names: vec!["set_greeting".to_owned()],
+                            },
+                        ),
+                    },
+                },
+            ),
+        ]);
+        let transaction_json: TransactionJson = serde_json::from_str(
+            &serde_json::to_string(&TransactionJson::try_from(&transaction).unwrap()).unwrap(),
+        )
+        .unwrap();
+        match &transaction_json.actions[1] {
+            ActionJson::FunctionCall {
+                args_json,
+                args_base64,
+                ..
+            } => {
+                assert_eq!(args_json, &Some(serde_json::json!({"greeting": "hi"})));
+                assert_eq!(args_base64, &None);
+            }
+            action => panic!("Unexpected action {:?}", action),
+        }
+        match &transaction_json.actions[2] {
+            ActionJson::FunctionCall { args_base64, .. } => assert!(args_base64.is_some()),
+            action => panic!("Unexpected action {:?}", action),
+        }
+        assert_eq!(
+            transaction_json
+                .to_transaction()
+                .unwrap()
+                .try_to_vec()
+                .unwrap(),
+            transaction.try_to_vec().unwrap()
+        );
+        assert_eq!(transaction_json.to_signed_transaction().unwrap(), None);
+
+        let edited_json = TransactionJson {
+            nonce: 1,
+            ..transaction_json
+        };
+        assert_eq!(edited_json.to_transaction().unwrap().nonce, 1);
+    }
+}
@@ -19,3 +19,15 @@ pub const DIR_NAME_TESTNET: &str = ".near-credentials/testnet/";
 pub const DIR_NAME_MAINNET: &str = ".near-credentials/mainnet/";
 pub const DIR_NAME_BETANET: &str = ".near-credentials/betanet/";
 pub const DIR_NAME_CUSTOM: &str = ".near-credentials/default/";
+
+pub const AGENT_SOCKET_ENV_VAR: &str = "NEAR_CLI_AGENT_SOCK";
+pub const AGENT_SOCKET_FILE_NAME: &str = ".near-credentials/agent.sock";
+
+#[cfg(feature = "ledger-emulator")]
+pub const LEDGER_EMULATOR_SEED_PHRASE_ENV_VAR: &str = "NEAR_CLI_LEDGER_EMULATOR_SEED_PHRASE";
+#[cfg(feature = "ledger-emulator")]
+pub const LEDGER_EMULATOR_REJECT_ENV_VAR: &str = "NEAR_CLI_LEDGER_EMULATOR_REJECT";
+
+pub const NONCE_STORE_DIR_NAME: &str = ".nonces";
+
+pub const SIGNING_POLICY_FILE_NAME: &str = "signing-policy.json";